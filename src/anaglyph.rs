@@ -0,0 +1,131 @@
+// Red/cyan anaglyph: the smooth escape count is a height field, and each row is projected
+// once per eye with the height shifting points sideways, the left eye's view going into
+// the red channel and the right eye's into green and blue. High counts and the interior
+// stand out of the screen; the far exterior lies on it.
+use crate::compositor::{Cell, Layer};
+use crate::layout::{Pane, Rect};
+use num_complex::Complex64;
+use std::f64::consts::TAU;
+
+// Samples per cell across, so shifts finer than a cell still move edges
+const SUB: usize = 3;
+// Separation of the two views at full height, in cells
+const MAX_SHIFT: f64 = 3.0;
+// Contour bands per unit of height: flat shading gives the eyes nothing to fuse
+const BANDS: f64 = 7.0;
+
+// Height 0..=1 from a smooth escape count, log-scaled so the boundary's steep rise
+// doesn't flatten the rest of the exterior
+pub fn height(smooth: f64, max_iters: usize) -> f64 {
+    ((1.0 + smooth).ln() / (1.0 + max_iters as f64).ln()).clamp(0.0, 1.0)
+}
+
+// Brightness 0..=1 of a point: contour bands on the exterior, a dim plateau inside
+fn shade(h: f64, inside: bool) -> f64 {
+    if inside {
+        0.3
+    } else {
+        0.3 + 0.7 * (0.5 + 0.5 * (h * BANDS * TAU).cos())
+    }
+}
+
+// One eye's view of a row of `heights` (with their `shades`), each moved `shift` × height
+// samples to the right (negative: left). Nearer points hide farther ones that land on the
+// same sample, and the gaps they open behind them are filled from the ground beside.
+pub fn project(heights: &[f64], shades: &[f64], shift: f64) -> Vec<f64> {
+    let n = heights.len();
+    let mut out: Vec<Option<f64>> = vec![None; n];
+    let mut depth = vec![f64::NEG_INFINITY; n];
+    for (x, (&h, &s)) in heights.iter().zip(shades).enumerate() {
+        let to = (x as f64 + shift * h).round();
+        if to < 0.0 || to >= n as f64 {
+            continue;
+        }
+        let to = to as usize;
+        if h > depth[to] {
+            depth[to] = h;
+            out[to] = Some(s);
+        }
+    }
+    // Points moving right uncover ground on their left, and the other way round
+    if shift < 0.0 {
+        out.reverse();
+    }
+    let mut last = out.iter().flatten().next().copied().unwrap_or(0.0);
+    let mut row: Vec<f64> = out
+        .into_iter()
+        .map(|s| {
+            last = s.unwrap_or(last);
+            last
+        })
+        .collect();
+    if shift < 0.0 {
+        row.reverse();
+    }
+    row
+}
+
+pub fn draw(layer: &mut Layer, rect: Rect, pane: &Pane, c: Complex64, max_iters: usize) {
+    let (w, h) = (rect.w as usize, rect.h as usize);
+    let c = pane.param(c);
+    // Each eye sees half the separation, in opposite directions
+    let shift = MAX_SHIFT * SUB as f64 / 2.0;
+    for y in 0..h {
+        let mut heights = Vec::with_capacity(w * SUB);
+        let mut shades = Vec::with_capacity(w * SUB);
+        for sx in 0..w * SUB {
+            let fx = (sx as f64 + 0.5) / SUB as f64;
+            let p = pane.pixel(fx, (y as f64 + 0.5) * 2.0, w, h * 2);
+            let n = pane.fractal.escape_smooth(p, c, max_iters);
+            let height = height(n, max_iters);
+            heights.push(height);
+            shades.push(shade(height, n >= max_iters as f64));
+        }
+        let left = project(&heights, &shades, shift);
+        let right = project(&heights, &shades, -shift);
+        for x in 0..w {
+            let avg = |v: &[f64]| v[x * SUB..(x + 1) * SUB].iter().sum::<f64>() / SUB as f64;
+            let (l, r) = ((avg(&left) * 255.0) as u8, (avg(&right) * 255.0) as u8);
+            layer.put(
+                rect.x + x as u16,
+                rect.y + y as u16,
+                Cell::rgb('█', (l, r, r)),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn raised_points_shift_and_occlude() {
+        // A raised block in the middle of a flat row
+        let heights: Vec<f64> = (0..20)
+            .map(|x| if (8..12).contains(&x) { 1.0 } else { 0.0 })
+            .collect();
+        let shades: Vec<f64> = heights
+            .iter()
+            .map(|&h| if h > 0.0 { 1.0 } else { 0.0 })
+            .collect();
+        let left = project(&heights, &shades, 2.0);
+        let lit: Vec<usize> = (0..20).filter(|&x| left[x] == 1.0).collect();
+        assert_eq!(lit, [10, 11, 12, 13]);
+        // The uncovered gap takes the shade of the ground beside it
+        assert_eq!(left[8], 0.0);
+        let right = project(&heights, &shades, -2.0);
+        assert_eq!(
+            (0..20).filter(|&x| right[x] == 1.0).collect::<Vec<_>>(),
+            [6, 7, 8, 9]
+        );
+        assert_eq!(right[11], 0.0);
+        // Flat rows are the same for both eyes
+        assert_eq!(project(&[0.0; 5], &[0.5; 5], 2.0), vec![0.5; 5]);
+    }
+    #[test]
+    fn height_spans_unit_range() {
+        assert_eq!(height(0.0, 100), 0.0);
+        assert_eq!(height(100.0, 100), 1.0);
+        assert!(height(10.0, 100) > 0.4);
+    }
+}
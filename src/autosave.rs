@@ -0,0 +1,209 @@
+// Crash-safe autosave: every few seconds the session's view goes to a small file in the
+// temp directory, one per process, in the shared-session line format. A clean exit removes
+// it, so a file whose process is gone means that run was killed or panicked, and the next
+// start offers to bring its view back.
+use crate::share::State;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+// How often the view is saved, if it changed
+pub const INTERVAL: Duration = Duration::from_secs(5);
+const PREFIX: &str = "terminal-fractal-";
+const EXT: &str = "autosave";
+
+pub struct Autosave {
+    path: PathBuf,
+    // Line on disk and when it was written
+    saved: String,
+    at: Option<Instant>,
+}
+
+// View left behind by a run that didn't exit cleanly
+#[derive(Debug, PartialEq)]
+pub struct Crashed {
+    pub state: State,
+    // Time since it was saved
+    pub age: Duration,
+}
+
+// Files are per user, as the temp directory is often shared
+fn user() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_else(|_| "user".to_string())
+}
+
+fn file_name(user: &str, pid: u32) -> String {
+    format!("{PREFIX}{user}-{pid}.{EXT}")
+}
+
+#[cfg(unix)]
+fn alive(pid: u32) -> bool {
+    // Signal 0 only checks; EPERM still means the process exists
+    // SAFETY: kill with signal 0 sends nothing and only reads the pid; no memory involved
+    let r = unsafe { libc::kill(pid as libc::pid_t, 0) };
+    r == 0 || io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(not(unix))]
+fn alive(pid: u32) -> bool {
+    pid == std::process::id()
+}
+
+impl Autosave {
+    pub fn new() -> Self {
+        Autosave::in_dir(&std::env::temp_dir(), std::process::id())
+    }
+
+    fn in_dir(dir: &Path, pid: u32) -> Self {
+        Autosave {
+            path: dir.join(file_name(&user(), pid)),
+            saved: String::new(),
+            at: None,
+        }
+    }
+
+    // Write `state` if INTERVAL has passed and it changed since the last save
+    pub fn update(&mut self, now: Instant, state: &State) -> io::Result<()> {
+        if self.at.is_some_and(|at| now.duration_since(at) < INTERVAL) {
+            return Ok(());
+        }
+        self.at = Some(now);
+        let line = state.encode();
+        if line == self.saved {
+            return Ok(());
+        }
+        // Renamed into place, so a crash mid-write leaves the previous save intact. The temp
+        // directory is shared and the name predictable: the file must be a new one of ours,
+        // never something planted there (a symlink would have the write land elsewhere).
+        let part = self.path.with_extension("part");
+        let _ = std::fs::remove_file(&part);
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        options
+            .open(&part)?
+            .write_all(format!("{line}\n").as_bytes())?;
+        std::fs::rename(&part, &self.path)?;
+        self.saved = line;
+        Ok(())
+    }
+
+    // Clean exit, or saving stopped: nothing to restore next time
+    pub fn finish(self) {
+        let _ = std::fs::remove_file(&self.path);
+        let _ = std::fs::remove_file(self.path.with_extension("part"));
+    }
+}
+
+// The newest save of a run that is no longer running. All such files are removed, as they
+// are offered only once.
+pub fn find_crashed() -> Option<Crashed> {
+    find_in(&std::env::temp_dir(), &user(), alive)
+}
+
+fn find_in(dir: &Path, user: &str, alive: impl Fn(u32) -> bool) -> Option<Crashed> {
+    let prefix = format!("{PREFIX}{user}-");
+    let mut newest: Option<(SystemTime, State)> = None;
+    for entry in std::fs::read_dir(dir).ok()?.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let Some(pid) = name
+            .strip_prefix(&prefix)
+            .and_then(|n| n.strip_suffix(&format!(".{EXT}")))
+            .and_then(|p| p.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        if alive(pid) {
+            continue;
+        }
+        let path = entry.path();
+        let modified = entry.metadata().and_then(|m| m.modified());
+        let state = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|t| State::decode(t.trim()).ok());
+        let _ = std::fs::remove_file(&path);
+        if let (Ok(at), Some(state)) = (modified, state)
+            && newest.as_ref().is_none_or(|(t, _)| at > *t)
+        {
+            newest = Some((at, state));
+        }
+    }
+    let (at, state) = newest?;
+    let age = SystemTime::now().duration_since(at).unwrap_or_default();
+    Some(Crashed { state, age })
+}
+
+// "40 s", "12 min" or "3 h"
+pub fn format_age(age: Duration) -> String {
+    match age.as_secs() {
+        s if s < 120 => format!("{s} s"),
+        s if s < 2 * 3600 => format!("{} min", s / 60),
+        s => format!("{} h", s / 3600),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fractal::Fractal;
+    use crate::layout::{Layout, Pane};
+    use num_complex::Complex64;
+    fn state(zoom: f64) -> State {
+        let mut pane = Pane::new(Fractal::Mandelbrot);
+        pane.view.zoom = zoom;
+        State {
+            c: Complex64::new(-0.8, 0.156),
+            max_iters: 500,
+            layout: Layout::Single,
+            panes: [pane; 2],
+        }
+    }
+    #[test]
+    fn offers_the_save_of_a_dead_run_once() {
+        let dir = std::env::temp_dir().join(format!("tf-autosave-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let t0 = Instant::now();
+        let mut crashed = Autosave::in_dir(&dir, 11);
+        crashed.update(t0, &state(1e9)).unwrap();
+        // Within the interval nothing is written
+        crashed
+            .update(t0 + Duration::from_secs(1), &state(2e9))
+            .unwrap();
+        let mut running = Autosave::in_dir(&dir, 22);
+        running.update(t0, &state(3.0)).unwrap();
+        // A leftover temp file, or one planted there, is replaced rather than written through
+        #[cfg(unix)]
+        {
+            let target = dir.join("target");
+            std::fs::write(&target, "keep").unwrap();
+            let part = running.path.with_extension("part");
+            std::os::unix::fs::symlink(&target, &part).unwrap();
+            running
+                .update(t0 + Duration::from_secs(10), &state(4.0))
+                .unwrap();
+            assert_eq!(std::fs::read_to_string(&target).unwrap(), "keep");
+            std::fs::remove_file(&target).unwrap();
+        }
+        let alive = |pid| pid == 22;
+        let found = find_in(&dir, &user(), alive).unwrap();
+        assert_eq!(found.state.panes[0].view.zoom, 1e9);
+        assert!(find_in(&dir, &user(), alive).is_none());
+        // A clean exit leaves nothing behind
+        running.finish();
+        assert!(find_in(&dir, &user(), |_| false).is_none());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+    #[test]
+    fn formats_ages() {
+        assert_eq!(format_age(Duration::from_secs(40)), "40 s");
+        assert_eq!(format_age(Duration::from_secs(750)), "12 min");
+        assert_eq!(format_age(Duration::from_secs(4 * 3600)), "4 h");
+    }
+}
@@ -0,0 +1,212 @@
+// Terminal capabilities: color count from the terminfo database, plus an optional live probe
+// (DECRQSS for truecolor, DA1 for sixel, the kitty graphics query, OSC 11 for the background
+// color) answered within a timeout
+use std::path::PathBuf;
+use std::time::Duration;
+#[cfg(unix)]
+use std::time::Instant;
+
+// What the terminal itself answered; all false/None when it stayed silent
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Probe {
+    pub truecolor: bool,
+    pub sixel: bool,
+    pub kitty: bool,
+    // Background color, 8 bits per channel
+    pub background: Option<(u8, u8, u8)>,
+}
+
+// `max_colors` of TERM's compiled terminfo entry; Some(0) when the entry has no colors
+pub fn terminfo_colors(term: &str) -> Option<i32> {
+    let first = term.chars().next()?;
+    if term.contains(['/', '\\']) || term.starts_with('.') {
+        return None;
+    }
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    if let Some(d) = std::env::var_os("TERMINFO") {
+        dirs.push(d.into());
+    }
+    if let Some(home) = std::env::var_os("HOME") {
+        dirs.push(PathBuf::from(home).join(".terminfo"));
+    }
+    if let Some(list) = std::env::var_os("TERMINFO_DIRS") {
+        dirs.extend(std::env::split_paths(&list).filter(|p| !p.as_os_str().is_empty()));
+    }
+    for d in [
+        "/etc/terminfo",
+        "/lib/terminfo",
+        "/usr/share/terminfo",
+        "/usr/lib/terminfo",
+    ] {
+        dirs.push(d.into());
+    }
+    // Entries live under their first letter, or its hex code on macOS
+    let subdirs = [first.to_string(), format!("{:x}", first as u32)];
+    dirs.iter()
+        .flat_map(|d| subdirs.iter().map(move |s| d.join(s).join(term)))
+        .find_map(|path| std::fs::read(path).ok())
+        .and_then(|data| parse_max_colors(&data))
+}
+
+// Compiled terminfo: a header of six little-endian shorts, the names, one byte per boolean,
+// alignment to an even offset, then the numbers (16-bit, or 32-bit in the extended format)
+fn parse_max_colors(data: &[u8]) -> Option<i32> {
+    const MAX_COLORS: usize = 13;
+    let short = |i: usize| {
+        let b = data.get(i * 2..i * 2 + 2)?;
+        Some(i16::from_le_bytes([b[0], b[1]]))
+    };
+    let num_size = match short(0)? {
+        0o432 => 2,
+        0o1036 => 4,
+        _ => return None,
+    };
+    let (names, bools, nums) = (short(1)? as usize, short(2)? as usize, short(3)? as usize);
+    if MAX_COLORS >= nums {
+        return Some(0);
+    }
+    let mut at = 12 + names + bools;
+    at += at % 2;
+    let at = at + MAX_COLORS * num_size;
+    let b = data.get(at..at + num_size)?;
+    let n = if num_size == 2 {
+        i16::from_le_bytes([b[0], b[1]]) as i32
+    } else {
+        i32::from_le_bytes([b[0], b[1], b[2], b[3]])
+    };
+    Some(n.max(0))
+}
+
+// Ask the terminal directly. Must run in raw mode before anything else reads input; the DA1
+// query goes last because every terminal answers it, so its reply ends the wait early.
+#[cfg(unix)]
+pub fn probe(timeout: Duration) -> Probe {
+    use std::io::Write;
+    let query = concat!(
+        "\x1b[48:2:1:2:3m\x1bP$qm\x1b\\\x1b[m", // truecolor: set an RGB bg and read it back
+        "\x1b_Gi=31,s=1,v=1,a=q,t=d,f=24;AAAA\x1b\\", // kitty graphics
+        "\x1b]11;?\x1b\\",                      // background color
+        "\x1b[c",                               // primary device attributes
+    );
+    let mut out = std::io::stdout();
+    if out
+        .write_all(query.as_bytes())
+        .and_then(|_| out.flush())
+        .is_err()
+    {
+        return Probe::default();
+    }
+    let deadline = Instant::now() + timeout;
+    let mut reply = Vec::new();
+    while da1_params(&reply).is_none() {
+        let left = deadline.saturating_duration_since(Instant::now());
+        let mut fd = libc::pollfd {
+            fd: 0,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let mut buf = [0u8; 256];
+        // SAFETY: plain poll/read on stdin with buffers we own
+        let n = unsafe {
+            if left.is_zero() || libc::poll(&mut fd, 1, left.as_millis() as libc::c_int) <= 0 {
+                break;
+            }
+            libc::read(0, buf.as_mut_ptr().cast(), buf.len())
+        };
+        if n <= 0 {
+            break;
+        }
+        reply.extend_from_slice(&buf[..n as usize]);
+    }
+    parse_probe(&reply)
+}
+
+// No raw stdin access elsewhere; rely on the environment alone
+#[cfg(not(unix))]
+pub fn probe(_timeout: Duration) -> Probe {
+    Probe::default()
+}
+
+fn parse_probe(reply: &[u8]) -> Probe {
+    let text = String::from_utf8_lossy(reply);
+    // DECRQSS echoes the SGR back after "1$r" when it understood the RGB color
+    let truecolor = text.split("\x1bP1$r").nth(1).is_some_and(|r| {
+        let sgr = r.split('\x1b').next().unwrap_or("");
+        sgr.contains("2:1:2:3") || sgr.contains("2;1;2;3")
+    });
+    let kitty = text.contains("\x1b_Gi=31;OK");
+    let sixel = da1_params(reply).is_some_and(|p| p.split(';').any(|v| v == "4"));
+    Probe {
+        truecolor,
+        sixel,
+        kitty,
+        background: osc11_rgb(&text),
+    }
+}
+
+// "OSC 11 ; rgb:RRRR/GGGG/BBBB" with 1-4 hex digits per channel, scaled to 8 bits
+fn osc11_rgb(text: &str) -> Option<(u8, u8, u8)> {
+    let rest = &text[text.find("\x1b]11;rgb:")? + 9..];
+    let end = rest.find(['\x1b', '\x07']).unwrap_or(rest.len());
+    let mut channels = rest[..end].split('/').map(|h| {
+        let v = u32::from_str_radix(h, 16)
+            .ok()
+            .filter(|_| (1..=4).contains(&h.len()))?;
+        let max = (1u32 << (4 * h.len())) - 1;
+        Some((v * 255 / max) as u8)
+    });
+    let rgb = (channels.next()??, channels.next()??, channels.next()??);
+    channels.next().is_none().then_some(rgb)
+}
+
+// Parameters of a "CSI ? ... c" device attributes reply, if one has arrived
+fn da1_params(reply: &[u8]) -> Option<String> {
+    let text = String::from_utf8_lossy(reply);
+    let rest = &text[text.find("\x1b[?")? + 3..];
+    let end = rest.find(|ch: char| !(ch.is_ascii_digit() || ch == ';'))?;
+    (rest[end..].starts_with('c')).then(|| rest[..end].to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    // Minimal legacy-format entry with 14 numbers, max_colors = `colors`
+    fn entry(colors: i16) -> Vec<u8> {
+        let names = b"xterm|t\0";
+        let mut d = Vec::new();
+        for v in [0o432i16, names.len() as i16, 1, 14, 0, 0] {
+            d.extend_from_slice(&v.to_le_bytes());
+        }
+        d.extend_from_slice(names);
+        d.push(1); // one boolean, then a pad byte to reach an even offset
+        d.push(0);
+        for i in 0..14 {
+            let v: i16 = if i == 13 { colors } else { -1 };
+            d.extend_from_slice(&v.to_le_bytes());
+        }
+        d
+    }
+    #[test]
+    fn reads_terminfo_colors() {
+        assert_eq!(parse_max_colors(&entry(256)), Some(256));
+        assert_eq!(parse_max_colors(&entry(-1)), Some(0));
+        assert_eq!(parse_max_colors(b"garbage"), None);
+    }
+    #[test]
+    fn parses_probe_replies() {
+        let reply = b"\x1bP1$r0;48:2:1:2:3m\x1b\\\x1b_Gi=31;OK\x1b\\\x1b[?62;4;22c";
+        let all = Probe {
+            truecolor: true,
+            sixel: true,
+            kitty: true,
+            background: None,
+        };
+        assert_eq!(parse_probe(reply), all);
+        let light = b"\x1b]11;rgb:ffff/fafa/f0f0\x1b\\\x1b[?1;2c";
+        assert_eq!(parse_probe(light).background, Some((255, 250, 240)));
+        assert_eq!(osc11_rgb("\x1b]11;rgb:0/8/f\x07"), Some((0, 136, 255)));
+        assert_eq!(osc11_rgb("\x1b]11;rgb:00/00\x07"), None);
+        assert_eq!(parse_probe(b"\x1bP0$r\x1b\\\x1b[?1;2c"), Probe::default());
+        assert_eq!(da1_params(b"\x1b[?62;4"), None); // incomplete
+    }
+}
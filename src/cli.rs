@@ -0,0 +1,451 @@
+// Command-line options (tiny hand-rolled parser, no extra dependency)
+use crate::color::{Background, ColorLevel, Ramp};
+use crate::export;
+use crate::layout::Rect;
+use crate::playlist;
+use crate::rays;
+use crate::sequence;
+use crate::strip;
+
+pub const USAGE: &str = "\
+Usage: terminal-fractal [OPTIONS]
+       terminal-fractal zoom LOCATION --to ZOOM [OPTIONS]   (see zoom --help)
+       terminal-fractal record FILE [OPTIONS]
+       terminal-fractal play FILE [--export DIR [--size WxH] [--fps N]] [OPTIONS]
+
+Subcommands:
+  record FILE        Explore as usual while recording the session (parameters,
+                     keys and the seed) to a small .tfrec file
+  play FILE          Replay a recording at this terminal's size; q or Esc stops
+  --export DIR       With play: write the replay as numbered PNG frames to DIR
+                     instead of showing it
+  --size WxH         Size of those frames in pixels (default: 1280x720)
+  --fps N            Frames per second of the export (default: 30)
+
+Options:
+  --region X,Y,WxH   Render only into this rectangle of the current screen
+                     (no alternate screen, rest of the terminal untouched)
+  --rays LIST        External ray angles in turns for the Mandelbrot overlay,
+                     e.g. 1/3,2/3,0.25 (default: 0, 1/2, 1/3, 2/3, n/7)
+  --aspect W:H       Lock the render area to a pixel aspect ratio with
+                     letterbox bars, e.g. 16:9 (or a number like 1.78)
+  --ramp CHARS       Shade characters from sparse to dense, e.g. ' .:oO@'
+                     (single-column characters only)
+  --background B     Terminal background, dark or light (default: ask the
+                     terminal, then COLORFGBG); light darkens bright colors
+  --daily            Fractal of the day: c, palette and wander from today's date
+  --seed N           Same from any number (the HUD shows the seed to share)
+  --screensaver      Cycle through a playlist of views with crossfades
+  --playlist FILE    Screensaver playlist, one location per line (implies
+                     --screensaver), e.g. fractal=julia c=-0.8,0.156 palette=fire
+  --interval SECS    Seconds per screensaver entry (default: 20)
+  --present FILE     Run a presentation script of timed steps (show, zoom,
+                     wait, palette, caption); arrows step, Space pauses
+  --sonify FILE      Write a sound of the fractal to FILE or a FIFO as raw PCM
+                     (S16LE mono 22050 Hz, e.g. play with aplay)
+  --exit-report FILE Write the exit summary as JSON to FILE ('-' for stdout)
+  --poster           On quit, print the last view once more at high quality
+                     (more iterations, supersampled, smooth colors) below the
+                     exit summary
+  --host [ADDR:]PORT Share the session: viewers that join see this view live
+                     (default port 7878); implies --no-focus-pause
+  --join HOST[:PORT] Watch a shared session, rendering it locally
+  --one-frame        Print one frame to stdout and exit, without raw mode or the
+                     alternate screen (also done when stdout is not a terminal)
+  --strip            Ambient strip for tmux status lines and prompts: a small
+                     frame (40x1 unless --width/--height say otherwise) printed
+                     every few seconds; with --one-frame, once
+  --refresh SECS     Seconds between strip frames (default: 5)
+  --strip-format F   Escapes for ansi (default), tmux (#[fg=...] styles), bash
+                     or zsh prompts
+  --width N          Columns of that frame (default: COLUMNS, then the terminal)
+  --height N         Rows of that frame (default: LINES, then the terminal, less
+                     one for the prompt)
+  --eco              Eco mode: 15 FPS and half the iterations (also turned on
+                     when running on battery)
+  --no-focus-pause   Keep animating while the window is unfocused
+  --no-color         Plain characters only (same as NO_COLOR=1)
+  --colors LEVEL     Use these colors whatever the terminal reports: none, 16
+                     (the terminal's own palette, so its theme shows), 256 or
+                     truecolor
+  -h, --help         Print this help";
+
+pub const ZOOM_USAGE: &str = "\
+Usage: terminal-fractal zoom LOCATION --to ZOOM [OPTIONS]
+
+Renders an exponential zoom into LOCATION as numbered PNG frames for a video.
+Frames already in the output directory are kept, so running the same command
+again after an interruption carries on where it stopped.
+
+  LOCATION           Where to zoom in the location format ('c' in the viewer
+                     copies it), e.g. 'fractal=mandelbrot center=-0.745,0.105
+                     iters=200 palette=fire'; its iters are the first frame's
+  --to ZOOM          Magnification of the last frame, e.g. 1e12
+  --from ZOOM        Magnification of the first frame (default: LOCATION's)
+  --frames N         Number of frames (default: 300)
+  --size WxH         Frame size in pixels (default: 1280x720)
+  --out DIR          Directory for frame-00001.png, ... (default: frames)
+  --iters-per-decade N
+                     Iterations added for every 10x of zoom (default: 100)
+  --perturb          Perturbation kernel: keeps zooming sharply past the f64
+                     limit (around 1e13x) up to 1e300x
+  -h, --help         Print this help";
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Options {
+    pub region: Option<Rect>,
+    pub rays: Option<Vec<f64>>,
+    pub aspect: Option<f64>,
+    pub ramp: Option<Ramp>,
+    pub background: Option<Background>,
+    pub daily: bool,
+    pub seed: Option<u64>,
+    pub screensaver: bool,
+    pub playlist: Option<String>,
+    pub interval: Option<f64>,
+    pub present: Option<String>,
+    pub sonify: Option<String>,
+    pub exit_report: Option<String>,
+    pub poster: bool,
+    pub host: Option<String>,
+    pub join: Option<String>,
+    pub one_frame: bool,
+    pub strip: bool,
+    pub refresh: Option<f64>,
+    pub strip_format: Option<strip::Format>,
+    pub width: Option<u16>,
+    pub height: Option<u16>,
+    pub eco: bool,
+    pub no_focus_pause: bool,
+    pub no_color: bool,
+    pub colors: Option<ColorLevel>,
+    pub help: bool,
+    pub record: Option<String>,
+    pub play: Option<String>,
+    pub export: Option<String>,
+    pub export_size: Option<(u32, u32)>,
+    pub fps: Option<f64>,
+}
+
+impl Options {
+    // Whether to offer back the view of a crashed run: only when exploring full-screen. A
+    // playlist, script, joined session or recording drives the view itself, and a --region widget
+    // has no HUD to show the question in.
+    pub fn offers_restore(&self) -> bool {
+        !self.screensaver
+            && self.present.is_none()
+            && self.join.is_none()
+            && self.play.is_none()
+            && self.region.is_none()
+    }
+}
+
+// Frames per second of an exported recording
+pub const DEFAULT_FPS: f64 = 30.0;
+
+pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Options, String> {
+    let mut opts = Options::default();
+    let mut args = args.into_iter().peekable();
+    if let Some(sub) = args.next_if(|a| a == "record" || a == "play") {
+        let file = args.next().ok_or_else(|| format!("{sub} needs a FILE"))?;
+        if sub == "record" {
+            opts.record = Some(file);
+        } else {
+            opts.play = Some(file);
+        }
+    }
+    while let Some(arg) = args.next() {
+        let (flag, inline) = match arg.split_once('=') {
+            Some((f, v)) if f.starts_with("--") => (f.to_string(), Some(v.to_string())),
+            _ => (arg, None),
+        };
+        let mut value = |name: &str| {
+            inline
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("{name} needs a value"))
+        };
+        match flag.as_str() {
+            "--region" => opts.region = Some(parse_region(&value("--region")?)?),
+            "--aspect" => opts.aspect = Some(parse_aspect(&value("--aspect")?)?),
+            "--rays" => opts.rays = Some(rays::parse_angles(&value("--rays")?)?),
+            "--ramp" => opts.ramp = Some(Ramp::parse(&value("--ramp")?)?),
+            "--background" => opts.background = Some(Background::parse(&value("--background")?)?),
+            "--daily" => opts.daily = true,
+            "--seed" => {
+                let v = value("--seed")?;
+                opts.seed = Some(v.parse().map_err(|_| format!("invalid seed '{v}'"))?);
+            }
+            "--screensaver" => opts.screensaver = true,
+            "--playlist" => {
+                opts.playlist = Some(value("--playlist")?);
+                opts.screensaver = true;
+            }
+            "--interval" => opts.interval = Some(parse_secs(&value("--interval")?)?),
+            "--present" => opts.present = Some(value("--present")?),
+            "--sonify" => opts.sonify = Some(value("--sonify")?),
+            "--exit-report" => opts.exit_report = Some(value("--exit-report")?),
+            "--poster" => opts.poster = true,
+            "--host" => opts.host = Some(value("--host")?),
+            "--join" => opts.join = Some(value("--join")?),
+            "--one-frame" => opts.one_frame = true,
+            "--strip" => opts.strip = true,
+            "--refresh" => opts.refresh = Some(parse_secs(&value("--refresh")?)?),
+            "--strip-format" => {
+                opts.strip_format = Some(strip::Format::parse(&value("--strip-format")?)?)
+            }
+            "--width" => opts.width = Some(parse_dim("--width", &value("--width")?)?),
+            "--height" => opts.height = Some(parse_dim("--height", &value("--height")?)?),
+            "--eco" => opts.eco = true,
+            "--no-focus-pause" => opts.no_focus_pause = true,
+            "--no-color" => opts.no_color = true,
+            "--colors" => opts.colors = Some(ColorLevel::parse(&value("--colors")?)?),
+            "--export" => opts.export = Some(value("--export")?),
+            "--size" => opts.export_size = Some(export::parse_size(&value("--size")?)?),
+            "--fps" => opts.fps = Some(parse_fps(&value("--fps")?)?),
+            "-h" | "--help" => opts.help = true,
+            other => return Err(format!("unknown option '{other}'")),
+        }
+    }
+    if opts.play.is_none()
+        && (opts.export.is_some() || opts.export_size.is_some() || opts.fps.is_some())
+    {
+        return Err("--export, --size and --fps go with play FILE".to_string());
+    }
+    if !opts.strip && (opts.refresh.is_some() || opts.strip_format.is_some()) {
+        return Err("--refresh and --strip-format go with --strip".to_string());
+    }
+    if opts.play.is_some() && (opts.screensaver || opts.present.is_some() || opts.join.is_some()) {
+        return Err(
+            "play drives the view itself; drop --screensaver, --present or --join".to_string(),
+        );
+    }
+    if opts.host.is_some() && opts.join.is_some() {
+        return Err("--host and --join can't be combined".to_string());
+    }
+    if opts.present.is_some() && (opts.screensaver || opts.join.is_some()) {
+        return Err("--present drives the view itself; drop --screensaver or --join".to_string());
+    }
+    Ok(opts)
+}
+
+// Arguments after `zoom`; None for --help
+pub fn parse_zoom(args: impl IntoIterator<Item = String>) -> Result<Option<sequence::Job>, String> {
+    let mut args = args.into_iter();
+    let (mut entry, mut from, mut to) = (None, None, None);
+    let mut job = sequence::Job {
+        entry: playlist::parse_entry("fractal=mandelbrot")?,
+        from: 1.0,
+        to: 1.0,
+        frames: sequence::DEFAULT_FRAMES,
+        size: sequence::DEFAULT_SIZE,
+        out: "frames".to_string(),
+        iters_per_decade: sequence::DEFAULT_ITERS_PER_DECADE,
+        perturb: false,
+    };
+    while let Some(arg) = args.next() {
+        let (flag, inline) = match arg.split_once('=') {
+            Some((f, v)) if f.starts_with("--") => (f.to_string(), Some(v.to_string())),
+            _ => (arg, None),
+        };
+        let mut value = |name: &str| {
+            inline
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("{name} needs a value"))
+        };
+        match flag.as_str() {
+            "--to" => to = Some(parse_zoom_factor(&value("--to")?)?),
+            "--from" => from = Some(parse_zoom_factor(&value("--from")?)?),
+            "--frames" => {
+                let v = value("--frames")?;
+                job.frames = match v.parse::<usize>() {
+                    Ok(n) if n > 0 => n,
+                    _ => return Err(format!("invalid frame count '{v}'")),
+                };
+            }
+            "--size" => job.size = export::parse_size(&value("--size")?)?,
+            "--out" => job.out = value("--out")?,
+            "--iters-per-decade" => {
+                let v = value("--iters-per-decade")?;
+                job.iters_per_decade = v
+                    .parse()
+                    .map_err(|_| format!("invalid iteration count '{v}'"))?;
+            }
+            "--perturb" => job.perturb = true,
+            "-h" | "--help" => return Ok(None),
+            other if other.starts_with('-') => return Err(format!("unknown option '{other}'")),
+            _ if entry.is_some() => return Err(format!("unexpected argument '{flag}'")),
+            _ => entry = Some(playlist::parse_entry(&flag)?),
+        }
+    }
+    job.entry = entry.ok_or("zoom needs a LOCATION to zoom into")?;
+    job.from = from.unwrap_or(job.entry.location.view.zoom);
+    job.to = to.ok_or("zoom needs --to, the magnification of the last frame")?;
+    Ok(Some(job))
+}
+
+fn parse_zoom_factor(s: &str) -> Result<f64, String> {
+    match s.trim().trim_end_matches('x').parse::<f64>() {
+        Ok(z) if z.is_finite() && (1e-3..=1e300).contains(&z) => Ok(z),
+        _ => Err(format!(
+            "invalid zoom '{s}' (expected a magnification like 1e12)"
+        )),
+    }
+}
+
+fn parse_dim(name: &str, s: &str) -> Result<u16, String> {
+    match s.trim().parse::<u16>() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(format!(
+            "invalid {name} '{s}' (expected a positive number of cells)"
+        )),
+    }
+}
+
+fn parse_secs(s: &str) -> Result<f64, String> {
+    match s.trim().parse::<f64>() {
+        Ok(v) if v.is_finite() && v > 0.0 => Ok(v),
+        _ => Err(format!("invalid duration '{s}' (expected seconds)")),
+    }
+}
+
+fn parse_fps(s: &str) -> Result<f64, String> {
+    match s.trim().parse::<f64>() {
+        Ok(v) if v.is_finite() && v > 0.0 => Ok(v),
+        _ => Err(format!("invalid --fps '{s}' (expected frames per second)")),
+    }
+}
+
+// "16:9" or a plain ratio like "1.78"
+fn parse_aspect(s: &str) -> Result<f64, String> {
+    let ratio = match s.split_once(':') {
+        Some((w, h)) => match (w.trim().parse::<f64>(), h.trim().parse::<f64>()) {
+            (Ok(w), Ok(h)) if h > 0.0 => w / h,
+            _ => f64::NAN,
+        },
+        None => s.trim().parse().unwrap_or(f64::NAN),
+    };
+    if ratio.is_finite() && ratio > 0.0 {
+        Ok(ratio)
+    } else {
+        Err(format!("invalid aspect '{s}' (expected W:H)"))
+    }
+}
+
+// "X,Y,WxH", e.g. "0,0,40x12"
+fn parse_region(s: &str) -> Result<Rect, String> {
+    let bad = || format!("invalid region '{s}' (expected X,Y,WxH)");
+    let mut parts = s.split(',');
+    let (Some(x), Some(y), Some(size), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(bad());
+    };
+    let (w, h) = size.split_once(['x', 'X']).ok_or_else(bad)?;
+    let num = |v: &str| v.trim().parse::<u16>().map_err(|_| bad());
+    let rect = Rect::new(num(x)?, num(y)?, num(w)?, num(h)?);
+    if rect.w == 0 || rect.h == 0 {
+        return Err(bad());
+    }
+    Ok(rect)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn args(v: &[&str]) -> Vec<String> {
+        v.iter().map(|s| s.to_string()).collect()
+    }
+    #[test]
+    fn parses_region() {
+        let o = parse(args(&["--region", "2,3,40x12"])).unwrap();
+        assert_eq!(o.region, Some(Rect::new(2, 3, 40, 12)));
+        let o = parse(args(&["--region=0,0,10X5"])).unwrap();
+        assert_eq!(o.region, Some(Rect::new(0, 0, 10, 5)));
+    }
+    #[test]
+    fn parses_aspect() {
+        let o = parse(args(&["--aspect", "4:3"])).unwrap();
+        assert_eq!(o.aspect, Some(4.0 / 3.0));
+        let o = parse(args(&["--aspect=2"])).unwrap();
+        assert_eq!(o.aspect, Some(2.0));
+    }
+    #[test]
+    fn restores_only_when_exploring_full_screen() {
+        assert!(parse(args(&["--seed", "7"])).unwrap().offers_restore());
+        assert!(
+            !parse(args(&["--region", "0,0,40x12"]))
+                .unwrap()
+                .offers_restore()
+        );
+        assert!(
+            !parse(args(&["--playlist", "views.txt"]))
+                .unwrap()
+                .offers_restore()
+        );
+        assert!(!parse(args(&["play", "a.tfrec"])).unwrap().offers_restore());
+    }
+    #[test]
+    fn parses_one_frame_size() {
+        let o = parse(args(&["--one-frame", "--width=60", "--height", "12"])).unwrap();
+        assert!(o.one_frame);
+        assert_eq!((o.width, o.height), (Some(60), Some(12)));
+    }
+    #[test]
+    fn parses_strip_options() {
+        let o = parse(args(&["--strip", "--strip-format", "tmux", "--refresh=10"])).unwrap();
+        assert!(o.strip);
+        assert_eq!(
+            (o.strip_format, o.refresh),
+            (Some(strip::Format::Tmux), Some(10.0))
+        );
+        assert!(parse(args(&["--strip-format", "zsh"])).is_err());
+    }
+    #[test]
+    fn parses_zoom_jobs() {
+        let loc = "fractal=julia c=-0.8,0.156 zoom=2 palette=fire";
+        let job = parse_zoom(args(&[loc, "--to", "1e14", "--perturb", "--frames=60"]))
+            .unwrap()
+            .unwrap();
+        assert_eq!((job.from, job.to, job.frames), (2.0, 1e14, 60));
+        assert!(job.perturb);
+        assert_eq!(job.out, "frames");
+        assert_eq!(parse_zoom(args(&["--help"])), Ok(None));
+        assert!(parse_zoom(args(&[loc])).is_err());
+        assert!(parse_zoom(args(&["--to", "1e6"])).is_err());
+        assert!(parse_zoom(args(&[loc, "--to", "0"])).is_err());
+        assert!(parse_zoom(args(&[loc, loc, "--to", "10"])).is_err());
+    }
+    #[test]
+    fn rejects_bad_input() {
+        assert!(parse(args(&["--region", "0,0,40"])).is_err());
+        assert!(parse(args(&["--region", "0,0,0x5"])).is_err());
+        assert!(parse(args(&["--region"])).is_err());
+        assert!(parse(args(&["--bogus"])).is_err());
+        assert!(parse(args(&["--interval", "-3"])).is_err());
+        assert!(parse(args(&["--aspect", "16:0"])).is_err());
+        assert!(parse(args(&["--ramp", " .＃"])).is_err());
+        assert!(parse(args(&["--background", "grey"])).is_err());
+        assert!(parse(args(&["--colors", "88"])).is_err());
+        assert!(parse(args(&["--width", "0"])).is_err());
+        assert!(parse(args(&["--host", "7878", "--join", "pi.local"])).is_err());
+        assert!(parse(args(&["--present", "talk.txt", "--screensaver"])).is_err());
+        assert!(parse(args(&["--export", "frames"])).is_err());
+        assert!(parse(args(&["play"])).is_err());
+        assert!(parse(args(&["play", "a.tfrec", "--join", "pi.local"])).is_err());
+    }
+    #[test]
+    fn parses_recordings() {
+        let o = parse(args(&["record", "dive.tfrec", "--seed", "7"])).unwrap();
+        assert_eq!((o.record.as_deref(), o.seed), (Some("dive.tfrec"), Some(7)));
+        let o = parse(args(&["play", "dive.tfrec", "--export=out", "--fps", "60"])).unwrap();
+        assert_eq!(o.play.as_deref(), Some("dive.tfrec"));
+        assert_eq!((o.export.as_deref(), o.fps), (Some("out"), Some(60.0)));
+        let fps = |v| parse(args(&["play", "dive.tfrec", "--export=out", "--fps", v]));
+        assert_eq!(fps("29.97").unwrap().fps, Some(29.97));
+        for bad in ["abc", "0", "-30", "inf"] {
+            assert!(fps(bad).unwrap_err().contains("--fps"));
+        }
+    }
+}
@@ -0,0 +1,64 @@
+// Frame timing: animation steps from a monotonic clock, clamped so that a suspended process,
+// a sleeping laptop or a stalled terminal can't make the parameter jump, and an FPS estimate
+// from wall-clock frame intervals that ignores those stalls instead of averaging them in
+use std::time::{Duration, Instant};
+
+// Longest step the animation takes in one frame
+const MAX_DT: f64 = 0.1;
+// Intervals longer than this are pauses, not frames
+const STALL: Duration = Duration::from_millis(500);
+
+pub struct FrameClock {
+    last: Instant,
+    fps: f64,
+}
+
+impl FrameClock {
+    pub fn new(now: Instant, target_fps: f64) -> Self {
+        FrameClock {
+            last: now,
+            fps: target_fps,
+        }
+    }
+
+    // Start of a frame: animation time step in seconds, within 0..=MAX_DT
+    pub fn tick(&mut self, now: Instant) -> f64 {
+        // A clock that went backwards reads as no time passed
+        let interval = now.checked_duration_since(self.last).unwrap_or_default();
+        self.last = now;
+        if !interval.is_zero() && interval < STALL {
+            self.fps = self.fps * 0.85 + 0.15 / interval.as_secs_f64();
+        }
+        interval.as_secs_f64().min(MAX_DT)
+    }
+
+    // Forget the time spent stopped (suspend) so the next tick is an ordinary frame
+    pub fn resync(&mut self, now: Instant) {
+        self.last = now;
+    }
+
+    // Smoothed frames per second of wall-clock time
+    pub fn fps(&self) -> f64 {
+        self.fps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn clamps_anomalies() {
+        let t0 = Instant::now();
+        let mut c = FrameClock::new(t0, 60.0);
+        let t1 = t0 + Duration::from_millis(20);
+        assert!((c.tick(t1) - 0.02).abs() < 1e-9);
+        assert!(c.fps() < 60.0);
+        let fps = c.fps();
+        // Hours of sleep: capped step, FPS untouched
+        let t2 = t1 + Duration::from_secs(3 * 3600);
+        assert_eq!(c.tick(t2), MAX_DT);
+        assert_eq!(c.fps(), fps);
+        // Going backwards: no step
+        assert_eq!(c.tick(t1), 0.0);
+    }
+}
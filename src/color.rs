@@ -1,6 +1,257 @@
 // Shade & color utilities (smoothed ramp with more gradual transitions)
+use crate::width;
+
 pub const SHADES: &[char] = &[' ', '.', ':', '-', '=', '+', '*', 'o', 'O', '#', '█'];
 
+// What the output backend may emit
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorLevel {
+    // Shade ramp only (attributes like reverse still apply)
+    Mono,
+    // Classic SGR 30-37/90-97; palette colors are mapped onto the terminal's own 16 by
+    // hue and brightness, so they follow its color scheme
+    Ansi16,
+    Ansi256,
+    // 24-bit capable; palette indices are still emitted as-is
+    TrueColor,
+}
+
+impl ColorLevel {
+    // `--colors`: none, 16, 256 or truecolor
+    pub fn parse(s: &str) -> Result<ColorLevel, String> {
+        match s.trim() {
+            "none" | "mono" | "0" => Ok(ColorLevel::Mono),
+            "16" | "8" => Ok(ColorLevel::Ansi16),
+            "256" => Ok(ColorLevel::Ansi256),
+            "truecolor" | "24bit" => Ok(ColorLevel::TrueColor),
+            _ => Err(format!(
+                "unknown color level '{s}' (none, 16, 256 or truecolor)"
+            )),
+        }
+    }
+}
+
+// Color support from the environment. NO_COLOR (non-empty) always wins; COLORTERM announces
+// truecolor; otherwise TERM's terminfo entry (looked up via `terminfo`) gives the count.
+// FORCE_COLOR or CLICOLOR_FORCE only settle the unsure cases, an unset, dumb or unknown
+// TERM, and never lower what was detected; "0" or "false" counts as unset.
+pub fn detect_color(
+    env: impl Fn(&str) -> Option<String>,
+    terminfo: impl Fn(&str) -> Option<i32>,
+) -> ColorLevel {
+    if env("NO_COLOR").is_some_and(|v| !v.is_empty()) {
+        return ColorLevel::Mono;
+    }
+    let term = env("TERM").unwrap_or_default();
+    let (detected, sure) = if term.is_empty() || term == "dumb" {
+        (ColorLevel::Mono, false)
+    } else if matches!(env("COLORTERM").as_deref(), Some("truecolor" | "24bit")) {
+        (ColorLevel::TrueColor, true)
+    } else {
+        match terminfo(&term) {
+            Some(n) if n >= 256 => (ColorLevel::Ansi256, true),
+            Some(n) if n >= 8 => (ColorLevel::Ansi16, true),
+            Some(_) => (ColorLevel::Mono, true),
+            // No entry installed: trust the name, otherwise stay with what every color
+            // terminal has
+            None if term.contains("256color") => (ColorLevel::Ansi256, true),
+            None => (ColorLevel::Ansi16, false),
+        }
+    };
+    let set = |name: &str| env(name).filter(|v| !matches!(v.as_str(), "0" | "false"));
+    let forced = match (set("FORCE_COLOR"), set("CLICOLOR_FORCE")) {
+        (Some(v), _) if v == "3" => Some(ColorLevel::TrueColor),
+        (Some(_), _) | (None, Some(_)) => Some(ColorLevel::Ansi256),
+        (None, None) => None,
+    };
+    match forced {
+        Some(level) if !sure => detected.max(level),
+        _ => detected,
+    }
+}
+
+// RGB of an xterm palette index (standard 16, the 6x6x6 cube, and the gray ramp)
+pub fn rgb_of(idx: u8) -> (u8, u8, u8) {
+    const BASE: [(u8, u8, u8); 16] = [
+        (0, 0, 0),
+        (205, 0, 0),
+        (0, 205, 0),
+        (205, 205, 0),
+        (0, 0, 238),
+        (205, 0, 205),
+        (0, 205, 205),
+        (229, 229, 229),
+        (127, 127, 127),
+        (255, 0, 0),
+        (0, 255, 0),
+        (255, 255, 0),
+        (92, 92, 255),
+        (255, 0, 255),
+        (0, 255, 255),
+        (255, 255, 255),
+    ];
+    let level = |v: u8| if v == 0 { 0 } else { 55 + 40 * v };
+    match idx {
+        0..=15 => BASE[idx as usize],
+        16..=231 => {
+            let i = idx - 16;
+            (level(i / 36), level(i / 6 % 6), level(i % 6))
+        }
+        _ => {
+            let g = 8 + 10 * (idx - 232);
+            (g, g, g)
+        }
+    }
+}
+
+// Closest entry of the 6x6x6 cube or the gray ramp to a 24-bit color (the fallback for
+// truecolor cells on lesser backends)
+pub fn nearest_256(rgb: (u8, u8, u8)) -> u8 {
+    let dist = |i: u8| {
+        let (r2, g2, b2) = rgb_of(i);
+        let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+        d(rgb.0, r2) + d(rgb.1, g2) + d(rgb.2, b2)
+    };
+    // Cube levels are 0, 95, 135, 175, 215, 255
+    let level = |v: u8| match v {
+        0..48 => 0,
+        48..115 => 1,
+        _ => ((v - 115) / 40 + 2).min(5),
+    };
+    let cube = 16 + 36 * level(rgb.0) + 6 * level(rgb.1) + level(rgb.2);
+    (232..=255).fold(cube, |best, i| if dist(i) < dist(best) { i } else { best })
+}
+
+// One of the 16 standard colors for a palette index, plus whether to draw it bold. Only
+// the hue family (red, yellow, green, cyan, blue, magenta or gray) and a brightness band
+// are kept, never the exact shade: the terminal's color scheme decides what "red" and
+// "bright red" look like, so matching xterm's RGB values would only turn dark bands black.
+// Bands run normal, normal bold, bright; terminals that show bold as bright still see them
+// get lighter in that order.
+pub fn ansi_band(idx: u8) -> (u8, bool) {
+    if idx < 16 {
+        return (idx, false);
+    }
+    let (r, g, b) = rgb_of(idx);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    if max < 48 {
+        return (0, false);
+    }
+    // Grays and pale tints go by lightness alone: black, bright black, white, bright white
+    if (max - min) as f64 <= 0.3 * max as f64 {
+        let slot = match max {
+            0..160 => 8,
+            160..224 => 7,
+            _ => 15,
+        };
+        return (slot, false);
+    }
+    let (r, g, b) = (r as f64, g as f64, b as f64);
+    let (max, span) = (max as f64, (max - min) as f64);
+    let hue = if max == r {
+        (g - b) / span
+    } else if max == g {
+        (b - r) / span + 2.0
+    } else {
+        (r - g) / span + 4.0
+    };
+    // Sextants centred on red, yellow, green, cyan, blue and magenta
+    const FAMILY: [u8; 6] = [1, 3, 2, 6, 4, 5];
+    let slot = FAMILY[((hue + 6.5).floor() as usize) % 6];
+    match max as u8 {
+        0..135 => (slot, false),
+        135..215 => (slot, true),
+        _ => (slot + 8, false),
+    }
+}
+
+// Terminal background, from an OSC 11 reply or COLORFGBG
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Background {
+    #[default]
+    Dark,
+    Light,
+}
+
+// Brightest foreground kept on a light background (relative luminance)
+const LIGHT_BG_MAX_LUMA: f64 = 0.4;
+
+impl Background {
+    pub fn parse(s: &str) -> Result<Background, String> {
+        match s {
+            "dark" => Ok(Background::Dark),
+            "light" => Ok(Background::Light),
+            _ => Err(format!("invalid background '{s}' (expected dark or light)")),
+        }
+    }
+
+    pub fn of_rgb((r, g, b): (u8, u8, u8)) -> Background {
+        if luma((r, g, b)) > 0.5 {
+            Background::Light
+        } else {
+            Background::Dark
+        }
+    }
+
+    // COLORFGBG="fg;bg" (rxvt, Konsole, ...): palette index of the background
+    pub fn from_colorfgbg(v: &str) -> Option<Background> {
+        match v.rsplit(';').next()?.trim().parse::<u8>().ok()? {
+            7 | 9..=15 => Some(Background::Light),
+            0..=6 | 8 => Some(Background::Dark),
+            _ => None,
+        }
+    }
+
+    // Foreground substitute for every palette index. The palettes are made for dark
+    // backgrounds; on a light one bright colors are darkened (same hue) until they contrast.
+    pub fn fg_table(self) -> [u8; 256] {
+        let mut table = [0u8; 256];
+        for (i, t) in table.iter_mut().enumerate() {
+            let idx = i as u8;
+            let (r, g, b) = rgb_of(idx);
+            let l = luma((r, g, b));
+            *t = if self == Background::Dark || l <= LIGHT_BG_MAX_LUMA {
+                idx
+            } else {
+                let k = LIGHT_BG_MAX_LUMA / l;
+                let scale = |v: u8| v as f64 * k;
+                nearest_dim((scale(r), scale(g), scale(b)))
+            };
+        }
+        table
+    }
+
+    // Same for a 24-bit foreground, scaled down exactly instead of to the nearest index
+    pub fn fg_rgb(self, (r, g, b): (u8, u8, u8)) -> (u8, u8, u8) {
+        let l = luma((r, g, b));
+        if self == Background::Dark || l <= LIGHT_BG_MAX_LUMA {
+            return (r, g, b);
+        }
+        let k = LIGHT_BG_MAX_LUMA / l;
+        let scale = |v: u8| (v as f64 * k) as u8;
+        (scale(r), scale(g), scale(b))
+    }
+}
+
+// Relative luminance (Rec. 709 weights, no gamma) in 0..=1
+fn luma((r, g, b): (u8, u8, u8)) -> f64 {
+    (0.2126 * r as f64 + 0.7152 * g as f64 + 0.0722 * b as f64) / 255.0
+}
+
+// Closest cube or gray entry (16..=255) to an RGB color among those dim enough to read on
+// a light background (the cube is too coarse for plain rounding to stay below the limit)
+fn nearest_dim((r, g, b): (f64, f64, f64)) -> u8 {
+    let dist = |i: u8| {
+        let (r2, g2, b2) = rgb_of(i);
+        (r - r2 as f64).powi(2) + (g - g2 as f64).powi(2) + (b - b2 as f64).powi(2)
+    };
+    (16..=255u8)
+        .filter(|&i| luma(rgb_of(i)) <= LIGHT_BG_MAX_LUMA)
+        .min_by(|&a, &b| dist(a).total_cmp(&dist(b)))
+        .unwrap_or(16)
+}
+
 #[inline]
 pub fn hsv_to_256(h_deg: f64, s: f64, v: f64) -> u8 {
     let h = (h_deg % 360.0 + 360.0) % 360.0 / 60.0;
@@ -26,13 +277,125 @@ pub fn hsv_to_256(h_deg: f64, s: f64, v: f64) -> u8 {
     16 + 36 * ri + 6 * gi + bi
 }
 
-#[inline]
-pub fn shade(norm: f64) -> char {
-    // Slight gamma to bias toward darker chars longer
-    let gamma = 0.85;
-    let idx = (norm.powf(gamma) * (SHADES.len() - 1) as f64).clamp(0.0, (SHADES.len() - 1) as f64)
-        as usize;
-    SHADES[idx]
+// Escape-count coloring, from 0 (escapes at once) to 1 (just before max_iters)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Palette {
+    #[default]
+    Rainbow,
+    Fire,
+    Ocean,
+    Gray,
+}
+
+impl Palette {
+    pub const ALL: [Palette; 4] = [
+        Palette::Rainbow,
+        Palette::Fire,
+        Palette::Ocean,
+        Palette::Gray,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Palette::Rainbow => "rainbow",
+            Palette::Fire => "fire",
+            Palette::Ocean => "ocean",
+            Palette::Gray => "gray",
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<Palette, String> {
+        Palette::ALL
+            .into_iter()
+            .find(|p| p.name() == s)
+            .ok_or_else(|| format!("unknown palette '{s}' (rainbow, fire, ocean, gray)"))
+    }
+
+    #[inline]
+    pub fn color(self, norm: f64) -> u8 {
+        let n = norm.clamp(0.0, 1.0);
+        let ramp = |lo: f64| ((n - lo) * 3.0).clamp(0.0, 1.0);
+        match self {
+            Palette::Rainbow => hsv_to_256(n * 360.0, 0.9, 1.0),
+            // black -> red -> yellow -> white
+            Palette::Fire => cube(ramp(0.0), ramp(1.0 / 3.0), ramp(2.0 / 3.0)),
+            // deep blue -> cyan -> white
+            Palette::Ocean => cube(ramp(2.0 / 3.0), ramp(1.0 / 3.0), 0.4 + 0.6 * ramp(0.0)),
+            Palette::Gray => 232 + (n * 23.0).round() as u8,
+        }
+    }
+}
+
+// Nearest entry of the 6x6x6 color cube for components in 0..=1
+fn cube(r: f64, g: f64, b: f64) -> u8 {
+    let q = |v: f64| (v * 5.0).round() as u8;
+    16 + 36 * q(r) + 6 * q(g) + q(b)
+}
+
+// Characters from sparse to dense; every one takes exactly one column
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ramp {
+    chars: Vec<char>,
+}
+
+impl Default for Ramp {
+    fn default() -> Self {
+        Ramp {
+            chars: SHADES.to_vec(),
+        }
+    }
+}
+
+impl Ramp {
+    // Custom ramp from `--ramp`; wide, zero-width and control characters would shift columns
+    pub fn parse(s: &str) -> Result<Ramp, String> {
+        let chars: Vec<char> = s.chars().collect();
+        for &c in &chars {
+            let problem = match width::char_width(c) {
+                None => "a control character",
+                Some(0) => "zero-width (combining)",
+                Some(2) => "double-width",
+                Some(_) => continue,
+            };
+            return Err(format!(
+                "ramp character {c:?} (U+{:04X}) is {problem}",
+                c as u32
+            ));
+        }
+        if chars.len() < 2 {
+            return Err("ramp needs at least two characters".to_string());
+        }
+        Ok(Ramp { chars })
+    }
+
+    pub fn chars(&self) -> &[char] {
+        &self.chars
+    }
+
+    // Swap ambiguous-width glyphs for ASCII look-alikes on terminals that draw them wide
+    pub fn narrowed(&self) -> Ramp {
+        let chars = self
+            .chars
+            .iter()
+            .map(|&c| match c {
+                _ if !width::is_ambiguous(c) => c,
+                '█' | '▓' => '@',
+                '▒' | '■' => '#',
+                '░' | '·' | '•' => '.',
+                _ => '*',
+            })
+            .collect();
+        Ramp { chars }
+    }
+
+    #[inline]
+    pub fn shade(&self, norm: f64) -> char {
+        // Slight gamma to bias toward darker chars longer
+        let gamma = 0.85;
+        let last = (self.chars.len() - 1) as f64;
+        let idx = (norm.powf(gamma) * last).clamp(0.0, last) as usize;
+        self.chars[idx]
+    }
 }
 
 #[cfg(test)]
@@ -45,16 +408,140 @@ mod tests {
             assert!((c..=255).contains(&c));
         }
     }
+    fn level(vars: &[(&str, &str)]) -> ColorLevel {
+        let terminfo = |t: &str| match t {
+            "xterm-256color" => Some(256),
+            "xterm" | "linux" => Some(8),
+            "vt100" => Some(0),
+            _ => None,
+        };
+        detect_color(
+            |k| {
+                vars.iter()
+                    .find(|(n, _)| *n == k)
+                    .map(|(_, v)| v.to_string())
+            },
+            terminfo,
+        )
+    }
+    #[test]
+    fn color_env_precedence() {
+        assert_eq!(level(&[("TERM", "xterm-256color")]), ColorLevel::Ansi256);
+        let no = [("TERM", "xterm"), ("NO_COLOR", "1"), ("FORCE_COLOR", "1")];
+        assert_eq!(level(&no), ColorLevel::Mono);
+        assert_eq!(
+            level(&[("TERM", "xterm-256color"), ("NO_COLOR", "")]),
+            ColorLevel::Ansi256
+        );
+        assert_eq!(level(&[("TERM", "dumb")]), ColorLevel::Mono);
+        let forced = [("TERM", "dumb"), ("CLICOLOR_FORCE", "1")];
+        assert_eq!(level(&forced), ColorLevel::Ansi256);
+        // Forcing is for unsure terminals, and never lowers a known level
+        assert_eq!(
+            level(&[("TERM", "xterm"), ("FORCE_COLOR", "0")]),
+            ColorLevel::Ansi16
+        );
+        let known = [("TERM", "xterm-256color"), ("FORCE_COLOR", "1")];
+        assert_eq!(level(&known), ColorLevel::Ansi256);
+        let truecolor = [
+            ("TERM", "xterm"),
+            ("COLORTERM", "truecolor"),
+            ("FORCE_COLOR", "1"),
+        ];
+        assert_eq!(level(&truecolor), ColorLevel::TrueColor);
+        assert_eq!(
+            level(&[("TERM", "xterm"), ("FORCE_COLOR", "3")]),
+            ColorLevel::Ansi16
+        );
+        assert_eq!(
+            level(&[("TERM", "foo"), ("FORCE_COLOR", "3")]),
+            ColorLevel::TrueColor
+        );
+        assert_eq!(
+            level(&[("TERM", "dumb"), ("CLICOLOR_FORCE", "0")]),
+            ColorLevel::Mono
+        );
+    }
+    #[test]
+    fn terminfo_decides_color_count() {
+        assert_eq!(level(&[("TERM", "xterm")]), ColorLevel::Ansi16);
+        assert_eq!(level(&[("TERM", "vt100")]), ColorLevel::Mono);
+        assert_eq!(level(&[("TERM", "foo-256color")]), ColorLevel::Ansi256);
+        assert_eq!(level(&[("TERM", "foo")]), ColorLevel::Ansi16);
+        let truecolor = [("TERM", "xterm"), ("COLORTERM", "truecolor")];
+        assert_eq!(level(&truecolor), ColorLevel::TrueColor);
+    }
+    #[test]
+    fn palettes_span_their_range() {
+        assert_eq!(Palette::Fire.color(0.0), 16); // black
+        assert_eq!(Palette::Fire.color(1.0), 231); // white
+        assert_eq!(Palette::Gray.color(1.0), 255);
+        assert_eq!(Palette::Rainbow.color(0.5), hsv_to_256(180.0, 0.9, 1.0));
+        assert_eq!(Palette::parse("ocean"), Ok(Palette::Ocean));
+        assert!(Palette::parse("neon").is_err());
+    }
+    #[test]
+    fn maps_to_16_colors() {
+        assert_eq!(ansi_band(196), (9, false)); // pure red
+        assert_eq!(ansi_band(16), (0, false));
+        assert_eq!(ansi_band(231), (15, false));
+        assert_eq!(ansi_band(244), (8, false));
+        assert_eq!(ansi_band(3), (3, false));
+        // Dark shades keep their hue instead of turning black
+        assert_eq!(ansi_band(17), (4, false));
+        assert_eq!(ansi_band(88), (1, true));
+        // Fire runs red, bold red, bright red, bright yellow, bright white
+        let fire = [0.05, 0.15, 0.3, 0.5, 0.95].map(|n| ansi_band(Palette::Fire.color(n)));
+        assert_eq!(
+            fire,
+            [(1, false), (1, true), (9, false), (11, false), (15, false)]
+        );
+        assert_eq!(ColorLevel::parse("16"), Ok(ColorLevel::Ansi16));
+        assert!(ColorLevel::parse("88").is_err());
+        assert_eq!(nearest_256((255, 0, 0)), 196);
+        assert_eq!(nearest_256((0, 140, 130)), 16 + 6 * 2 + 2);
+        assert_eq!(nearest_256((128, 128, 128)), 244);
+    }
+    #[test]
+    fn darkens_for_light_backgrounds() {
+        assert_eq!(Background::of_rgb((250, 250, 245)), Background::Light);
+        assert_eq!(Background::of_rgb((30, 30, 30)), Background::Dark);
+        assert_eq!(Background::from_colorfgbg("0;15"), Some(Background::Light));
+        assert_eq!(
+            Background::from_colorfgbg("15;default;0"),
+            Some(Background::Dark)
+        );
+        assert_eq!(Background::from_colorfgbg("default"), None);
+        let dark = Background::Dark.fg_table();
+        assert!(dark.iter().enumerate().all(|(i, &v)| v as usize == i));
+        let light = Background::Light.fg_table();
+        assert!(light.iter().all(|&v| luma(rgb_of(v)) <= LIGHT_BG_MAX_LUMA));
+        assert_eq!(light[16], 16); // black stays
+        assert_eq!(light[196], 196); // pure red reads fine already
+        assert_ne!(light[226], 226); // yellow does not
+    }
     #[test]
     fn shade_density_progresses() {
         // Ensure later norm values don't map to an earlier index in the shade ramp
+        let ramp = Ramp::default();
         let mut last_idx = 0usize;
         for i in 0..50 {
             let n = i as f64 / 49.0;
-            let ch = shade(n);
+            let ch = ramp.shade(n);
             let idx = SHADES.iter().position(|c| *c == ch).unwrap();
             assert!(idx >= last_idx);
             last_idx = idx;
         }
     }
+    #[test]
+    fn ramp_rejects_shifting_chars() {
+        assert_eq!(Ramp::parse(" .oO@").unwrap().chars().len(), 5);
+        assert!(Ramp::parse(".").is_err());
+        assert!(Ramp::parse(" .漢").is_err());
+        assert!(Ramp::parse(" e\u{0301}").is_err());
+        assert!(Ramp::parse(" .\t#").is_err());
+        let narrow = Ramp::default().narrowed();
+        assert_eq!(narrow.chars().last(), Some(&'@'));
+        assert!(narrow.chars().iter().all(|&c| !width::is_ambiguous(c)));
+    }
 }
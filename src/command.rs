@@ -0,0 +1,125 @@
+// Commands typed on the ':' command line
+use crate::compare::{self, Settings};
+use crate::export;
+use crate::texture::Texture;
+use crate::warp::Warp;
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    // Famous place by name; None opens the picker
+    Goto(Option<String>),
+    // PNG of the focused view; the file name defaults to one with a timestamp
+    Export {
+        size: (u32, u32),
+        path: Option<String>,
+    },
+    // The next frame as it appears on screen, as a standalone HTML page
+    Html(Option<String>),
+    // The same drawn with the embedded font into a PNG, keeping the character-cell look
+    Shot(Option<String>),
+    // View transform of the focused pane
+    Warp(Warp),
+    // Retro texture settings, None to turn it off
+    Texture(Option<Texture>),
+    // Render settings of the two sides of an A/B comparison, None to end it
+    Compare(Option<[Settings; 2]>),
+}
+
+// None for an empty line
+pub fn parse(line: &str) -> Result<Option<Command>, String> {
+    let mut words = line.split_whitespace();
+    let Some(cmd) = words.next() else {
+        return Ok(None);
+    };
+    let rest: Vec<&str> = words.collect();
+    let cmd = match cmd {
+        "goto" => Command::Goto((!rest.is_empty()).then(|| rest.join(" "))),
+        "export" => {
+            // Optional WxH and an optional .png file name, in either order
+            let mut size = export::DEFAULT_SIZE;
+            let mut path = None;
+            for arg in rest {
+                match export::parse_size(arg) {
+                    Ok(s) => size = s,
+                    Err(_) if path.is_none() && arg.ends_with(".png") => {
+                        path = Some(arg.to_string())
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+            Command::Export { size, path }
+        }
+        "html" | "shot" => {
+            let path = match rest[..] {
+                [] => None,
+                [path] => Some(path.to_string()),
+                _ => return Err(format!("usage: {cmd} [FILE]")),
+            };
+            if cmd == "html" {
+                Command::Html(path)
+            } else {
+                Command::Shot(path)
+            }
+        }
+        "warp" => Command::Warp(Warp::parse(&rest.join(" "))?),
+        "texture" => Command::Texture(Texture::parse(&rest.join(" "))?),
+        "compare" => Command::Compare(compare::parse(&rest.join(" "))?),
+        other => {
+            return Err(format!(
+                "unknown command '{other}' (goto, export, html, shot, warp, texture, compare)"
+            ));
+        }
+    };
+    Ok(Some(cmd))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn parses_commands() {
+        assert_eq!(parse("  "), Ok(None));
+        assert_eq!(
+            parse("goto seahorse valley"),
+            Ok(Some(Command::Goto(Some("seahorse valley".to_string()))))
+        );
+        assert_eq!(parse("goto"), Ok(Some(Command::Goto(None))));
+        assert_eq!(
+            parse("export a.png 640x480"),
+            Ok(Some(Command::Export {
+                size: (640, 480),
+                path: Some("a.png".to_string())
+            }))
+        );
+        assert_eq!(
+            parse("export"),
+            Ok(Some(Command::Export {
+                size: export::DEFAULT_SIZE,
+                path: None
+            }))
+        );
+        assert!(parse("export 4k").is_err());
+        assert_eq!(
+            parse("html out.html"),
+            Ok(Some(Command::Html(Some("out.html".to_string()))))
+        );
+        assert!(parse("html a b").is_err());
+        assert_eq!(parse("shot"), Ok(Some(Command::Shot(None))));
+        assert_eq!(
+            parse("warp log polar"),
+            Ok(Some(Command::Warp(Warp::LogPolar)))
+        );
+        assert!(parse("warp").is_err());
+        assert!(matches!(
+            parse("texture noise"),
+            Ok(Some(Command::Texture(Some(_))))
+        ));
+        assert_eq!(parse("texture off"), Ok(Some(Command::Texture(None))));
+        assert!(matches!(
+            parse("compare f64 vs f32"),
+            Ok(Some(Command::Compare(Some(_))))
+        ));
+        assert!(parse("compare 120 1000").is_err());
+        assert!(parse("jump").is_err());
+    }
+}
@@ -0,0 +1,206 @@
+// A/B comparison: the focused view rendered twice side by side, each half with its own
+// render settings (banded or smooth coloring, f64 or f32 kernel, iteration budget) and
+// labelled with them and with how long it takes to draw, for tuning quality against speed
+// and checking a kernel against another.
+use crate::color::Ramp;
+use crate::compositor::{Cell, Layer};
+use crate::histogram::Histogram;
+use crate::layout::{Pane, Rect};
+use num_complex::Complex64;
+use std::time::Duration;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Coloring {
+    // Whole escape counts, as the normal view draws them
+    #[default]
+    Banded,
+    // Continuous escape count
+    Smooth,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Float {
+    #[default]
+    F64,
+    F32,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Settings {
+    pub coloring: Coloring,
+    pub float: Float,
+    // None follows the session's iteration budget ([ and ], eco mode, the governor)
+    pub iters: Option<usize>,
+}
+
+// Weight of the newest frame in the smoothed draw times
+const SMOOTHING: f64 = 0.1;
+
+impl Settings {
+    pub fn label(&self, session_iters: usize) -> String {
+        let coloring = match self.coloring {
+            Coloring::Banded => "banded",
+            Coloring::Smooth => "smooth",
+        };
+        let float = match self.float {
+            Float::F64 => "f64",
+            Float::F32 => "f32",
+        };
+        format!(
+            "{coloring} {float} {} iters",
+            self.iters.unwrap_or(session_iters)
+        )
+    }
+
+    // Words in any order: banded/smooth, f64/f32 and an iteration count
+    fn parse(s: &str) -> Result<Settings, String> {
+        let mut settings = Settings::default();
+        for word in s.split_whitespace() {
+            match word.to_lowercase().as_str() {
+                "banded" => settings.coloring = Coloring::Banded,
+                "smooth" => settings.coloring = Coloring::Smooth,
+                "f64" => settings.float = Float::F64,
+                "f32" => settings.float = Float::F32,
+                other => match other.parse::<usize>() {
+                    Ok(n) if (16..=100_000).contains(&n) => settings.iters = Some(n),
+                    _ => {
+                        return Err(format!(
+                            "unknown setting '{word}' (banded, smooth, f64, f32 or 16-100000 iterations)"
+                        ));
+                    }
+                },
+            }
+        }
+        Ok(settings)
+    }
+}
+
+// "A vs B", e.g. "120 vs 1000" or "banded f64 vs smooth f32"; settings a side leaves out
+// are the normal view's. Empty compares banded against smooth, "off" gives None.
+pub fn parse(s: &str) -> Result<Option<[Settings; 2]>, String> {
+    let s = s.trim();
+    if s.is_empty() {
+        let smooth = Settings {
+            coloring: Coloring::Smooth,
+            ..Settings::default()
+        };
+        return Ok(Some([Settings::default(), smooth]));
+    }
+    if s.eq_ignore_ascii_case("off") {
+        return Ok(None);
+    }
+    let words: Vec<&str> = s.split_whitespace().collect();
+    let Some(vs) = words.iter().position(|w| w.eq_ignore_ascii_case("vs")) else {
+        return Err("usage: compare [A vs B | off], e.g. compare 120 vs 1000".to_string());
+    };
+    let a = Settings::parse(&words[..vs].join(" "))?;
+    let b = Settings::parse(&words[vs + 1..].join(" "))?;
+    Ok(Some([a, b]))
+}
+
+pub struct Compare {
+    pub sides: [Settings; 2],
+    // Smoothed draw time of each side in milliseconds, once it has been drawn
+    ms: [Option<f64>; 2],
+}
+
+impl Compare {
+    pub fn new(sides: [Settings; 2]) -> Self {
+        Compare {
+            sides,
+            ms: [None; 2],
+        }
+    }
+
+    pub fn record(&mut self, side: usize, took: Duration) {
+        let ms = took.as_secs_f64() * 1000.0;
+        self.ms[side] = Some(match self.ms[side] {
+            Some(old) => old + (ms - old) * SMOOTHING,
+            None => ms,
+        });
+    }
+
+    // "A: smooth f64 120 iters | 3.1 ms"
+    pub fn label(&self, side: usize, session_iters: usize) -> String {
+        let name = if side == 0 { 'A' } else { 'B' };
+        let settings = self.sides[side].label(session_iters);
+        match self.ms[side] {
+            Some(ms) => format!("{name}: {settings} | {ms:.1} ms"),
+            None => format!("{name}: {settings}"),
+        }
+    }
+
+    pub fn summary(&self, session_iters: usize) -> String {
+        let [a, b] = self.sides.map(|s| s.label(session_iters));
+        format!("Comparing {a} vs {b}")
+    }
+}
+
+// `pane` into `rect` with the settings of one side, at full resolution so the times
+// compare the kernels and not the governor
+#[allow(clippy::too_many_arguments)]
+pub fn draw(
+    layer: &mut Layer,
+    rect: Rect,
+    pane: &Pane,
+    c: Complex64,
+    session_iters: usize,
+    ramp: &Ramp,
+    mut hist: Option<&mut Histogram>,
+    settings: Settings,
+) {
+    let (w, h) = (rect.w as usize, rect.h as usize);
+    let c = pane.param(c);
+    let max_iters = settings.iters.unwrap_or(session_iters);
+    for y in 0..h {
+        for x in 0..w {
+            let p = pane.point(x, y, w, h);
+            let f = pane.fractal;
+            let n = match (settings.coloring, settings.float) {
+                (Coloring::Banded, Float::F64) => f.escape(p, c, max_iters) as f64,
+                (Coloring::Banded, Float::F32) => f.escape_f32(p, c, max_iters) as f64,
+                (Coloring::Smooth, Float::F64) => f.escape_smooth(p, c, max_iters),
+                (Coloring::Smooth, Float::F32) => f.escape_smooth_f32(p, c, max_iters),
+            };
+            if let Some(hist) = hist.as_deref_mut() {
+                hist.add(n as usize);
+            }
+            let cell = if n >= max_iters as f64 {
+                Cell::BLANK
+            } else {
+                let norm = n / max_iters as f64;
+                Cell::new(ramp.shade(norm), Some(pane.palette.color(norm)))
+            };
+            layer.put(rect.x + x as u16, rect.y + y as u16, cell);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn parses_both_sides() {
+        let [a, b] = parse("120 vs smooth f32 1000").unwrap().unwrap();
+        assert_eq!(a.iters, Some(120));
+        assert_eq!(a.label(50), "banded f64 120 iters");
+        assert_eq!(b.label(50), "smooth f32 1000 iters");
+        let [a, b] = parse("").unwrap().unwrap();
+        assert_eq!(
+            (a.coloring, b.coloring),
+            (Coloring::Banded, Coloring::Smooth)
+        );
+        assert_eq!(a.label(300), "banded f64 300 iters");
+        assert_eq!(parse("off"), Ok(None));
+        assert!(parse("smooth").is_err());
+        assert!(parse("f16 vs f64").is_err());
+    }
+    #[test]
+    fn smooths_draw_times() {
+        let mut cmp = Compare::new(parse("").unwrap().unwrap());
+        assert_eq!(cmp.label(1, 120), "B: smooth f64 120 iters");
+        cmp.record(1, Duration::from_millis(10));
+        cmp.record(1, Duration::from_millis(20));
+        assert_eq!(cmp.label(1, 120), "B: smooth f64 120 iters | 11.0 ms");
+    }
+}
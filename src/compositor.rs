@@ -0,0 +1,575 @@
+// Layered cell buffers merged by z-order into one frame, then written out in a single pass
+use crate::color::{self, Background, ColorLevel};
+use crate::font;
+use crate::layout::Rect;
+use crate::texture::Texture;
+use crossterm::{
+    Command, cursor, queue,
+    style::{Attribute, Color, Print, SetAttribute, SetBackgroundColor, SetForegroundColor},
+};
+use std::fmt;
+use std::io::{self, Write};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cell {
+    pub ch: char,
+    // 256-color palette indices; None = terminal default
+    pub fg: Option<u8>,
+    pub bg: Option<u8>,
+    // 24-bit foreground, written instead of `fg` on truecolor backends (`fg` is the fallback)
+    pub rgb: Option<(u8, u8, u8)>,
+    pub bold: bool,
+    pub reverse: bool,
+}
+
+impl Cell {
+    pub const BLANK: Cell = Cell {
+        ch: ' ',
+        fg: None,
+        bg: None,
+        rgb: None,
+        bold: false,
+        reverse: false,
+    };
+
+    pub fn new(ch: char, fg: Option<u8>) -> Self {
+        Cell {
+            ch,
+            fg,
+            ..Cell::BLANK
+        }
+    }
+
+    pub fn bold(self) -> Self {
+        Cell { bold: true, ..self }
+    }
+
+    // Cell in a 24-bit color, with the nearest palette index for other backends
+    pub fn rgb(ch: char, rgb: (u8, u8, u8)) -> Self {
+        Cell {
+            rgb: Some(rgb),
+            ..Cell::new(ch, Some(color::nearest_256(rgb)))
+        }
+    }
+
+    pub fn reverse(self) -> Self {
+        Cell {
+            reverse: true,
+            ..self
+        }
+    }
+
+    // Drop what the backend may not emit
+    fn limit(self, colors: ColorLevel) -> Self {
+        match colors {
+            ColorLevel::Mono => Cell {
+                fg: None,
+                bg: None,
+                rgb: None,
+                ..self
+            },
+            ColorLevel::Ansi16 => {
+                let fg = self.fg.map(color::ansi_band);
+                Cell {
+                    fg: fg.map(|(slot, _)| slot),
+                    bg: self.bg.map(|i| color::ansi_band(i).0),
+                    rgb: None,
+                    bold: self.bold || fg.is_some_and(|(_, bold)| bold),
+                    ..self
+                }
+            }
+            ColorLevel::Ansi256 => Cell { rgb: None, ..self },
+            ColorLevel::TrueColor => self,
+        }
+    }
+
+    fn style(&self) -> Style {
+        (self.fg, self.bg, self.rgb, self.bold, self.reverse)
+    }
+}
+
+type Style = (Option<u8>, Option<u8>, Option<(u8, u8, u8)>, bool, bool);
+
+// Layers from bottom to top
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Z {
+    Fractal = 0,
+    Overlay = 1,
+    Chrome = 2,
+}
+
+// One z-level; None cells are transparent
+pub struct Layer {
+    w: u16,
+    h: u16,
+    cells: Vec<Option<Cell>>,
+}
+
+impl Layer {
+    fn new(w: u16, h: u16) -> Self {
+        Layer {
+            w,
+            h,
+            cells: vec![None; w as usize * h as usize],
+        }
+    }
+
+    #[inline]
+    pub fn put(&mut self, x: u16, y: u16, cell: Cell) {
+        if x < self.w && y < self.h {
+            self.cells[y as usize * self.w as usize + x as usize] = Some(cell);
+        }
+    }
+
+    // Cell at (x, y); None where transparent or outside
+    pub fn get(&self, x: u16, y: u16) -> Option<Cell> {
+        if x < self.w && y < self.h {
+            self.cells[y as usize * self.w as usize + x as usize]
+        } else {
+            None
+        }
+    }
+
+    // Write `text` from (x, y) with the style of `style`, clipped to `max_w` columns
+    pub fn text(&mut self, x: u16, y: u16, text: &str, style: Cell, max_w: u16) {
+        for (i, ch) in text.chars().take(max_w as usize).enumerate() {
+            self.put(x + i as u16, y, Cell { ch, ..style });
+        }
+    }
+
+    // Make cells of `rect` transparent again unless `keep(x, y)`
+    pub fn mask(&mut self, rect: Rect, keep: impl Fn(u16, u16) -> bool) {
+        for y in rect.y..(rect.y + rect.h).min(self.h) {
+            for x in rect.x..(rect.x + rect.w).min(self.w) {
+                if !keep(x, y) {
+                    self.cells[y as usize * self.w as usize + x as usize] = None;
+                }
+            }
+        }
+    }
+
+    // Make a rectangle opaque (panels hide whatever is underneath)
+    pub fn fill(&mut self, rect: Rect, cell: Cell) {
+        for y in rect.y..rect.y + rect.h {
+            for x in rect.x..rect.x + rect.w {
+                self.put(x, y, cell);
+            }
+        }
+    }
+}
+
+pub struct Compositor {
+    pub w: u16,
+    pub h: u16,
+    layers: [Layer; 3],
+    frame: Vec<Cell>,
+    // Foreground substitutes for the terminal's background (identity on dark ones)
+    fg_map: [u8; 256],
+    background: Background,
+    // Retro texture blended into the composed colors
+    texture: Option<Texture>,
+}
+
+impl Compositor {
+    pub fn new(w: u16, h: u16) -> Self {
+        Compositor {
+            w,
+            h,
+            layers: [Layer::new(w, h), Layer::new(w, h), Layer::new(w, h)],
+            frame: vec![Cell::BLANK; w as usize * h as usize],
+            fg_map: Background::Dark.fg_table(),
+            background: Background::Dark,
+            texture: None,
+        }
+    }
+
+    pub fn set_background(&mut self, bg: Background) {
+        self.fg_map = bg.fg_table();
+        self.background = bg;
+    }
+
+    pub fn set_texture(&mut self, texture: Option<Texture>) {
+        self.texture = texture;
+    }
+
+    // Reallocate on size change, otherwise just make every layer transparent again
+    pub fn begin(&mut self, w: u16, h: u16) {
+        if (w, h) != (self.w, self.h) {
+            *self = Compositor {
+                fg_map: self.fg_map,
+                background: self.background,
+                texture: self.texture,
+                ..Compositor::new(w, h)
+            };
+        } else {
+            for l in &mut self.layers {
+                l.cells.fill(None);
+            }
+        }
+    }
+
+    pub fn layer(&mut self, z: Z) -> &mut Layer {
+        &mut self.layers[z as usize]
+    }
+
+    // Topmost opaque cell wins; a cell without a background keeps the one below it
+    pub fn compose(&mut self) -> &[Cell] {
+        for (i, out) in self.frame.iter_mut().enumerate() {
+            let mut merged = Cell::BLANK;
+            for layer in &self.layers {
+                if let Some(cell) = layer.cells[i] {
+                    let bg = cell.bg.or(merged.bg);
+                    merged = Cell { bg, ..cell };
+                }
+            }
+            merged.fg = merged.fg.map(|i| self.fg_map[i as usize]);
+            merged.rgb = merged.rgb.map(|rgb| self.background.fg_rgb(rgb));
+            if let Some(t) = &self.texture {
+                let rgb = merged.rgb.or(merged.fg.map(color::rgb_of));
+                if let Some(rgb) = rgb {
+                    let (x, y) = (i % self.w as usize, i / self.w as usize);
+                    let rgb = t.apply(rgb, x, y, self.h as usize);
+                    merged = Cell {
+                        fg: Some(color::nearest_256(rgb)),
+                        rgb: Some(rgb),
+                        ..merged
+                    };
+                }
+            }
+            *out = merged;
+        }
+        &self.frame
+    }
+
+    // Write the composed frame with its top-left corner at (x, y). All styling goes through
+    // crossterm so legacy Windows consoles get their native API instead of escape codes.
+    pub fn present(
+        &self,
+        out: &mut impl Write,
+        x: u16,
+        y: u16,
+        colors: ColorLevel,
+    ) -> io::Result<()> {
+        let w = self.w as usize;
+        for (row, cells) in self.frame.chunks(w.max(1)).enumerate() {
+            queue!(out, cursor::MoveTo(x, y + row as u16))?;
+            write_row(out, cells, colors)?;
+        }
+        Ok(())
+    }
+
+    // Plain line-by-line output without cursor movement (pipes and files)
+    pub fn write_lines(&self, out: &mut impl Write, colors: ColorLevel) -> io::Result<()> {
+        for cells in self.frame.chunks(self.w.max(1) as usize) {
+            write_row(out, cells, colors)?;
+            writeln!(out)?;
+        }
+        Ok(())
+    }
+
+    // Stand-ins for the terminal's own foreground and background colors in exported files
+    fn default_colors(&self) -> ((u8, u8, u8), (u8, u8, u8)) {
+        match self.background {
+            Background::Dark => ((229, 229, 229), (0, 0, 0)),
+            Background::Light => ((0, 0, 0), (255, 255, 255)),
+        }
+    }
+
+    // The composed frame drawn with the embedded font, each cell `scale`×`scale` font
+    // pixels: width, height and RGB triples, ready for the PNG writer
+    pub fn rasterize(&self, colors: ColorLevel, scale: usize) -> (u32, u32, Vec<u8>) {
+        let (def_fg, def_bg) = self.default_colors();
+        let (cw, ch) = (font::W * scale, font::H * scale);
+        let (w, h) = (self.w as usize * cw, self.h as usize * ch);
+        let mut rgb = vec![0u8; w * h * 3];
+        for (i, cell) in self.frame.iter().enumerate() {
+            let cell = cell.limit(colors);
+            let mut fg = cell.rgb.or(cell.fg.map(color::rgb_of)).unwrap_or(def_fg);
+            let mut bg = cell.bg.map_or(def_bg, color::rgb_of);
+            if cell.reverse {
+                std::mem::swap(&mut fg, &mut bg);
+            }
+            let glyph = font::glyph(cell.ch);
+            let (x0, y0) = (i % self.w as usize * cw, i / self.w as usize * ch);
+            for y in 0..ch {
+                for x in 0..cw {
+                    let (gx, gy) = (x / scale, y / scale);
+                    let mut cov = font::coverage(&glyph, gx, gy);
+                    // Bold smears each glyph one font pixel to the right
+                    if cell.bold && gx > 0 {
+                        cov = cov.max(font::coverage(&glyph, gx - 1, gy));
+                    }
+                    let t = cov as u32;
+                    let mix = |f: u8, b: u8| ((f as u32 * t + b as u32 * (15 - t) + 7) / 15) as u8;
+                    let at = ((y0 + y) * w + x0 + x) * 3;
+                    rgb[at..at + 3].copy_from_slice(&[
+                        mix(fg.0, bg.0),
+                        mix(fg.1, bg.1),
+                        mix(fg.2, bg.2),
+                    ]);
+                }
+            }
+        }
+        (w as u32, h as u32, rgb)
+    }
+
+    // Standalone HTML page of the composed frame: a <pre> block with one <span> per run of
+    // equally styled cells, colored as the terminal would at `colors`
+    pub fn write_html(&self, out: &mut impl Write, colors: ColorLevel) -> io::Result<()> {
+        let (fg, bg) = self.default_colors();
+        let (page_fg, page_bg) = (&hex(fg), &hex(bg));
+        writeln!(out, "<!DOCTYPE html>")?;
+        writeln!(
+            out,
+            "<html><head><meta charset=\"utf-8\"><title>terminal-fractal</title>"
+        )?;
+        writeln!(
+            out,
+            "<style>pre{{color:{page_fg};background:{page_bg};padding:1em;\
+             font-family:Menlo,Consolas,'DejaVu Sans Mono',monospace;line-height:1.15}}</style>"
+        )?;
+        write!(out, "</head><body><pre>")?;
+        for cells in self.frame.chunks(self.w.max(1) as usize) {
+            let mut style = Cell::BLANK.style();
+            let mut run = String::new();
+            for cell in cells {
+                let cell = cell.limit(colors);
+                if cell.style() != style {
+                    write_span(out, &run, style, (page_fg, page_bg))?;
+                    run.clear();
+                    style = cell.style();
+                }
+                match cell.ch {
+                    '&' => run.push_str("&amp;"),
+                    '<' => run.push_str("&lt;"),
+                    '>' => run.push_str("&gt;"),
+                    ch => run.push(ch),
+                }
+            }
+            write_span(out, &run, style, (page_fg, page_bg))?;
+            writeln!(out)?;
+        }
+        writeln!(out, "</pre></body></html>")
+    }
+}
+
+fn hex((r, g, b): (u8, u8, u8)) -> String {
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+// Escaped `text` in a span carrying `style` (plain when it is the default style)
+fn write_span(
+    out: &mut impl Write,
+    text: &str,
+    (fg, bg, rgb, bold, reverse): Style,
+    (page_fg, page_bg): (&str, &str),
+) -> io::Result<()> {
+    if text.is_empty() {
+        return Ok(());
+    }
+    if (fg, bg, rgb, bold, reverse) == Cell::BLANK.style() {
+        return write!(out, "{text}");
+    }
+    let mut fg = rgb
+        .or(fg.map(color::rgb_of))
+        .map_or(page_fg.to_string(), hex);
+    let mut bg = bg.map_or(page_bg.to_string(), |i| hex(color::rgb_of(i)));
+    if reverse {
+        std::mem::swap(&mut fg, &mut bg);
+    }
+    let weight = if bold { ";font-weight:bold" } else { "" };
+    write!(
+        out,
+        "<span style=\"color:{fg};background:{bg}{weight}\">{text}</span>"
+    )
+}
+
+// One row of cells, switching style only between runs; always ends reset
+fn write_row(out: &mut impl Write, cells: &[Cell], colors: ColorLevel) -> io::Result<()> {
+    let mut style = Cell::BLANK.style();
+    let mut run = String::new();
+    for cell in cells {
+        let cell = &cell.limit(colors);
+        if cell.style() != style {
+            queue!(out, Print(&run))?;
+            run.clear();
+            style = cell.style();
+            apply_style(out, cell, colors)?;
+        }
+        run.push(cell.ch);
+    }
+    queue!(out, Print(&run))?;
+    if style != Cell::BLANK.style() {
+        queue!(out, SetAttribute(Attribute::Reset))?;
+    }
+    Ok(())
+}
+
+fn apply_style(out: &mut impl Write, cell: &Cell, colors: ColorLevel) -> io::Result<()> {
+    queue!(out, SetAttribute(Attribute::Reset))?;
+    let basic = colors == ColorLevel::Ansi16;
+    if let Some((r, g, b)) = cell.rgb {
+        queue!(out, SetForegroundColor(Color::Rgb { r, g, b }))?;
+    } else if let Some(fg) = cell.fg {
+        if basic {
+            queue!(out, Sgr16 { idx: fg, bg: false })?;
+        } else {
+            queue!(out, SetForegroundColor(Color::AnsiValue(fg)))?;
+        }
+    }
+    if let Some(bg) = cell.bg {
+        if basic {
+            queue!(out, Sgr16 { idx: bg, bg: true })?;
+        } else {
+            queue!(out, SetBackgroundColor(Color::AnsiValue(bg)))?;
+        }
+    }
+    if cell.bold {
+        queue!(out, SetAttribute(Attribute::Bold))?;
+    }
+    if cell.reverse {
+        queue!(out, SetAttribute(Attribute::Reverse))?;
+    }
+    Ok(())
+}
+
+// crossterm writes every color as "38;5;N", which 8/16-color terminals don't all parse;
+// this writes the classic 30-37/90-97 (40-47/100-107) codes instead
+struct Sgr16 {
+    idx: u8,
+    bg: bool,
+}
+
+#[cfg(windows)]
+impl Sgr16 {
+    fn color(&self) -> Color {
+        const NAMED: [Color; 16] = [
+            Color::Black,
+            Color::DarkRed,
+            Color::DarkGreen,
+            Color::DarkYellow,
+            Color::DarkBlue,
+            Color::DarkMagenta,
+            Color::DarkCyan,
+            Color::Grey,
+            Color::DarkGrey,
+            Color::Red,
+            Color::Green,
+            Color::Yellow,
+            Color::Blue,
+            Color::Magenta,
+            Color::Cyan,
+            Color::White,
+        ];
+        NAMED[self.idx as usize % 16]
+    }
+}
+
+impl Command for Sgr16 {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        let i = self.idx % 16;
+        let base = if i < 8 { 30 + i } else { 90 + i - 8 };
+        write!(f, "\x1b[{}m", base + if self.bg { 10 } else { 0 })
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> io::Result<()> {
+        if self.bg {
+            SetBackgroundColor(self.color()).execute_winapi()
+        } else {
+            SetForegroundColor(self.color()).execute_winapi()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn layers_merge_by_z_order() {
+        let mut c = Compositor::new(3, 1);
+        let bg = Cell {
+            bg: Some(17),
+            ..Cell::new('#', Some(200))
+        };
+        c.layer(Z::Fractal).put(0, 0, bg);
+        c.layer(Z::Fractal).put(1, 0, bg);
+        c.layer(Z::Overlay).put(0, 0, Cell::new('*', Some(231)));
+        c.layer(Z::Chrome).put(5, 0, Cell::new('x', None)); // clipped
+        let f = c.compose().to_vec();
+        assert_eq!(f[0].ch, '*');
+        assert_eq!(f[0].bg, Some(17)); // inherited from below
+        assert_eq!(f[1], bg);
+        assert_eq!(f[2], Cell::BLANK);
+    }
+    #[test]
+    fn begin_resets_layers() {
+        let mut c = Compositor::new(2, 2);
+        c.layer(Z::Chrome).put(1, 1, Cell::new('x', None));
+        c.begin(2, 2);
+        assert!(c.compose().iter().all(|cell| *cell == Cell::BLANK));
+        c.begin(4, 1);
+        assert_eq!(c.compose().len(), 4);
+    }
+    #[test]
+    fn rasterizes_cells_with_the_font() {
+        let mut c = Compositor::new(2, 1);
+        c.layer(Z::Fractal).put(0, 0, Cell::new('█', Some(196)));
+        c.layer(Z::Fractal)
+            .put(1, 0, Cell::new(' ', None).reverse());
+        c.compose();
+        let (w, h, rgb) = c.rasterize(ColorLevel::Ansi256, 2);
+        assert_eq!((w, h), (2 * font::W as u32 * 2, font::H as u32 * 2));
+        assert_eq!(rgb[..3], [255, 0, 0]);
+        // A reversed blank shows the default foreground
+        let right = (font::W * 2) * 3;
+        assert_eq!(rgb[right..right + 3], [229, 229, 229]);
+    }
+    #[test]
+    fn truecolor_cells_fall_back_to_the_palette() {
+        let mut c = Compositor::new(1, 1);
+        c.layer(Z::Fractal).put(0, 0, Cell::rgb('█', (250, 10, 10)));
+        c.compose();
+        assert_eq!(c.rasterize(ColorLevel::TrueColor, 1).2[..3], [250, 10, 10]);
+        assert_eq!(c.rasterize(ColorLevel::Ansi256, 1).2[..3], [255, 0, 0]);
+        let mut out = Vec::new();
+        c.present(&mut out, 0, 0, ColorLevel::TrueColor).unwrap();
+        assert!(String::from_utf8_lossy(&out).contains("38;2;250;10;10"));
+    }
+    #[test]
+    fn light_backgrounds_darken_truecolor_cells() {
+        let mut c = Compositor::new(2, 1);
+        c.set_background(Background::Light);
+        c.layer(Z::Fractal)
+            .put(0, 0, Cell::rgb('█', (255, 255, 200)));
+        c.layer(Z::Fractal).put(1, 0, Cell::rgb('█', (60, 20, 20)));
+        let f = c.compose().to_vec();
+        let (r, g, b) = f[0].rgb.unwrap();
+        // Same hue, below the luma ceiling of palette colors
+        assert!(r == g && g > b);
+        assert!(0.2126 * r as f64 + 0.7152 * g as f64 + 0.0722 * b as f64 <= 0.4 * 255.0);
+        assert_eq!(f[1].rgb, Some((60, 20, 20)));
+    }
+    #[test]
+    fn html_keeps_colors_and_escapes() {
+        let mut c = Compositor::new(3, 1);
+        c.layer(Z::Fractal).put(0, 0, Cell::new('<', Some(196)));
+        c.layer(Z::Fractal).put(1, 0, Cell::new('&', Some(196)));
+        c.compose();
+        let mut page = Vec::new();
+        c.write_html(&mut page, ColorLevel::Ansi256).unwrap();
+        let page = String::from_utf8(page).unwrap();
+        assert!(
+            page.contains(
+                "<pre><span style=\"color:#ff0000;background:#000000\">&lt;&amp;</span> \n"
+            )
+        );
+        let mut mono = Vec::new();
+        c.write_html(&mut mono, ColorLevel::Mono).unwrap();
+        assert!(
+            String::from_utf8(mono)
+                .unwrap()
+                .contains("<pre>&lt;&amp; \n")
+        );
+    }
+}
@@ -0,0 +1,82 @@
+// Scrollable log of recent events; the newest entry doubles as a short-lived HUD toast
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+const CAPACITY: usize = 200;
+const TOAST_TIME: Duration = Duration::from_secs(3);
+
+pub struct Entry {
+    pub at: Duration,
+    pub text: String,
+}
+
+pub struct Console {
+    start: Instant,
+    last: Option<Instant>,
+    pub entries: VecDeque<Entry>,
+    // Lines scrolled back from the newest entry
+    pub scroll: usize,
+}
+
+impl Console {
+    pub fn new(start: Instant) -> Self {
+        Console {
+            start,
+            last: None,
+            entries: VecDeque::new(),
+            scroll: 0,
+        }
+    }
+
+    pub fn push(&mut self, text: impl Into<String>) {
+        let now = Instant::now();
+        if self.entries.len() == CAPACITY {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(Entry {
+            at: now - self.start,
+            text: text.into(),
+        });
+        self.last = Some(now);
+    }
+
+    pub fn scroll_by(&mut self, delta: isize, rows: usize) {
+        let max = self.entries.len().saturating_sub(rows);
+        self.scroll = self.scroll.saturating_add_signed(delta).min(max);
+    }
+
+    // Entries that fit in `rows` lines at the current scroll position, oldest first
+    pub fn window(&self, rows: usize) -> impl Iterator<Item = &Entry> {
+        let end = self.entries.len().saturating_sub(self.scroll);
+        let start = end.saturating_sub(rows);
+        self.entries.range(start..end)
+    }
+
+    // Latest message while it is still fresh
+    pub fn toast(&self) -> Option<&str> {
+        match (self.last, self.entries.back()) {
+            (Some(t), Some(e)) if t.elapsed() < TOAST_TIME => Some(&e.text),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn scrolls_within_bounds() {
+        let mut c = Console::new(Instant::now());
+        for i in 0..10 {
+            c.push(format!("event {i}"));
+        }
+        let last: Vec<_> = c.window(3).map(|e| e.text.as_str()).collect();
+        assert_eq!(last, ["event 7", "event 8", "event 9"]);
+        c.scroll_by(100, 3);
+        assert_eq!(c.scroll, 7);
+        assert_eq!(c.window(3).next().unwrap().text, "event 0");
+        c.scroll_by(-100, 3);
+        assert_eq!(c.scroll, 0);
+        assert_eq!(c.toast(), Some("event 9"));
+    }
+}
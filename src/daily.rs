@@ -0,0 +1,75 @@
+// Seeded starts: one number picks the starting c, the palette and the wander's random
+// stream. `--daily` uses today's date as YYYYMMDD, so everyone gets the same fractal.
+use crate::color::Palette;
+use crate::gallery::cardioid_point;
+use num_complex::Complex64;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub struct Seeded {
+    pub seed: u64,
+    pub c: Complex64,
+    pub palette: Palette,
+    // Nonzero state for the wander's xorshift generator
+    pub rng: u64,
+}
+
+// SplitMix64 step: decorrelates nearby seeds like consecutive dates
+fn mix(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+pub fn from_seed(seed: u64) -> Seeded {
+    let a = mix(seed);
+    let b = mix(a);
+    let t = (a >> 11) as f64 / (1u64 << 53) as f64;
+    Seeded {
+        seed,
+        c: cardioid_point(t),
+        palette: Palette::ALL[(b % Palette::ALL.len() as u64) as usize],
+        rng: mix(b) | 1,
+    }
+}
+
+// Today's UTC date as YYYYMMDD
+pub fn today() -> u64 {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let (y, m, d) = civil_from_days((secs / 86_400) as i64);
+    (y * 10_000 + m * 100 + d) as u64
+}
+
+// Gregorian date of a day count since 1970-01-01 (Howard Hinnant's algorithm)
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + if m <= 2 { 1 } else { 0 };
+    (y, m, d)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn converts_dates() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+        assert_eq!(civil_from_days(20_740), (2026, 10, 14));
+    }
+    #[test]
+    fn seeds_are_reproducible() {
+        let (a, b) = (from_seed(20261014), from_seed(20261015));
+        assert_eq!(a.c, from_seed(20261014).c);
+        assert_ne!(a.c, b.c);
+        assert!(a.rng != 0 && a.c.norm() < 1.0);
+    }
+}
@@ -0,0 +1,145 @@
+// Box-counting dimension of the boundary in view: the view is sampled on a square-pixel
+// grid, pixels where escaping and non-escaping points meet form the boundary, and the
+// slope of log(boxes touching it) against log(1 / box size) estimates the dimension.
+use crate::layout::Pane;
+use num_complex::Complex64;
+use std::time::{Duration, Instant};
+
+// Long side of the sample grid in pixels
+const GRID: usize = 256;
+// Smallest number of box sizes worth fitting a line through
+const MIN_SIZES: usize = 3;
+// Recompute at most this often
+const INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Estimate {
+    pub dim: f64,
+    // Goodness of the straight-line fit (1 = perfectly self-similar over the sizes used)
+    pub r2: f64,
+    // Largest box size in pixels
+    pub max_box: usize,
+}
+
+// Throttled estimate for the HUD
+#[derive(Default)]
+pub struct Meter {
+    last: Option<Instant>,
+    pub value: Option<Estimate>,
+}
+
+impl Meter {
+    // Refresh from `pane` (w×h cells on screen) unless the last estimate is still recent
+    pub fn update(
+        &mut self,
+        now: Instant,
+        pane: &Pane,
+        c: Complex64,
+        max_iters: usize,
+        w: usize,
+        h: usize,
+    ) {
+        if self
+            .last
+            .is_some_and(|t| now.saturating_duration_since(t) < INTERVAL)
+        {
+            return;
+        }
+        self.last = Some(now);
+        // Cells are twice as tall as wide, so w×h cells cover w×2h square pixels
+        let aspect = (h * 2) as f64 / w.max(1) as f64;
+        let (gw, gh) = if aspect <= 1.0 {
+            (GRID, ((GRID as f64 * aspect).round() as usize).max(1))
+        } else {
+            (((GRID as f64 / aspect).round() as usize).max(1), GRID)
+        };
+        self.value = estimate(&boundary(pane, c, max_iters, gw, gh), gw, gh);
+    }
+}
+
+// Pixels of a gw×gh grid over the view that escape while a neighbour doesn't, or the reverse
+pub fn boundary(pane: &Pane, c: Complex64, max_iters: usize, gw: usize, gh: usize) -> Vec<bool> {
+    let c = pane.param(c);
+    let inside: Vec<bool> = (0..gw * gh)
+        .map(|i| {
+            let p = pane
+                .view
+                .pixel((i % gw) as f64 + 0.5, (i / gw) as f64 + 0.5, gw, gh);
+            pane.fractal.escape(p, c, max_iters) >= max_iters
+        })
+        .collect();
+    (0..gw * gh)
+        .map(|i| {
+            let (x, y) = (i % gw, i / gw);
+            let differs = |nx: usize, ny: usize| inside[ny * gw + nx] != inside[i];
+            (x + 1 < gw && differs(x + 1, y)) || (y + 1 < gh && differs(x, y + 1))
+        })
+        .collect()
+}
+
+// Least-squares slope over box sizes 1, 2, 4, ... up to a quarter of the short side
+pub fn estimate(mask: &[bool], w: usize, h: usize) -> Option<Estimate> {
+    let mut points = Vec::new();
+    let mut size = 1;
+    while size * 4 <= w.min(h) {
+        let boxes = count_boxes(mask, w, h, size);
+        if boxes == 0 {
+            return None;
+        }
+        points.push(((1.0 / size as f64).ln(), (boxes as f64).ln()));
+        size *= 2;
+    }
+    if points.len() < MIN_SIZES {
+        return None;
+    }
+    let n = points.len() as f64;
+    let mx = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let my = points.iter().map(|p| p.1).sum::<f64>() / n;
+    let sxy: f64 = points.iter().map(|p| (p.0 - mx) * (p.1 - my)).sum();
+    let sxx: f64 = points.iter().map(|p| (p.0 - mx).powi(2)).sum();
+    let syy: f64 = points.iter().map(|p| (p.1 - my).powi(2)).sum();
+    let r2 = if syy > 0.0 {
+        sxy * sxy / (sxx * syy)
+    } else {
+        1.0
+    };
+    Some(Estimate {
+        dim: sxy / sxx,
+        r2,
+        max_box: size / 2,
+    })
+}
+
+// Boxes of size×size pixels (the last row and column may be partial) holding any boundary
+fn count_boxes(mask: &[bool], w: usize, h: usize, size: usize) -> usize {
+    let (bw, bh) = (w.div_ceil(size), h.div_ceil(size));
+    let mut hit = vec![false; bw * bh];
+    for (i, _) in mask.iter().enumerate().filter(|(_, on)| **on) {
+        hit[(i / w / size) * bw + i % w / size] = true;
+    }
+    hit.iter().filter(|&&b| b).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fractal::Fractal;
+    #[test]
+    fn lines_and_areas_have_whole_dimensions() {
+        let (w, h) = (64, 64);
+        let line: Vec<bool> = (0..w * h).map(|i| i / w == 20).collect();
+        assert!((estimate(&line, w, h).unwrap().dim - 1.0).abs() < 1e-9);
+        let area = vec![true; w * h];
+        assert!((estimate(&area, w, h).unwrap().dim - 2.0).abs() < 1e-9);
+        assert_eq!(estimate(&vec![false; w * h], w, h), None);
+        assert_eq!(estimate(&[true; 64], 8, 8), None);
+    }
+    #[test]
+    fn mandelbrot_boundary_is_rough() {
+        let pane = Pane::new(Fractal::Mandelbrot);
+        let mask = boundary(&pane, Complex64::new(0.0, 0.0), 200, 192, 128);
+        let e = estimate(&mask, 192, 128).unwrap();
+        // Between a smooth curve and a filled area at this resolution
+        assert!(e.dim > 1.05 && e.dim < 1.9, "{e:?}");
+    }
+}
@@ -0,0 +1,221 @@
+// Finding interesting places to look: the focused view is sampled cell by cell and split into
+// tiles scored by the Shannon entropy of their escape counts. Flat tiles (all interior, or
+// one smooth exterior band) score near zero; tiles mixing many counts hold the detail.
+// A Sobel filter over the smooth escape count then weighs tiles by how much boundary
+// filament they hold, and aims inside the tile at its strongest edge.
+use crate::fractal::Viewport;
+use crate::layout::Pane;
+use num_complex::Complex64;
+
+// Tile size in cells; 8x4 is square on screen with the 2:1 cell aspect
+const TILE: (usize, usize) = (8, 4);
+// Tiles below this many bits of entropy have nothing to zoom in on
+const MIN_SCORE: f64 = 1.0;
+// Zoom factor and duration of one autopilot leg
+const LEG_ZOOM: f64 = 4.0;
+const LEG_SECS: f64 = 3.0;
+
+// The view to fly to next from `pane` at w×h cells, with the chosen tile's score in bits
+pub fn find_target(
+    pane: &Pane,
+    c: Complex64,
+    max_iters: usize,
+    w: usize,
+    h: usize,
+) -> Option<(Viewport, f64)> {
+    let field = Field::sample(pane, c, max_iters, w, h);
+    let tile = best(&score_tiles(&field, &sobel(&field)))?;
+    Some((jump(&pane.view, tile, w, h), tile.score))
+}
+
+// Escape counts of a view at w×h cells: whole bands for the entropy, and the log of the
+// smooth count for edges (log, so the few slow points near the set don't drown the rest)
+struct Field {
+    w: usize,
+    h: usize,
+    iters: Vec<usize>,
+    level: Vec<f64>,
+}
+
+impl Field {
+    fn sample(pane: &Pane, c: Complex64, max_iters: usize, w: usize, h: usize) -> Field {
+        let c = pane.param(c);
+        let smooth: Vec<f64> = (0..w * h)
+            .map(|i| {
+                let p = pane.view.point(i % w, i / w, w, h);
+                pane.fractal.escape_smooth(p, c, max_iters)
+            })
+            .collect();
+        Field {
+            w,
+            h,
+            iters: smooth.iter().map(|&v| v as usize).collect(),
+            level: smooth.iter().map(|&v| v.ln_1p()).collect(),
+        }
+    }
+}
+
+// Sobel gradient magnitude of the level field; zero on the outermost cells
+fn sobel(field: &Field) -> Vec<f64> {
+    let (w, h) = (field.w, field.h);
+    let mut out = vec![0.0; w * h];
+    for y in 1..h.saturating_sub(1) {
+        for x in 1..w.saturating_sub(1) {
+            let v = |dx: usize, dy: usize| field.level[(y + dy - 1) * w + x + dx - 1];
+            let gx = v(2, 0) + 2.0 * v(2, 1) + v(2, 2) - v(0, 0) - 2.0 * v(0, 1) - v(0, 2);
+            let gy = v(0, 2) + 2.0 * v(1, 2) + v(2, 2) - v(0, 0) - 2.0 * v(1, 0) - v(2, 0);
+            out[y * w + x] = gx.hypot(gy);
+        }
+    }
+    out
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Tile {
+    // Strongest edge cell of the tile (its center when it has no edges)
+    x: usize,
+    y: usize,
+    // Entropy in bits, and the mean edge strength
+    score: f64,
+    edge: f64,
+}
+
+fn score_tiles(field: &Field, edges: &[f64]) -> Vec<Tile> {
+    let (tw, th) = TILE;
+    let mut tiles = Vec::new();
+    for ty in 0..field.h / th {
+        for tx in 0..field.w / tw {
+            let cells: Vec<usize> = (0..th)
+                .flat_map(|dy| (0..tw).map(move |dx| (ty * th + dy) * field.w + tx * tw + dx))
+                .collect();
+            let mut counts: Vec<usize> = cells.iter().map(|&i| field.iters[i]).collect();
+            counts.sort_unstable();
+            let strongest = cells
+                .iter()
+                .copied()
+                .filter(|&i| edges[i] > 0.0)
+                .max_by(|&a, &b| edges[a].total_cmp(&edges[b]));
+            let at = strongest.unwrap_or((ty * th + th / 2) * field.w + tx * tw + tw / 2);
+            tiles.push(Tile {
+                x: at % field.w,
+                y: at / field.w,
+                score: entropy(&counts),
+                edge: cells.iter().map(|&i| edges[i]).sum::<f64>() / cells.len() as f64,
+            });
+        }
+    }
+    tiles
+}
+
+// Most interesting tile, if any has detail at all: entropy, weighted up to double by the
+// tile's share of the strongest edges in view
+fn best(tiles: &[Tile]) -> Option<Tile> {
+    let top_edge = tiles.iter().map(|t| t.edge).fold(0.0, f64::max).max(1e-12);
+    let weight = |t: &Tile| t.score * (1.0 + t.edge / top_edge);
+    tiles
+        .iter()
+        .copied()
+        .filter(|t| t.score >= MIN_SCORE)
+        .max_by(|a, b| weight(a).total_cmp(&weight(b)))
+}
+
+// Entropy in bits of sorted samples
+fn entropy(sorted: &[usize]) -> f64 {
+    let n = sorted.len() as f64;
+    sorted
+        .chunk_by(|a, b| a == b)
+        .map(|run| {
+            let p = run.len() as f64 / n;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+// Where a jump to `tile` of a w×h view lands: centered on it, LEG_ZOOM times closer
+fn jump(view: &Viewport, tile: Tile, w: usize, h: usize) -> Viewport {
+    Viewport {
+        center: view.point(tile.x, tile.y, w, h),
+        zoom: view.zoom * LEG_ZOOM,
+    }
+}
+
+// Smooth flight from one view to the next: the center eases over, the zoom grows at a steady
+// rate (interpolated in log space so it doesn't seem to speed up)
+pub struct Autopilot {
+    from: Viewport,
+    to: Viewport,
+    t: f64,
+}
+
+impl Autopilot {
+    pub fn new(from: Viewport, to: Viewport) -> Self {
+        Autopilot { from, to, t: 0.0 }
+    }
+
+    // View after another `dt` seconds, and whether the leg is over
+    pub fn advance(&mut self, dt: f64) -> (Viewport, bool) {
+        self.t = (self.t + dt / LEG_SECS).min(1.0);
+        let ease = self.t * self.t * (3.0 - 2.0 * self.t);
+        let view = Viewport {
+            center: self.from.center + (self.to.center - self.from.center) * ease,
+            zoom: self.from.zoom * (self.to.zoom / self.from.zoom).powf(self.t),
+        };
+        (view, self.t >= 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fractal::Fractal;
+    #[test]
+    fn entropy_finds_detail() {
+        assert_eq!(entropy(&[5; 32]), 0.0);
+        assert_eq!(entropy(&[1, 1, 2, 2]), 1.0);
+        // Left half flat, right half noisy
+        let (w, h) = (16, 4);
+        let iters: Vec<usize> = (0..w * h)
+            .map(|i| if i % w < 8 { 3 } else { i * 7 % 13 })
+            .collect();
+        let level = iters.iter().map(|&v| v as f64).collect();
+        let field = Field { w, h, iters, level };
+        let tiles = score_tiles(&field, &vec![0.0; w * h]);
+        assert_eq!(tiles.len(), 2);
+        assert_eq!(tiles[0].score, 0.0);
+        assert_eq!(best(&tiles).map(|t| (t.x, t.y)), Some((12, 2)));
+        // The whole Mandelbrot set has a boundary worth zooming in on
+        let pane = Pane::new(Fractal::Mandelbrot);
+        let (to, _) = find_target(&pane, Complex64::default(), 100, 80, 24).unwrap();
+        assert_eq!(to.zoom, LEG_ZOOM);
+    }
+    #[test]
+    fn edges_steer_the_target() {
+        // A step from 0 to 4 between columns 11 and 12
+        let (w, h) = (16, 8);
+        let level: Vec<f64> = (0..w * h)
+            .map(|i| if i % w < 12 { 0.0 } else { 4.0 })
+            .collect();
+        let iters = (0..w * h).map(|i| i % 5).collect();
+        let field = Field { w, h, iters, level };
+        let edges = sobel(&field);
+        assert_eq!(edges[3 * w + 5], 0.0);
+        assert_eq!(edges[3 * w + 11], 16.0);
+        // Equal entropy everywhere: the tiles on the edge win, aimed at the edge itself
+        let t = best(&score_tiles(&field, &edges)).unwrap();
+        assert!((11..=12).contains(&t.x), "{t:?}");
+    }
+    #[test]
+    fn autopilot_flies_in_log_space() {
+        let from = Viewport::new(Complex64::new(0.0, 0.0));
+        let to = Viewport {
+            center: Complex64::new(1.0, 1.0),
+            zoom: 4.0,
+        };
+        let mut ap = Autopilot::new(from, to);
+        let (mid, done) = ap.advance(LEG_SECS / 2.0);
+        assert!(!done);
+        assert!((mid.zoom - 2.0).abs() < 1e-9);
+        assert!((mid.center - Complex64::new(0.5, 0.5)).norm() < 1e-9);
+        assert_eq!(ap.advance(LEG_SECS), (to, true));
+    }
+}
@@ -0,0 +1,176 @@
+// High-resolution PNG export of a view, independent of the terminal size. Renders with
+// square pixels and supersampling in background threads while the session keeps running.
+use crate::color::{self, Palette};
+use crate::layout::Pane;
+use crate::png;
+use num_complex::Complex64;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread::JoinHandle;
+
+pub const DEFAULT_SIZE: (u32, u32) = (3840, 2160);
+// Samples per pixel along each axis
+const SUPERSAMPLE: usize = 3;
+// Images larger than this on a side are almost certainly a typo
+const MAX_SIDE: u32 = 16384;
+
+pub struct Export {
+    pub path: String,
+    pub size: (u32, u32),
+    rows_done: Arc<AtomicUsize>,
+    handle: Option<JoinHandle<Result<(), String>>>,
+}
+
+impl Export {
+    // Renders `pane` (with Julia parameter `c`) and writes the PNG to `path`
+    pub fn start(
+        pane: Pane,
+        c: Complex64,
+        max_iters: usize,
+        size: (u32, u32),
+        path: String,
+    ) -> Self {
+        let rows_done = Arc::new(AtomicUsize::new(0));
+        let handle = {
+            let (rows_done, path) = (rows_done.clone(), path.clone());
+            std::thread::spawn(move || {
+                let (w, h) = size;
+                let rgb = render(&pane, c, max_iters, w as usize, h as usize, &rows_done);
+                std::fs::write(&path, png::encode_rgb(w, h, &rgb)).map_err(|e| e.to_string())
+            })
+        };
+        Export {
+            path,
+            size,
+            rows_done,
+            handle: Some(handle),
+        }
+    }
+
+    // Fraction of rows rendered so far (0..1)
+    pub fn progress(&self) -> f64 {
+        self.rows_done.load(Ordering::Relaxed) as f64 / self.size.1.max(1) as f64
+    }
+
+    // The outcome once the file is written (or failed to be), None while still working
+    pub fn poll(&mut self) -> Option<Result<(), String>> {
+        if !self.handle.as_ref()?.is_finished() {
+            return None;
+        }
+        let result = self.handle.take()?.join();
+        Some(result.unwrap_or_else(|_| Err("render thread panicked".to_string())))
+    }
+}
+
+// RGB triples of a w×h image of the pane's view, counting finished rows in `rows_done`
+pub fn render(
+    pane: &Pane,
+    c: Complex64,
+    max_iters: usize,
+    w: usize,
+    h: usize,
+    rows_done: &AtomicUsize,
+) -> Vec<u8> {
+    let c = pane.param(c);
+    let count = |x: f64, y: f64| {
+        let p = pane.pixel(x, y, w, h);
+        pane.fractal.escape_smooth(p, c, max_iters)
+    };
+    render_with(pane.palette, max_iters, w, h, rows_done, count)
+}
+
+// Same with the smooth escape count at fractional pixel (x, y) given by `count`, for other
+// kernels. Rows are shared out to all but one core so the interactive view stays smooth.
+pub fn render_with(
+    palette: Palette,
+    max_iters: usize,
+    w: usize,
+    h: usize,
+    rows_done: &AtomicUsize,
+    count: impl Fn(f64, f64) -> f64 + Sync,
+) -> Vec<u8> {
+    let workers = std::thread::available_parallelism()
+        .map_or(1, |n| n.get().saturating_sub(1))
+        .max(1);
+    let mut rgb = vec![0u8; w * h * 3];
+    let rows: Vec<(usize, &mut [u8])> = rgb.chunks_mut((w * 3).max(1)).enumerate().collect();
+    let rows = std::sync::Mutex::new(rows);
+    std::thread::scope(|s| {
+        for _ in 0..workers {
+            s.spawn(|| {
+                while let Some((y, row)) = rows.lock().ok().and_then(|mut r| r.pop()) {
+                    for x in 0..w {
+                        let px = pixel(palette, max_iters, x, y, &count);
+                        row[x * 3..x * 3 + 3].copy_from_slice(&px);
+                    }
+                    rows_done.fetch_add(1, Ordering::Relaxed);
+                }
+            });
+        }
+    });
+    rgb
+}
+
+// Average color of SUPERSAMPLE² points spread over pixel (x, y); the interior is black
+fn pixel(
+    palette: Palette,
+    max_iters: usize,
+    x: usize,
+    y: usize,
+    count: impl Fn(f64, f64) -> f64,
+) -> [u8; 3] {
+    let n = SUPERSAMPLE;
+    let mut sum = [0u32; 3];
+    for sy in 0..n {
+        for sx in 0..n {
+            let fx = x as f64 + (sx as f64 + 0.5) / n as f64;
+            let fy = y as f64 + (sy as f64 + 0.5) / n as f64;
+            let nu = count(fx, fy);
+            if nu >= max_iters as f64 {
+                continue;
+            }
+            let (r, g, b) = color::rgb_of(palette.color(nu / max_iters as f64));
+            sum[0] += r as u32;
+            sum[1] += g as u32;
+            sum[2] += b as u32;
+        }
+    }
+    let count = (n * n) as u32;
+    sum.map(|v| ((v + count / 2) / count) as u8)
+}
+
+// "3840x2160"
+pub fn parse_size(s: &str) -> Result<(u32, u32), String> {
+    let bad = || format!("invalid size '{s}' (expected WxH, e.g. 3840x2160)");
+    let (w, h) = s.split_once(['x', 'X']).ok_or_else(bad)?;
+    match (w.trim().parse::<u32>(), h.trim().parse::<u32>()) {
+        (Ok(w), Ok(h)) if (1..=MAX_SIDE).contains(&w) && (1..=MAX_SIDE).contains(&h) => Ok((w, h)),
+        _ => Err(bad()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fractal::Fractal;
+    #[test]
+    fn renders_square_pixels() {
+        let pane = Pane::new(Fractal::Mandelbrot);
+        let done = AtomicUsize::new(0);
+        let rgb = render(&pane, Complex64::new(0.0, 0.0), 50, 30, 20, &done);
+        assert_eq!(rgb.len(), 30 * 20 * 3);
+        assert_eq!(done.load(Ordering::Relaxed), 20);
+        // The center (-0.5, 0) is interior, the corners escape quickly
+        let at = |x: usize, y: usize| &rgb[(y * 30 + x) * 3..(y * 30 + x) * 3 + 3];
+        assert_eq!(at(15, 10), [0, 0, 0]);
+        assert_ne!(at(0, 0), [0, 0, 0]);
+    }
+    #[test]
+    fn parses_sizes() {
+        assert_eq!(parse_size("3840x2160"), Ok((3840, 2160)));
+        assert_eq!(parse_size("640X480"), Ok((640, 480)));
+        assert!(parse_size("0x10").is_err());
+        assert!(parse_size("100000x10").is_err());
+        assert!(parse_size("4k").is_err());
+    }
+}
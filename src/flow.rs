@@ -0,0 +1,167 @@
+// Particles drifting down the gradient of the escape potential G = log|z_n| / 2^n. Its
+// field lines are the external rays, so the particles stream in toward the set from all
+// sides and bunch up along the boundary. They live in plane coordinates, so they stay put
+// while the view pans; ones that reach the set, leave the view or grow old start over.
+use crate::compositor::{Cell, Layer};
+use crate::fractal::Fractal;
+use crate::layout::{Pane, Rect};
+use num_complex::Complex64;
+
+// Cells of pane area per particle, and a cap for big terminals
+const AREA_PER: usize = 40;
+const MAX_PARTICLES: usize = 400;
+// Speed in cell widths per second
+const SPEED: f64 = 6.0;
+// Lifetimes are spread over this range so respawns don't come in waves
+const LIFE: (f64, f64) = (2.0, 6.0);
+// Cells of trail behind each particle
+const TRAIL: usize = 3;
+const BAILOUT: f64 = 1e4;
+
+// Downhill direction of the potential at `p` as a unit vector, None inside the set (or
+// for points that don't escape within `max_iters`). G is the real part of the analytic
+// log(z_n) / 2^n, so its gradient is the conjugate of z_n' / z_n (scaled, unneeded here).
+pub fn downhill(
+    fractal: Fractal,
+    p: Complex64,
+    c: Complex64,
+    max_iters: usize,
+) -> Option<Complex64> {
+    let (mut z, mut dz, c, plus) = match fractal {
+        Fractal::Julia => (p, Complex64::new(1.0, 0.0), c, 0.0),
+        Fractal::Mandelbrot => (Complex64::new(0.0, 0.0), Complex64::new(0.0, 0.0), p, 1.0),
+    };
+    for _ in 0..max_iters {
+        if z.norm_sqr() > BAILOUT * BAILOUT {
+            let g = -(dz / z).conj();
+            let n = g.norm();
+            return (n > 0.0 && n.is_finite()).then(|| g / n);
+        }
+        dz = z * dz * 2.0 + plus;
+        z = z * z + c;
+    }
+    None
+}
+
+struct Particle {
+    pos: Complex64,
+    age: f64,
+    life: f64,
+    // Recent cells, newest first
+    trail: Vec<(u16, u16)>,
+}
+
+pub struct Flow {
+    particles: Vec<Particle>,
+    rng: u64,
+}
+
+impl Flow {
+    pub fn new(seed: u64) -> Self {
+        Flow {
+            particles: Vec::new(),
+            rng: seed | 1,
+        }
+    }
+
+    // Uniform in [0, 1)
+    fn next(&mut self) -> f64 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        (self.rng >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn spawn(&mut self, pane: &Pane, w: usize, h: usize) -> Particle {
+        let (x, y) = (self.next() * w as f64, self.next() * h as f64);
+        let life = LIFE.0 + self.next() * (LIFE.1 - LIFE.0);
+        Particle {
+            pos: pane.point(x as usize, y as usize, w, h),
+            age: 0.0,
+            life,
+            trail: Vec::new(),
+        }
+    }
+
+    // Advance every particle by `dt` seconds over `pane` shown at w×h cells
+    pub fn step(
+        &mut self,
+        pane: &Pane,
+        c: Complex64,
+        max_iters: usize,
+        w: usize,
+        h: usize,
+        dt: f64,
+    ) {
+        let want = (w * h / AREA_PER).min(MAX_PARTICLES);
+        self.particles.truncate(want);
+        while self.particles.len() < want {
+            let p = self.spawn(pane, w, h);
+            self.particles.push(p);
+        }
+        let c = pane.param(c);
+        let step = SPEED * pane.view.cell_size(w, h) * dt;
+        for i in 0..self.particles.len() {
+            let p = &mut self.particles[i];
+            p.age += dt;
+            let dir = downhill(pane.fractal, p.pos, c, max_iters);
+            let (x, y) = pane.cell_of(p.pos, w, h);
+            let inside = x >= -0.5 && y >= -0.5 && x < w as f64 - 0.5 && y < h as f64 - 0.5;
+            match dir {
+                Some(d) if inside && p.age < p.life => {
+                    p.pos += d * step;
+                    let cell = (x.round() as u16, y.round() as u16);
+                    if p.trail.first() != Some(&cell) {
+                        p.trail.insert(0, cell);
+                        p.trail.truncate(TRAIL + 1);
+                    }
+                }
+                _ => self.particles[i] = self.spawn(pane, w, h),
+            }
+        }
+    }
+
+    // Bright heads with trails fading to gray
+    pub fn draw(&self, layer: &mut Layer, rect: Rect) {
+        const SHADES: [u8; TRAIL + 1] = [231, 250, 244, 239];
+        for p in &self.particles {
+            for (k, &(x, y)) in p.trail.iter().enumerate().rev() {
+                if x < rect.w && y < rect.h {
+                    let cell = if k == 0 {
+                        Cell::new('•', Some(SHADES[0])).bold()
+                    } else {
+                        Cell::new('·', Some(SHADES[k]))
+                    };
+                    layer.put(rect.x + x, rect.y + y, cell);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn flows_toward_the_set() {
+        let zero = Complex64::new(0.0, 0.0);
+        // Far out on the real axis the Mandelbrot potential falls straight toward the origin
+        let d = downhill(Fractal::Mandelbrot, Complex64::new(3.0, 0.0), zero, 100).unwrap();
+        assert!((d.re + 1.0).abs() < 1e-6 && d.im.abs() < 1e-6, "{d}");
+        let d = downhill(Fractal::Julia, Complex64::new(0.0, 2.5), zero, 100).unwrap();
+        assert!((d.im + 1.0).abs() < 1e-6, "{d}");
+        assert_eq!(downhill(Fractal::Mandelbrot, zero, zero, 100), None);
+    }
+    #[test]
+    fn keeps_particles_in_view() {
+        let pane = Pane::new(Fractal::Mandelbrot);
+        let mut flow = Flow::new(42);
+        for _ in 0..100 {
+            flow.step(&pane, Complex64::new(0.0, 0.0), 100, 80, 24, 0.05);
+        }
+        assert_eq!(flow.particles.len(), 80 * 24 / AREA_PER);
+        for p in &flow.particles {
+            assert!(p.trail.iter().all(|&(x, y)| x < 80 && y < 24));
+        }
+    }
+}
@@ -0,0 +1,197 @@
+// Embedded 8x16 monospace font for rasterizing frames to images: antialiased glyphs baked
+// from DejaVu Sans Mono (Bitstream Vera license, see LICENSE-FONT), 4 bits of coverage per
+// pixel. Block and box-drawing characters are generated instead so they tile without seams.
+
+pub const W: usize = 8;
+pub const H: usize = 16;
+
+// One row per u32, leftmost pixel in the top nibble; coverage 0 (empty) ..= 15 (solid)
+pub type Glyph = [u32; H];
+
+// Coverage 0..=15 of pixel (x, y) of a glyph
+pub fn coverage(g: &Glyph, x: usize, y: usize) -> u8 {
+    ((g[y] >> (4 * (W - 1 - x))) & 15) as u8
+}
+
+// Glyph for `ch`; characters the font doesn't have get an empty box
+pub fn glyph(ch: char) -> Glyph {
+    if let Some(g) = drawn(ch) {
+        return g;
+    }
+    match GLYPHS.binary_search_by_key(&ch, |&(c, _)| c) {
+        Ok(i) => GLYPHS[i].1,
+        Err(_) if ch.is_whitespace() => [0; H],
+        Err(_) => tofu(),
+    }
+}
+
+// Block elements, shades and single box-drawing lines
+fn drawn(ch: char) -> Option<Glyph> {
+    let row = |on: u32| -> u32 { (0..W).fold(0, |r, x| r | on << (4 * x)) };
+    let rows = |f: &dyn Fn(usize) -> u32| -> Glyph { std::array::from_fn(f) };
+    let center_col = 15 << (4 * (W - 1 - W / 2));
+    let mid = H / 2;
+    Some(match ch {
+        // ▁..█: bottom eighths
+        '\u{2581}'..='\u{2588}' => {
+            let n = (ch as usize - 0x2580) * H / 8;
+            rows(&|y| if y >= H - n { row(15) } else { 0 })
+        }
+        '░' => [row(4); H],
+        '▒' => [row(8); H],
+        '▓' => [row(12); H],
+        '│' => [center_col; H],
+        '─' => rows(&|y| if y == mid { row(15) } else { 0 }),
+        '┌' | '┐' | '└' | '┘' => {
+            // Half of the horizontal line toward the open side, half of the vertical one
+            let left = matches!(ch, '┐' | '┘');
+            let half = (0..W)
+                .filter(|&x| if left { x <= W / 2 } else { x >= W / 2 })
+                .fold(0, |r, x| r | 15 << (4 * (W - 1 - x)));
+            let down = matches!(ch, '┌' | '┐');
+            rows(&|y| match y {
+                y if y == mid => half,
+                y if (y > mid) == down => center_col,
+                _ => 0,
+            })
+        }
+        _ => return None,
+    })
+}
+
+// Hollow rectangle for characters without a glyph
+fn tofu() -> Glyph {
+    let side = 15 << (4 * (W - 2)) | 15 << 4;
+    std::array::from_fn(|y| match y {
+        2 | 13 => 0x0ffffff0,
+        3..=12 => side,
+        _ => 0,
+    })
+}
+
+// Sorted by character for binary search
+#[rustfmt::skip]
+const GLYPHS: &[(char, Glyph)] = &[
+    ('!', [0x00000000, 0x00000000, 0x00000000, 0x000cc000, 0x000cc000, 0x000cc000, 0x000cc000, 0x000ab000, 0x00088000, 0x00044000, 0x00000000, 0x000cc000, 0x00066000, 0x00000000, 0x00000000, 0x00000000]),
+    ('"', [0x00000000, 0x00000000, 0x00000000, 0x00c44c00, 0x00c44c00, 0x00c44c00, 0x00622600, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000]),
+    ('#', [0x00000000, 0x00000000, 0x00000000, 0x000b51f0, 0x000f15b0, 0x146e4aa4, 0x3ceecfcc, 0x00b51f00, 0x00f15b00, 0xfffffff4, 0x0790d300, 0x0b51f000, 0x07126000, 0x00000000, 0x00000000, 0x00000000]),
+    ('$', [0x00000000, 0x00000000, 0x00024000, 0x00048000, 0x01affd60, 0x0ab48230, 0x0c848000, 0x08faa100, 0x004bff60, 0x000483f2, 0x000480f4, 0x0b98bbd0, 0x027ac600, 0x00048000, 0x00024000, 0x00000000]),
+    ('%', [0x00000000, 0x00000000, 0x00000000, 0x18b50000, 0x994e2000, 0xc1084000, 0x8b8e1165, 0x0485aa50, 0x16b85860, 0x3502e5b8, 0x0004802c, 0x0002e5a8, 0x00004870, 0x00000000, 0x00000000, 0x00000000]),
+    ('&', [0x00000000, 0x00000000, 0x00044000, 0x01edcc00, 0x07d00100, 0x06d00000, 0x01f70000, 0x0ccf3013, 0x6c08d14c, 0x9800ca6a, 0x8c001fe4, 0x2fb45df3, 0x02acb455, 0x00000000, 0x00000000, 0x00000000]),
+    ('\'', [0x00000000, 0x00000000, 0x00000000, 0x00088000, 0x00088000, 0x00088000, 0x00044000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000]),
+    ('(', [0x00000000, 0x00000000, 0x00003500, 0x0000e400, 0x0006d000, 0x000b7000, 0x000f4000, 0x004f0000, 0x004f0000, 0x003f1000, 0x000f4000, 0x000b8000, 0x0005e000, 0x0000c400, 0x00002200, 0x00000000]),
+    (')', [0x00000000, 0x00000000, 0x00530000, 0x004e0000, 0x000d6000, 0x0007b000, 0x0004f000, 0x0000f400, 0x0000f400, 0x0001f300, 0x0004f000, 0x0008b000, 0x000e5000, 0x004c0000, 0x00220000, 0x00000000]),
+    ('*', [0x00000000, 0x00000000, 0x00011000, 0x00044000, 0x09844890, 0x004dd400, 0x04baab40, 0x06144160, 0x00033000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000]),
+    ('+', [0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00088000, 0x00088000, 0x00088000, 0x8ffffff8, 0x00088000, 0x00088000, 0x00066000, 0x00000000, 0x00000000, 0x00000000, 0x00000000]),
+    (',', [0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00034000, 0x000cf000, 0x000ea000, 0x002f2000, 0x00260000, 0x00000000]),
+    ('-', [0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00688600, 0x00688600, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000]),
+    ('.', [0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00066000, 0x000cc000, 0x00066000, 0x00000000, 0x00000000, 0x00000000]),
+    ('/', [0x00000000, 0x00000000, 0x00000000, 0x000008c0, 0x00001f40, 0x00007c00, 0x0000e600, 0x0006e000, 0x000e6000, 0x005e0000, 0x00c80000, 0x04f10000, 0x0c800000, 0x1c100000, 0x00000000, 0x00000000]),
+    ('0', [0x00000000, 0x00000000, 0x00044000, 0x01deed10, 0x0ac00ca0, 0x0f6006f0, 0x0f4114f0, 0x3f4bb4f3, 0x1f4554f1, 0x0f4004f0, 0x0d9009d0, 0x05f66f50, 0x005cc500, 0x00000000, 0x00000000, 0x00000000]),
+    ('1', [0x00000000, 0x00000000, 0x00000000, 0x05dff000, 0x0467f000, 0x0004f000, 0x0004f000, 0x0004f000, 0x0004f000, 0x0004f000, 0x0004f000, 0x028af880, 0x02888880, 0x00000000, 0x00000000, 0x00000000]),
+    ('2', [0x00000000, 0x00000000, 0x00243000, 0x0dfdfd10, 0x08101ea0, 0x000008c0, 0x00000ca0, 0x00006f20, 0x0003f300, 0x003f6000, 0x03f60000, 0x0fd88860, 0x08888860, 0x00000000, 0x00000000, 0x00000000]),
+    ('3', [0x00000000, 0x00000000, 0x00243000, 0x0cfcfd10, 0x02001ca0, 0x000008c0, 0x00015f60, 0x008ffa00, 0x00002d90, 0x000005f0, 0x000006f0, 0x0c748f90, 0x08ccc600, 0x00000000, 0x00000000, 0x00000000]),
+    ('4', [0x00000000, 0x00000000, 0x00000000, 0x0000df00, 0x0007cf00, 0x002d5f00, 0x00c54f00, 0x05c04f00, 0x1f204f00, 0x4fccdfc3, 0x14447f41, 0x00004f00, 0x00002800, 0x00000000, 0x00000000, 0x00000000]),
+    ('5', [0x00000000, 0x00000000, 0x00000000, 0x0cffff40, 0x0c800000, 0x0c800000, 0x0cecc500, 0x08649f50, 0x000009d0, 0x000008f0, 0x000009d0, 0x0b649f50, 0x09ccb400, 0x00000000, 0x00000000, 0x00000000]),
+    ('6', [0x00000000, 0x00000000, 0x00024300, 0x00afdf80, 0x08e20020, 0x0e600000, 0x0f5bc810, 0x3fd54da0, 0x2f7005f0, 0x0f4004f3, 0x0d7005f0, 0x06f65da0, 0x006cc810, 0x00000000, 0x00000000, 0x00000000]),
+    ('7', [0x00000000, 0x00000000, 0x00000000, 0x0ffffff0, 0x00000aa0, 0x00001f30, 0x00007e00, 0x0000e700, 0x0003f200, 0x000ab000, 0x001f6000, 0x006f0000, 0x00650000, 0x00000000, 0x00000000, 0x00000000]),
+    ('8', [0x00000000, 0x00000000, 0x00044000, 0x03fddf30, 0x0da00ad0, 0x0f8008f0, 0x09c11c90, 0x01dffd10, 0x0ca11bc0, 0x2f4004f2, 0x2f5004f2, 0x0cd55dc0, 0x018cc810, 0x00000000, 0x00000000, 0x00000000]),
+    ('9', [0x00000000, 0x00000000, 0x00143000, 0x05fded10, 0x0f800ca0, 0x3f3006e0, 0x3f3006f0, 0x0f700bf0, 0x06fcdbf0, 0x002434f0, 0x00000ab0, 0x0674af30, 0x04bca300, 0x00000000, 0x00000000, 0x00000000]),
+    (':', [0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00033000, 0x000cc000, 0x00099000, 0x00000000, 0x00000000, 0x00066000, 0x000cc000, 0x00066000, 0x00000000, 0x00000000, 0x00000000]),
+    (';', [0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00033000, 0x000cc000, 0x00099000, 0x00000000, 0x00000000, 0x00034000, 0x000cf000, 0x000ea000, 0x002f2000, 0x00260000, 0x00000000]),
+    ('<', [0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000176, 0x0004afb4, 0x27ee7200, 0x8f900000, 0x06cfa300, 0x00029ee4, 0x00000055, 0x00000000, 0x00000000, 0x00000000, 0x00000000]),
+    ('=', [0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x48888884, 0x6cccccc6, 0x00000000, 0x6cccccc6, 0x24444442, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000]),
+    ('>', [0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x67100000, 0x4bfa4000, 0x0027ee72, 0x000009f8, 0x003afc60, 0x4ee92000, 0x55000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000]),
+    ('?', [0x00000000, 0x00000000, 0x00044000, 0x05fdef30, 0x05200cb0, 0x00000ab0, 0x00006f30, 0x0005f300, 0x000c8000, 0x000c8000, 0x00000000, 0x000c8000, 0x00064000, 0x00000000, 0x00000000, 0x00000000]),
+    ('@', [0x00000000, 0x00000000, 0x00000000, 0x00058600, 0x03ea8ad1, 0x1e6000a7, 0x6a03ab7b, 0xb50f74dc, 0xc44c005c, 0xc44c005c, 0xc51f42dc, 0x7a03cc89, 0x1e400000, 0x03f94440, 0x0017ab60, 0x00000000]),
+    ('A', [0x00000000, 0x00000000, 0x00000000, 0x000ff000, 0x005ee500, 0x009aa900, 0x00e55e00, 0x03f11f30, 0x08c00c80, 0x0dffffd0, 0x2f3003f2, 0x7e0000e7, 0x56000065, 0x00000000, 0x00000000, 0x00000000]),
+    ('B', [0x00000000, 0x00000000, 0x00000000, 0x0ffffe30, 0x0f800ae0, 0x0f8004f0, 0x0f802bc0, 0x0ffffe30, 0x0f8007f1, 0x0f8000f4, 0x0f8001f4, 0x0fc88ed1, 0x08888710, 0x00000000, 0x00000000, 0x00000000]),
+    ('C', [0x00000000, 0x00000000, 0x00014400, 0x008fdef0, 0x05f30040, 0x0d900000, 0x0f500000, 0x0f400000, 0x0f400000, 0x0f700000, 0x0ac00000, 0x02fb46c0, 0x0029cc70, 0x00000000, 0x00000000, 0x00000000]),
+    ('D', [0x00000000, 0x00000000, 0x00000000, 0x0fffe700, 0x0f405f70, 0x0f4009e0, 0x0f4004f1, 0x0f4004f4, 0x0f4004f3, 0x0f4006f0, 0x0f400cb0, 0x0fa8de20, 0x08886100, 0x00000000, 0x00000000, 0x00000000]),
+    ('E', [0x00000000, 0x00000000, 0x00000000, 0x0cfffff0, 0x0c800000, 0x0c800000, 0x0c800000, 0x0cffffc0, 0x0c800000, 0x0c800000, 0x0c800000, 0x0cc88882, 0x06888882, 0x00000000, 0x00000000, 0x00000000]),
+    ('F', [0x00000000, 0x00000000, 0x00000000, 0x08fffff4, 0x08c00000, 0x08c00000, 0x08d44430, 0x08ffffc0, 0x08c00000, 0x08c00000, 0x08c00000, 0x08c00000, 0x04600000, 0x00000000, 0x00000000, 0x00000000]),
+    ('G', [0x00000000, 0x00000000, 0x00024300, 0x01bfcfa0, 0x09d10050, 0x1f600000, 0x4f200000, 0x4f002441, 0x4f006cf4, 0x3f3000f4, 0x0e8000f4, 0x05f848f3, 0x004bcb40, 0x00000000, 0x00000000, 0x00000000]),
+    ('H', [0x00000000, 0x00000000, 0x00000000, 0x0f4004f0, 0x0f4004f0, 0x0f4004f0, 0x0f4004f0, 0x0ffffff0, 0x0f4004f0, 0x0f4004f0, 0x0f4004f0, 0x0f4004f0, 0x08200280, 0x00000000, 0x00000000, 0x00000000]),
+    ('I', [0x00000000, 0x00000000, 0x00000000, 0x0cffffc0, 0x000cc000, 0x000cc000, 0x000cc000, 0x000cc000, 0x000cc000, 0x000cc000, 0x000cc000, 0x068ee860, 0x06888860, 0x00000000, 0x00000000, 0x00000000]),
+    ('J', [0x00000000, 0x00000000, 0x00000000, 0x008fff40, 0x00000f40, 0x00000f40, 0x00000f40, 0x00000f40, 0x00000f40, 0x00000f40, 0x10004f20, 0x4c64ce00, 0x18cca200, 0x00000000, 0x00000000, 0x00000000]),
+    ('K', [0x00000000, 0x00000000, 0x00000000, 0x0f4005f6, 0x0f403f60, 0x0f43f600, 0x0f7f6000, 0x0fff8000, 0x0f85f300, 0x0f40bd00, 0x0f401f80, 0x0f4005f3, 0x08200076, 0x00000000, 0x00000000, 0x00000000]),
+    ('L', [0x00000000, 0x00000000, 0x00000000, 0x08c00000, 0x08c00000, 0x08c00000, 0x08c00000, 0x08c00000, 0x08c00000, 0x08c00000, 0x08c00000, 0x08e88884, 0x04888884, 0x00000000, 0x00000000, 0x00000000]),
+    ('M', [0x00000000, 0x00000000, 0x00000000, 0x8f9009f8, 0x8de00ed8, 0x8cb33bc8, 0x8c6996c8, 0x8c1ee1c8, 0x8c0bb0c8, 0x8c0220c8, 0x8c0000c8, 0x8c0000c8, 0x46000064, 0x00000000, 0x00000000, 0x00000000]),
+    ('N', [0x00000000, 0x00000000, 0x00000000, 0x0fd004f0, 0x0ff304f0, 0x0fca04f0, 0x0f5f14f0, 0x0f4a74f0, 0x0f44e4f0, 0x0f40e8f0, 0x0f406ef0, 0x0f401ff0, 0x08200680, 0x00000000, 0x00000000, 0x00000000]),
+    ('O', [0x00000000, 0x00000000, 0x00044000, 0x02eeee20, 0x0bb00bb0, 0x0f5005f0, 0x4f4004f4, 0x4f4004f4, 0x4f4004f4, 0x2f4004f2, 0x0e7007e0, 0x08f66f80, 0x006cc600, 0x00000000, 0x00000000, 0x00000000]),
+    ('P', [0x00000000, 0x00000000, 0x00000000, 0x0cfffe60, 0x0c8008f2, 0x0c8000f7, 0x0c8001f5, 0x0ca47df1, 0x0cecc820, 0x0c800000, 0x0c800000, 0x0c800000, 0x06400000, 0x00000000, 0x00000000, 0x00000000]),
+    ('Q', [0x00000000, 0x00000000, 0x00044000, 0x02eeee20, 0x0bb00bb0, 0x0f5005f0, 0x4f4004f4, 0x4f4004f4, 0x4f4004f4, 0x2f4004f2, 0x0e7007e0, 0x08f66f80, 0x006cfc00, 0x00003f80, 0x00000200, 0x00000000]),
+    ('R', [0x00000000, 0x00000000, 0x00000000, 0x0ffffc10, 0x0f402eb0, 0x0f4008f0, 0x0f400ad0, 0x0fa8be30, 0x0fa8dc00, 0x0f401e80, 0x0f4006f1, 0x0f4000e8, 0x08200057, 0x00000000, 0x00000000, 0x00000000]),
+    ('S', [0x00000000, 0x00000000, 0x00044100, 0x03fecf70, 0x0e700130, 0x0f400000, 0x0fb20000, 0x03efea10, 0x00037ec0, 0x000004f0, 0x010004f0, 0x0e846dc0, 0x06bcc810, 0x00000000, 0x00000000, 0x00000000]),
+    ('T', [0x00000000, 0x00000000, 0x00000000, 0xcffffffc, 0x000cc000, 0x000cc000, 0x000cc000, 0x000cc000, 0x000cc000, 0x000cc000, 0x000cc000, 0x000cc000, 0x00066000, 0x00000000, 0x00000000, 0x00000000]),
+    ('U', [0x00000000, 0x00000000, 0x00000000, 0x0f4004f0, 0x0f4004f0, 0x0f4004f0, 0x0f4004f0, 0x0f4004f0, 0x0f4004f0, 0x0f4004f0, 0x0f5005f0, 0x09d55d90, 0x008cc800, 0x00000000, 0x00000000, 0x00000000]),
+    ('V', [0x00000000, 0x00000000, 0x00000000, 0x7e0000e7, 0x3f2002f3, 0x0e7007e0, 0x09b00b90, 0x05f00f50, 0x00f33f00, 0x00b77b00, 0x006cc600, 0x002ff200, 0x00077000, 0x00000000, 0x00000000, 0x00000000]),
+    ('W', [0x00000000, 0x00000000, 0x00000000, 0xf500005f, 0xc800008c, 0xa906609a, 0x8c0ee0c8, 0x4c2ee1c4, 0x3f5aa5f3, 0x0f8668f0, 0x0ef33fe0, 0x0cf00fc0, 0x04600640, 0x00000000, 0x00000000, 0x00000000]),
+    ('X', [0x00000000, 0x00000000, 0x00000000, 0x2f5002f5, 0x08e00bb0, 0x00d84f10, 0x004fe600, 0x000de000, 0x004fe600, 0x01e74f10, 0x09d00ca0, 0x3f4002f4, 0x56000065, 0x00000000, 0x00000000, 0x00000000]),
+    ('Y', [0x00000000, 0x00000000, 0x00000000, 0x7f1001f7, 0x0d8008d0, 0x04f22f40, 0x00baab00, 0x002ff200, 0x000cc000, 0x000cc000, 0x000cc000, 0x000cc000, 0x00066000, 0x00000000, 0x00000000, 0x00000000]),
+    ('Z', [0x00000000, 0x00000000, 0x00000000, 0x0cfffff8, 0x000006f2, 0x00001f70, 0x0000bc00, 0x0005f200, 0x001f7000, 0x00ac0000, 0x04f20000, 0x0ed88884, 0x08888884, 0x00000000, 0x00000000, 0x00000000]),
+    ('[', [0x00000000, 0x00000000, 0x00088600, 0x000fa600, 0x000f4000, 0x000f4000, 0x000f4000, 0x000f4000, 0x000f4000, 0x000f4000, 0x000f4000, 0x000f4000, 0x000f4000, 0x000fa600, 0x00044300, 0x00000000]),
+    ('\\', [0x00000000, 0x00000000, 0x00000000, 0x1f200000, 0x0aa00000, 0x02f20000, 0x00aa0000, 0x003f1000, 0x000c8000, 0x0004f100, 0x0000c700, 0x00006e00, 0x00000e60, 0x000005a0, 0x00000000, 0x00000000]),
+    (']', [0x00000000, 0x00000000, 0x00688000, 0x006af000, 0x0004f000, 0x0004f000, 0x0004f000, 0x0004f000, 0x0004f000, 0x0004f000, 0x0004f000, 0x0004f000, 0x0004f000, 0x006af000, 0x00344000, 0x00000000]),
+    ('^', [0x00000000, 0x00000000, 0x00000000, 0x002ff200, 0x01d88d10, 0x0ba00ab0, 0x37000073, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000]),
+    ('_', [0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x88888888]),
+    ('`', [0x00000000, 0x00000000, 0x00c80000, 0x001d4000, 0x00017000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000]),
+    ('a', [0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x04bcc800, 0x05644d90, 0x000005c0, 0x03cfffc0, 0x0f8005c0, 0x2f0009c0, 0x0fa47ec0, 0x03bc9360, 0x00000000, 0x00000000, 0x00000000]),
+    ('b', [0x00000000, 0x00000000, 0x06400000, 0x0c800000, 0x0c800000, 0x0c9bca10, 0x0cf64da0, 0x0ca004f1, 0x0c8000f4, 0x0c8000f4, 0x0ca005f0, 0x0cf74d90, 0x065ac810, 0x00000000, 0x00000000, 0x00000000]),
+    ('c', [0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x002acc80, 0x02fa44b0, 0x09d00000, 0x0c800000, 0x0c800000, 0x08d00000, 0x01fb45b0, 0x0029cc60, 0x00000000, 0x00000000, 0x00000000]),
+    ('d', [0x00000000, 0x00000000, 0x00000460, 0x000008c0, 0x000008c0, 0x01acb9c0, 0x0ad46fc0, 0x0f400ac0, 0x4f0008c0, 0x4f0008c0, 0x0f500ac0, 0x09d47fc0, 0x008ca560, 0x00000000, 0x00000000, 0x00000000]),
+    ('e', [0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x006cca10, 0x08f54bb0, 0x0f5001f1, 0x4fccccf4, 0x4f444441, 0x0f500000, 0x07f646c0, 0x005bcb60, 0x00000000, 0x00000000, 0x00000000]),
+    ('f', [0x00000000, 0x00000000, 0x00006880, 0x0008d880, 0x000c8000, 0x09cfecc0, 0x034da440, 0x000c8000, 0x000c8000, 0x000c8000, 0x000c8000, 0x000c8000, 0x00064000, 0x00000000, 0x00000000, 0x00000000]),
+    ('g', [0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x01acb790, 0x0ad45fc0, 0x0f500ac0, 0x4f0008c0, 0x4f1008c0, 0x0f500ac0, 0x08f68fc0, 0x006b78c0, 0x00000a90, 0x08b8af20, 0x01586100]),
+    ('h', [0x00000000, 0x00000000, 0x06400000, 0x0c800000, 0x0c800000, 0x0c9acb10, 0x0ce64da0, 0x0c9008c0, 0x0c8008c0, 0x0c8008c0, 0x0c8008c0, 0x0c8008c0, 0x06400460, 0x00000000, 0x00000000, 0x00000000]),
+    ('i', [0x00000000, 0x00000000, 0x00046000, 0x0008c000, 0x00000000, 0x03cc9000, 0x014ac000, 0x0008c000, 0x0008c000, 0x0008c000, 0x0008c000, 0x034ad440, 0x06888880, 0x00000000, 0x00000000, 0x00000000]),
+    ('j', [0x00000000, 0x00000000, 0x00008000, 0x0000f000, 0x00000000, 0x03ccc000, 0x0144f000, 0x0000f000, 0x0000f000, 0x0000f000, 0x0000f000, 0x0000f000, 0x0000f000, 0x0004f000, 0x09ce9000, 0x03440000]),
+    ('k', [0x00000000, 0x00000000, 0x04600000, 0x08c00000, 0x08c00000, 0x08c006b1, 0x08c06f30, 0x08c6f300, 0x08ffd000, 0x08e2d900, 0x08c03f40, 0x08c007f1, 0x04600075, 0x00000000, 0x00000000, 0x00000000]),
+    ('l', [0x00000000, 0x00000000, 0x08882000, 0x044f4000, 0x000f4000, 0x000f4000, 0x000f4000, 0x000f4000, 0x000f4000, 0x000f4000, 0x000f4000, 0x000cd860, 0x00017860, 0x00000000, 0x00000000, 0x00000000]),
+    ('m', [0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x3abb5c90, 0x4f4ce4e3, 0x4c0890c4, 0x4c0880c4, 0x4c0880c4, 0x4c0880c4, 0x4c0880c4, 0x26044062, 0x00000000, 0x00000000, 0x00000000]),
+    ('n', [0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x097acb10, 0x0ce64da0, 0x0c9008c0, 0x0c8008c0, 0x0c8008c0, 0x0c8008c0, 0x0c8008c0, 0x06400460, 0x00000000, 0x00000000, 0x00000000]),
+    ('o', [0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x008cc800, 0x09d55d90, 0x0f6006f0, 0x0f4004f0, 0x0f4004f0, 0x0f6006f0, 0x08f55f80, 0x007cc700, 0x00000000, 0x00000000, 0x00000000]),
+    ('p', [0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x097bca10, 0x0cf64da0, 0x0ca004f0, 0x0c8000f4, 0x0c8000f4, 0x0ca005f0, 0x0cf74d90, 0x0c9ac810, 0x0c800000, 0x0c800000, 0x03200000]),
+    ('q', [0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x008cb490, 0x08d55ec0, 0x0f6009c0, 0x0f4006c0, 0x0f4006c0, 0x0f6009c0, 0x08d55ec0, 0x008cb5c0, 0x000004c0, 0x000004c0, 0x00000260]),
+    ('r', [0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00967cc4, 0x00cdb455, 0x00cd0000, 0x00c80000, 0x00c80000, 0x00c80000, 0x00c80000, 0x00640000, 0x00000000, 0x00000000, 0x00000000]),
+    ('s', [0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x008ccb20, 0x07e44630, 0x08a00000, 0x04fd9400, 0x0015af60, 0x000009c0, 0x06745e70, 0x04acc700, 0x00000000, 0x00000000, 0x00000000]),
+    ('t', [0x00000000, 0x00000000, 0x00000000, 0x003c0000, 0x004f0000, 0x3cdfcc90, 0x147f4430, 0x004f0000, 0x004f0000, 0x004f0000, 0x004f0000, 0x000fa430, 0x00038860, 0x00000000, 0x00000000, 0x00000000]),
+    ('u', [0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x09600690, 0x0c8008c0, 0x0c8008c0, 0x0c8008c0, 0x0c8008c0, 0x0c8009c0, 0x07f56fc0, 0x01aca560, 0x00000000, 0x00000000, 0x00000000]),
+    ('v', [0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x3c0000c3, 0x0f5005f0, 0x0aa00aa0, 0x03f00f30, 0x00e56e00, 0x009bb900, 0x002ff200, 0x00077000, 0x00000000, 0x00000000, 0x00000000]),
+    ('w', [0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0xb300003b, 0xc700007c, 0x8a0540a8, 0x4e0dd0e4, 0x0f2cc2f0, 0x0da77ad0, 0x09f23f90, 0x03800830, 0x00000000, 0x00000000, 0x00000000]),
+    ('x', [0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x1b3003b1, 0x06e11e60, 0x00abba00, 0x001ee100, 0x003ff300, 0x01e77e10, 0x0bb00bb0, 0x28100182, 0x00000000, 0x00000000, 0x00000000]),
+    ('y', [0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x2c1000b4, 0x0e6003f1, 0x07c00aa0, 0x02f20f40, 0x00b96e00, 0x005eb700, 0x000ef200, 0x000ab000, 0x001e6000, 0x09dd0000, 0x03410000]),
+    ('z', [0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x06cccc90, 0x02444da0, 0x00008d10, 0x0005f300, 0x003f5000, 0x01d80000, 0x0ad44430, 0x06888860, 0x00000000, 0x00000000, 0x00000000]),
+    ('{', [0x00000000, 0x00000000, 0x00004840, 0x0006f840, 0x0008c000, 0x0008c000, 0x0008c000, 0x000c8000, 0x06fd1000, 0x003e7000, 0x0008b000, 0x0008c000, 0x0008c000, 0x0007e420, 0x00019c60, 0x00000000]),
+    ('|', [0x00000000, 0x00000000, 0x00044000, 0x00088000, 0x00088000, 0x00088000, 0x00088000, 0x00088000, 0x00088000, 0x00088000, 0x00088000, 0x00088000, 0x00088000, 0x00088000, 0x00088000, 0x00066000]),
+    ('}', [0x00000000, 0x00000000, 0x04840000, 0x048f6000, 0x000c8000, 0x000c8000, 0x000c8000, 0x0008c000, 0x0001df60, 0x0007e300, 0x000b8000, 0x000c8000, 0x000c8000, 0x024e7000, 0x06c91000, 0x00000000]),
+    ('~', [0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x04850003, 0x7dadece7, 0x20003730, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000]),
+    ('²', [0x00000000, 0x00000000, 0x00143000, 0x0088d800, 0x00005b00, 0x0001c300, 0x001c3000, 0x00db8600, 0x00444300, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000]),
+    ('·', [0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x000cc000, 0x000cc000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000]),
+    ('×', [0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x05100150, 0x0ad11da0, 0x00adda00, 0x001ff100, 0x01daad10, 0x0ca00ac0, 0x02000020, 0x00000000, 0x00000000, 0x00000000, 0x00000000]),
+    ('–', [0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x88888888, 0x88888888, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000]),
+    ('•', [0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00033000, 0x009ff900, 0x00ffff00, 0x00cffb00, 0x00188100, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000]),
+    ('■', [0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0xcccccccc, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0x00000000, 0x00000000, 0x00000000]),
+    ('▶', [0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0xa2000000, 0xffa20000, 0xffffa200, 0xffffffa2, 0xffffffe6, 0xffffe600, 0xffe60000, 0xe6000000, 0x00000000, 0x00000000, 0x00000000]),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn looks_up_glyphs() {
+        assert!(GLYPHS.windows(2).all(|w| w[0].0 < w[1].0));
+        // 'l' has a solid stem, a space nothing at all
+        let l = glyph('l');
+        assert!((0..H).any(|y| (0..W).any(|x| coverage(&l, x, y) == 15)));
+        assert_eq!(glyph(' '), [0; H]);
+        assert_eq!(glyph('漢'), tofu());
+        let full = glyph('█');
+        assert!((0..H).all(|y| (0..W).all(|x| coverage(&full, x, y) == 15)));
+        let lower = glyph('▄');
+        assert_eq!(
+            (coverage(&lower, 0, H / 2 - 1), coverage(&lower, 0, H / 2)),
+            (0, 15)
+        );
+    }
+}
@@ -0,0 +1,301 @@
+// Escape-time kernels and screen -> complex plane mapping
+use num_complex::{Complex32, Complex64};
+
+// Terminal cells are roughly twice as tall as they are wide
+pub const CELL_ASPECT: f64 = 2.0;
+// Extent shown at zoom 1 (half width / half height in complex units)
+const BASE_HALF_W: f64 = 1.5;
+const BASE_HALF_H: f64 = 1.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Fractal {
+    Julia,
+    Mandelbrot,
+}
+
+impl Fractal {
+    pub fn name(self) -> &'static str {
+        match self {
+            Fractal::Julia => "Julia",
+            Fractal::Mandelbrot => "Mandelbrot",
+        }
+    }
+
+    // Default view framing the whole set
+    pub fn home(self) -> Viewport {
+        match self {
+            Fractal::Julia => Viewport::new(Complex64::new(0.0, 0.0)),
+            Fractal::Mandelbrot => Viewport::new(Complex64::new(-0.5, 0.0)),
+        }
+    }
+
+    // Iterations before escape for plane point `p`; `c` is the Julia parameter
+    #[inline]
+    pub fn escape(self, p: Complex64, c: Complex64, max_iters: usize) -> usize {
+        let (mut z, c) = match self {
+            Fractal::Julia => (p, c),
+            Fractal::Mandelbrot => (Complex64::new(0.0, 0.0), p),
+        };
+        let mut iters = 0usize;
+        while z.norm_sqr() <= 4.0 && iters < max_iters {
+            z = z * z + c;
+            iters += 1;
+        }
+        iters
+    }
+
+    // Continuous escape count (no banding), max_iters for points that never escape. Uses a
+    // larger bailout than `escape` so the fractional part is accurate.
+    pub fn escape_smooth(self, p: Complex64, c: Complex64, max_iters: usize) -> f64 {
+        const BAILOUT: f64 = 256.0;
+        let max = max_iters as f64;
+        self.smooth_count(p, c, max_iters, BAILOUT)
+            .map_or(max, |nu| nu.clamp(0.0, max))
+    }
+
+    // Continuous escape count n + 1 - log2 ln|z| once |z| passes `bailout`, None for points
+    // that don't escape within max_iters
+    #[inline]
+    pub fn smooth_count(
+        self,
+        p: Complex64,
+        c: Complex64,
+        max_iters: usize,
+        bailout: f64,
+    ) -> Option<f64> {
+        let (mut z, c) = match self {
+            Fractal::Julia => (p, c),
+            Fractal::Mandelbrot => (Complex64::new(0.0, 0.0), p),
+        };
+        for n in 0..max_iters {
+            let r2 = z.norm_sqr();
+            if r2 > bailout * bailout {
+                return Some(n as f64 + 1.0 - (0.5 * r2.ln()).log2());
+            }
+            z = z * z + c;
+        }
+        None
+    }
+
+    // `escape` in single precision, for comparing against the f64 kernel
+    pub fn escape_f32(self, p: Complex64, c: Complex64, max_iters: usize) -> usize {
+        let (p, c) = (single(p), single(c));
+        let (mut z, c) = match self {
+            Fractal::Julia => (p, c),
+            Fractal::Mandelbrot => (Complex32::new(0.0, 0.0), p),
+        };
+        let mut iters = 0usize;
+        while z.norm_sqr() <= 4.0 && iters < max_iters {
+            z = z * z + c;
+            iters += 1;
+        }
+        iters
+    }
+
+    // `escape_smooth` in single precision
+    pub fn escape_smooth_f32(self, p: Complex64, c: Complex64, max_iters: usize) -> f64 {
+        const BAILOUT: f32 = 256.0;
+        let (p, c) = (single(p), single(c));
+        let (mut z, c) = match self {
+            Fractal::Julia => (p, c),
+            Fractal::Mandelbrot => (Complex32::new(0.0, 0.0), p),
+        };
+        for n in 0..max_iters {
+            let r2 = z.norm_sqr();
+            if r2 > BAILOUT * BAILOUT {
+                let nu = n as f64 + 1.0 - (0.5 * (r2 as f64).ln()).log2();
+                return nu.clamp(0.0, max_iters as f64);
+            }
+            z = z * z + c;
+        }
+        max_iters as f64
+    }
+}
+
+fn single(z: Complex64) -> Complex32 {
+    Complex32::new(z.re as f32, z.im as f32)
+}
+
+// How much f64 resolution is left at the current zoom
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Precision {
+    Fine,
+    // Few distinct doubles per cell: blocky artifacts are about to show
+    Near,
+    // Neighbouring cells round to the same point
+    Lost,
+}
+
+// Magnification like "12.5x" or, once it gets long, "3.2e+08x"
+pub fn format_zoom(zoom: f64) -> String {
+    if zoom < 1e4 {
+        format!("{zoom:.1}x")
+    } else {
+        format!("{}x", sci(zoom))
+    }
+}
+
+// Scientific notation with a signed two-digit exponent, e.g. "4.7e-09"
+pub fn sci(v: f64) -> String {
+    let s = format!("{v:.1e}");
+    match s.split_once('e') {
+        Some((m, e)) => {
+            let e: i32 = e.parse().unwrap_or(0);
+            format!("{m}e{}{:02}", if e < 0 { '-' } else { '+' }, e.abs())
+        }
+        None => s,
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Viewport {
+    pub center: Complex64,
+    pub zoom: f64,
+}
+
+impl Viewport {
+    pub fn new(center: Complex64) -> Self {
+        Viewport { center, zoom: 1.0 }
+    }
+
+    // Complex units per column; the base extent is fitted into w×h cells keeping aspect
+    #[inline]
+    pub fn cell_size(&self, w: usize, h: usize) -> f64 {
+        let sx = 2.0 * BASE_HALF_W / w.max(1) as f64;
+        let sy = 2.0 * BASE_HALF_H / (h.max(1) as f64 * CELL_ASPECT);
+        sx.max(sy) / self.zoom
+    }
+
+    // Distinct doubles per cell near the center: orbit values are up to 2 in size, so the
+    // spacing of doubles there is the floor however small the center's coordinates are
+    pub fn precision(&self, w: usize, h: usize) -> Precision {
+        self.precision_at(self.cell_size(w, h))
+    }
+
+    // Same for samples `spacing` plane units apart, e.g. the pixels of an image
+    pub fn precision_at(&self, spacing: f64) -> Precision {
+        let magnitude = self.center.re.abs().max(self.center.im.abs()).max(2.0);
+        let steps = spacing / (magnitude * f64::EPSILON);
+        if steps < 4.0 {
+            Precision::Lost
+        } else if steps < 256.0 {
+            Precision::Near
+        } else {
+            Precision::Fine
+        }
+    }
+
+    // Shift by a fraction of the visible base extent
+    pub fn pan(&mut self, fx: f64, fy: f64) {
+        self.center.re += fx * 2.0 * BASE_HALF_W / self.zoom;
+        self.center.im += fy * 2.0 * BASE_HALF_H / self.zoom;
+    }
+
+    pub fn zoom_by(&mut self, factor: f64) {
+        self.zoom = (self.zoom * factor).max(0.25);
+    }
+
+    // Inverse of `point`: fractional cell coordinates of plane point `p`
+    #[inline]
+    pub fn cell_of(&self, p: Complex64, w: usize, h: usize) -> (f64, f64) {
+        let s = self.cell_size(w, h);
+        let x = (p.re - self.center.re) / s + w as f64 / 2.0 - 0.5;
+        let y = -(p.im - self.center.im) / (s * CELL_ASPECT) + h as f64 / 2.0 - 0.5;
+        (x, y)
+    }
+
+    // Plane point at fractional pixel (x, y) of a w×h image with square pixels, framing
+    // the same base extent as the terminal view
+    pub fn pixel(&self, x: f64, y: f64, w: usize, h: usize) -> Complex64 {
+        self.center + self.pixel_offset(x, y, w, h)
+    }
+
+    // Plane units per pixel of such an image
+    pub fn pixel_size(&self, w: usize, h: usize) -> f64 {
+        let sx = 2.0 * BASE_HALF_W / w.max(1) as f64;
+        let sy = 2.0 * BASE_HALF_H / h.max(1) as f64;
+        sx.max(sy) / self.zoom
+    }
+
+    // `pixel` relative to the center, which keeps its precision however deep the zoom
+    pub fn pixel_offset(&self, x: f64, y: f64, w: usize, h: usize) -> Complex64 {
+        let s = self.pixel_size(w, h);
+        Complex64::new((x - w as f64 / 2.0) * s, -(y - h as f64 / 2.0) * s)
+    }
+
+    // Plane point at the center of cell (x, y); +im points up
+    #[inline]
+    pub fn point(&self, x: usize, y: usize, w: usize, h: usize) -> Complex64 {
+        let s = self.cell_size(w, h);
+        let re = self.center.re + (x as f64 + 0.5 - w as f64 / 2.0) * s;
+        let im = self.center.im - (y as f64 + 0.5 - h as f64 / 2.0) * s * CELL_ASPECT;
+        Complex64::new(re, im)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn view_is_centered() {
+        let v = Viewport::new(Complex64::new(-0.5, 0.25));
+        let a = v.point(0, 0, 40, 20);
+        let b = v.point(39, 19, 40, 20);
+        assert!(((a + b) / 2.0 - v.center).norm() < 1e-12);
+        assert!(a.im > b.im);
+    }
+    #[test]
+    fn cell_of_inverts_point() {
+        let mut v = Viewport::new(Complex64::new(0.3, -0.2));
+        v.zoom_by(7.0);
+        let (x, y) = v.cell_of(v.point(12, 5, 60, 18), 60, 18);
+        assert!((x - 12.0).abs() < 1e-9 && (y - 5.0).abs() < 1e-9);
+    }
+    #[test]
+    fn reads_out_deep_zooms() {
+        assert_eq!(format_zoom(12.48), "12.5x");
+        assert_eq!(format_zoom(3.2e8), "3.2e+08x");
+        assert_eq!(sci(4.7e-9), "4.7e-09");
+        let mut v = Viewport::new(Complex64::new(-0.75, 0.1));
+        assert_eq!(v.precision(100, 30), Precision::Fine);
+        v.zoom = 1e12;
+        assert_eq!(v.precision(100, 30), Precision::Near);
+        v.zoom = 1e15;
+        assert_eq!(v.precision(100, 30), Precision::Lost);
+    }
+    #[test]
+    fn smooth_count_is_continuous() {
+        let c = Complex64::new(0.0, 0.0);
+        let f = |re: f64| Fractal::Mandelbrot.escape_smooth(Complex64::new(re, 0.5), c, 100);
+        // Neighbouring points differ by a fraction, not by whole bands
+        assert!((f(0.4) - f(0.4001)).abs() < 0.1);
+        assert!(f(0.4) > f(1.0));
+        assert_eq!(f(-0.1), 100.0);
+    }
+    #[test]
+    fn single_precision_agrees_when_zoomed_out() {
+        let c = Complex64::new(-0.8, 0.156);
+        for p in [Complex64::new(0.3, 0.2), Complex64::new(-1.2, 0.05)] {
+            let (a, b) = (
+                Fractal::Julia.escape(p, c, 200),
+                Fractal::Julia.escape_f32(p, c, 200),
+            );
+            assert!(a.abs_diff(b) <= 1, "{p}: {a} vs {b}");
+        }
+        let p = Complex64::new(0.4, 0.5);
+        let (a, b) = (
+            Fractal::Mandelbrot.escape_smooth(p, c, 100),
+            Fractal::Mandelbrot.escape_smooth_f32(p, c, 100),
+        );
+        assert!((a - b).abs() < 0.01, "{a} vs {b}");
+    }
+    #[test]
+    fn mandelbrot_interior_never_escapes() {
+        let c = Complex64::new(0.0, 0.0);
+        assert_eq!(
+            Fractal::Mandelbrot.escape(Complex64::new(-0.1, 0.0), c, 50),
+            50
+        );
+        assert!(Fractal::Mandelbrot.escape(Complex64::new(1.0, 1.0), c, 50) < 3);
+    }
+}
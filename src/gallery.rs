@@ -0,0 +1,103 @@
+// Julia set gallery: a grid of tiles with different c values and a selection cursor
+use num_complex::Complex64;
+use std::f64::consts::TAU;
+
+// Classic Julia sets by name
+const PRESETS: [(&str, Complex64); 9] = [
+    ("Dendrite", Complex64::new(0.0, 1.0)),
+    ("Douady rabbit", Complex64::new(-0.123, 0.745)),
+    ("San Marco", Complex64::new(-0.75, 0.0)),
+    ("Siegel disk", Complex64::new(-0.391, -0.587)),
+    ("Basilica", Complex64::new(-1.0, 0.0)),
+    ("Airplane", Complex64::new(-1.7549, 0.0)),
+    ("Spiral galaxy", Complex64::new(-0.7269, 0.1889)),
+    ("Cauliflower", Complex64::new(0.25, 0.0)),
+    ("Cantor dust", Complex64::new(0.5, 0.5)),
+];
+
+pub struct Gallery {
+    pub cols: u16,
+    pub rows: u16,
+    pub params: Vec<Complex64>,
+    // Tile names; empty for galleries that are labelled with c itself
+    pub names: Vec<&'static str>,
+    pub cursor: usize,
+}
+
+impl Gallery {
+    // Samples c just inside the main cardioid boundary, where Julia sets are most detailed
+    pub fn cardioid(cols: u16, rows: u16) -> Self {
+        let n = (cols * rows) as usize;
+        let params = (0..n)
+            .map(|k| cardioid_point((k as f64 + 0.5) / n as f64))
+            .collect();
+        Gallery {
+            cols,
+            rows,
+            params,
+            names: Vec::new(),
+            cursor: 0,
+        }
+    }
+
+    // The named classics in a 3x3 grid
+    pub fn presets() -> Self {
+        Gallery {
+            cols: 3,
+            rows: 3,
+            params: PRESETS.iter().map(|&(_, c)| c).collect(),
+            names: PRESETS.iter().map(|&(name, _)| name).collect(),
+            cursor: 0,
+        }
+    }
+
+    // Tile caption: its name, or c where tiles have none
+    pub fn label(&self, i: usize) -> String {
+        match self.names.get(i) {
+            Some(name) => name.to_string(),
+            None => format!("{:+.3}{:+.3}i", self.params[i].re, self.params[i].im),
+        }
+    }
+
+    // Move the cursor by whole tiles, clamped to the grid
+    pub fn move_cursor(&mut self, dx: i32, dy: i32) {
+        let (cols, rows) = (self.cols as i32, self.rows as i32);
+        let x = (self.cursor as i32 % cols + dx).clamp(0, cols - 1);
+        let y = (self.cursor as i32 / cols + dy).clamp(0, rows - 1);
+        self.cursor = (y * cols + x) as usize;
+    }
+
+    pub fn selected(&self) -> Complex64 {
+        self.params[self.cursor]
+    }
+}
+
+// c just inside the main cardioid at fraction `t` (0..1) of the way around it
+pub fn cardioid_point(t: f64) -> Complex64 {
+    let e = Complex64::from_polar(1.0, TAU * t);
+    (e / 2.0 - e * e / 4.0) * 0.985
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn cursor_stays_in_grid() {
+        let mut g = Gallery::cardioid(4, 3);
+        assert_eq!(g.params.len(), 12);
+        g.move_cursor(-1, -1);
+        assert_eq!(g.cursor, 0);
+        g.move_cursor(10, 1);
+        assert_eq!(g.cursor, 7);
+        g.move_cursor(0, 5);
+        assert_eq!(g.cursor, 11);
+        assert_eq!(g.label(0), "+0.262+0.004i");
+    }
+    #[test]
+    fn presets_fill_their_grid() {
+        let g = Gallery::presets();
+        assert_eq!(g.params.len(), (g.cols * g.rows) as usize);
+        assert_eq!(g.label(1), "Douady rabbit");
+        assert_eq!(g.params[2], Complex64::new(-0.75, 0.0));
+    }
+}
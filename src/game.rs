@@ -0,0 +1,126 @@
+// "Find the minibrot": a hidden Mandelbrot location to navigate to with the usual pan and
+// zoom keys, with hot/cold hints from the distance in parameter space and a score for speed
+use crate::fractal::{Fractal, Viewport};
+use crate::location::Location;
+use num_complex::Complex64;
+use std::time::Instant;
+
+// Minibrot centers (all inside the set) with a zoom that frames them
+const TARGETS: [(f64, f64, f64, usize); 6] = [
+    (-1.7548776662, 0.0, 60.0, 300),
+    (-0.1565202, 1.0322471, 80.0, 300),
+    (-1.9407998, 0.0, 400.0, 500),
+    (-1.7864402, 0.0, 500.0, 500),
+    (-1.6254137, 0.0, 800.0, 600),
+    (-1.9854243, 0.0, 1500.0, 800),
+];
+
+pub struct Hunt {
+    pub target: Location,
+    started: Instant,
+    dist: f64,
+    trend: &'static str,
+}
+
+impl Hunt {
+    // `pick` chooses the target (any number, wrapped)
+    pub fn new(pick: usize, view: &Viewport, now: Instant) -> Self {
+        let (re, im, zoom, max_iters) = TARGETS[pick % TARGETS.len()];
+        let target = Location {
+            fractal: Fractal::Mandelbrot,
+            view: Viewport {
+                center: Complex64::new(re, im),
+                zoom,
+            },
+            c: Complex64::default(),
+            max_iters,
+        };
+        Hunt {
+            target,
+            started: now,
+            dist: distance(view, &target.view),
+            trend: "",
+        }
+    }
+
+    pub fn elapsed(&self, now: Instant) -> f64 {
+        now.saturating_duration_since(self.started).as_secs_f64()
+    }
+
+    // Follow the player's view; Some(seconds taken) once the target is framed
+    pub fn update(&mut self, view: &Viewport, now: Instant) -> Option<f64> {
+        let d = distance(view, &self.target.view);
+        // Only a move changes the trend, so it doesn't flicker back between key presses
+        if d < self.dist - 1e-9 {
+            self.trend = "warmer";
+        } else if d > self.dist + 1e-9 {
+            self.trend = "colder";
+        }
+        self.dist = d;
+        found(view, &self.target.view).then(|| self.elapsed(now))
+    }
+
+    pub fn hint(&self) -> String {
+        let heat = match self.dist {
+            d if d < 1.5 => "burning",
+            d if d < 3.0 => "hot",
+            d if d < 5.0 => "warm",
+            d if d < 7.0 => "cool",
+            _ => "cold",
+        };
+        if self.trend.is_empty() {
+            heat.to_string()
+        } else {
+            format!("{heat}, {}", self.trend)
+        }
+    }
+}
+
+// How far `view` is from framing `target`: the offset measured in the target's own screen
+// widths (log scale, so it still means something far away) plus the zoom levels missing
+fn distance(view: &Viewport, target: &Viewport) -> f64 {
+    let off = (view.center - target.center).norm() * target.zoom;
+    let gap = (target.zoom / view.zoom).log2().abs();
+    (1.0 + off).log2() + gap * 0.5
+}
+
+// The target is on screen and zoomed in on closely enough to make out
+fn found(view: &Viewport, target: &Viewport) -> bool {
+    let off = (view.center - target.center).norm() * view.zoom;
+    off < 0.8 && view.zoom >= target.zoom * 0.5
+}
+
+// Points for finding a target in `secs`: 1000 for under ten seconds, halving every minute
+pub fn score(secs: f64) -> u32 {
+    (1000.0 * 0.5f64.powf((secs - 10.0).max(0.0) / 60.0)).round() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn targets_are_minibrots() {
+        for &(re, im, _, _) in &TARGETS {
+            let p = Complex64::new(re, im);
+            let c = Complex64::default();
+            assert_eq!(Fractal::Mandelbrot.escape(p, c, 5000), 5000, "{p}");
+        }
+    }
+    #[test]
+    fn hints_track_the_target() {
+        let t0 = Instant::now();
+        let mut view = Fractal::Mandelbrot.home();
+        let mut hunt = Hunt::new(0, &view, t0);
+        assert_eq!(hunt.hint(), "cold");
+        view.center = hunt.target.view.center + Complex64::new(0.05, 0.0);
+        assert_eq!(hunt.update(&view, t0), None);
+        assert!(hunt.hint().ends_with("warmer"), "{}", hunt.hint());
+        view.zoom = 10_000.0;
+        assert!(hunt.update(&view, t0).is_none());
+        assert!(hunt.hint().ends_with("colder"));
+        view.center = hunt.target.view.center;
+        assert_eq!(hunt.update(&view, t0), Some(0.0));
+        assert_eq!(score(5.0), 1000);
+        assert_eq!(score(70.0), 500);
+    }
+}
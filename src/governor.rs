@@ -0,0 +1,149 @@
+// Quality governor: when frames take longer than their budget for a few seconds running,
+// step quality down one rung at a time, and back up once the faster rung leaves enough
+// headroom for the slower one. The terminal renderer has no supersampling to give up, so
+// resolution goes first (one sample per block of cells), then iterations.
+use std::time::{Duration, Instant};
+
+// How long frames must be over budget before stepping down, or have headroom before
+// stepping back up
+const OVERLOAD: Duration = Duration::from_secs(3);
+const HEADROOM: Duration = Duration::from_secs(5);
+// Stepping up needs the predicted cost at the better rung under this share of the budget
+const UP_SHARE: f64 = 0.8;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rung {
+    pub name: &'static str,
+    // Cells per side of a block that shares one sample
+    pub block: usize,
+    // Share of the iteration budget
+    pub iters: f64,
+}
+
+impl Rung {
+    // Work relative to full quality
+    fn cost(&self) -> f64 {
+        self.iters / (self.block * self.block) as f64
+    }
+}
+
+const RUNGS: [Rung; 5] = [
+    Rung {
+        name: "full quality",
+        block: 1,
+        iters: 1.0,
+    },
+    Rung {
+        name: "half resolution",
+        block: 2,
+        iters: 1.0,
+    },
+    Rung {
+        name: "third resolution",
+        block: 3,
+        iters: 1.0,
+    },
+    Rung {
+        name: "third resolution, half iterations",
+        block: 3,
+        iters: 0.5,
+    },
+    Rung {
+        name: "third resolution, quarter iterations",
+        block: 3,
+        iters: 0.25,
+    },
+];
+
+#[derive(Default)]
+pub struct Governor {
+    level: usize,
+    // Smoothed render time per frame, in seconds
+    work: f64,
+    // Since when frames have been over budget, or had headroom for the rung above
+    over_since: Option<Instant>,
+    under_since: Option<Instant>,
+}
+
+impl Governor {
+    pub fn rung(&self) -> Rung {
+        RUNGS[self.level]
+    }
+
+    // Iteration budget at the current rung
+    pub fn iters(&self, n: usize) -> usize {
+        ((n as f64 * self.rung().iters) as usize).max(16).min(n)
+    }
+
+    // Feed the render time of a frame and its budget; returns the new rung after a change
+    pub fn record(&mut self, now: Instant, took: Duration, budget: Duration) -> Option<Rung> {
+        self.work = self.work * 0.9 + took.as_secs_f64() * 0.1;
+        let budget = budget.as_secs_f64();
+        let over = self.work > budget;
+        let fits_above = self.level > 0 && {
+            let above = RUNGS[self.level - 1].cost() / self.rung().cost();
+            self.work * above < budget * UP_SHARE
+        };
+        self.over_since = if over {
+            self.over_since.or(Some(now))
+        } else {
+            None
+        };
+        self.under_since = if fits_above {
+            self.under_since.or(Some(now))
+        } else {
+            None
+        };
+        let lasted = |since: Option<Instant>, need| since.is_some_and(|t| now - t >= need);
+        let level = if lasted(self.over_since, OVERLOAD) && self.level + 1 < RUNGS.len() {
+            self.level + 1
+        } else if lasted(self.under_since, HEADROOM) {
+            self.level - 1
+        } else {
+            return None;
+        };
+        // The smoothed time carries over scaled to the new rung's cost
+        self.work *= RUNGS[level].cost() / self.rung().cost();
+        self.level = level;
+        self.over_since = None;
+        self.under_since = None;
+        Some(self.rung())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    const BUDGET: Duration = Duration::from_millis(16);
+    fn run(g: &mut Governor, t: &mut Instant, secs: u64, took_ms: u64) -> Vec<Rung> {
+        let mut changes = Vec::new();
+        for _ in 0..secs * 60 {
+            *t += BUDGET;
+            changes.extend(g.record(*t, Duration::from_millis(took_ms), BUDGET));
+        }
+        changes
+    }
+    #[test]
+    fn steps_down_under_load_and_back_up() {
+        let mut g = Governor::default();
+        let mut t = Instant::now();
+        // A short spike is not sustained load
+        assert!(run(&mut g, &mut t, 1, 40).is_empty());
+        assert!(run(&mut g, &mut t, 2, 10).is_empty());
+        let down = run(&mut g, &mut t, 5, 40);
+        assert_eq!(down[0].name, "half resolution");
+        assert_eq!(g.iters(120), 120);
+        // Plenty of headroom: back to full quality, one rung at a time
+        let up = run(&mut g, &mut t, 30, 1);
+        assert_eq!(up.last().map(|r| r.block), Some(1));
+    }
+    #[test]
+    fn bottoms_out_at_the_last_rung() {
+        let mut g = Governor::default();
+        let mut t = Instant::now();
+        run(&mut g, &mut t, 60, 100);
+        assert_eq!(g.rung(), RUNGS[RUNGS.len() - 1]);
+        assert_eq!(g.iters(120), 30);
+        assert_eq!(g.iters(40), 16);
+    }
+}
@@ -0,0 +1,83 @@
+// Per-frame histogram of escape iteration counts
+pub struct Histogram {
+    // counts[i] = cells that escaped after i iterations; counts[max_iters] = interior
+    pub counts: Vec<u32>,
+}
+
+impl Histogram {
+    pub fn new(max_iters: usize) -> Self {
+        Histogram {
+            counts: vec![0; max_iters + 1],
+        }
+    }
+
+    pub fn reset(&mut self, max_iters: usize) {
+        self.counts.clear();
+        self.counts.resize(max_iters + 1, 0);
+    }
+
+    #[inline]
+    pub fn add(&mut self, iters: usize) {
+        let i = iters.min(self.counts.len() - 1);
+        self.counts[i] += 1;
+    }
+
+    pub fn max_iters(&self) -> usize {
+        self.counts.len() - 1
+    }
+
+    pub fn total(&self) -> u64 {
+        self.counts.iter().map(|&c| c as u64).sum()
+    }
+
+    pub fn interior_fraction(&self) -> f64 {
+        let total = self.total();
+        if total == 0 {
+            return 0.0;
+        }
+        self.counts[self.max_iters()] as f64 / total as f64
+    }
+
+    // Iteration count below which fraction `p` of the escaping cells fall
+    pub fn escape_percentile(&self, p: f64) -> usize {
+        let escaped = &self.counts[..self.max_iters()];
+        let total: u64 = escaped.iter().map(|&c| c as u64).sum();
+        let target = (total as f64 * p).ceil() as u64;
+        let mut acc = 0u64;
+        for (i, &c) in escaped.iter().enumerate() {
+            acc += c as u64;
+            if acc >= target && acc > 0 {
+                return i;
+            }
+        }
+        0
+    }
+
+    // Escaping counts grouped into `n` equal-width buckets over 0..max_iters
+    pub fn buckets(&self, n: usize) -> Vec<u32> {
+        let max = self.max_iters().max(1);
+        let mut out = vec![0u32; n];
+        for (i, &c) in self.counts[..self.max_iters()].iter().enumerate() {
+            out[(i * n / max).min(n - 1)] += c;
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn counts_and_percentiles() {
+        let mut h = Histogram::new(10);
+        for i in [1, 1, 2, 3, 9, 10, 10, 50] {
+            h.add(i);
+        }
+        assert_eq!(h.total(), 8);
+        assert!((h.interior_fraction() - 3.0 / 8.0).abs() < 1e-12);
+        assert_eq!(h.escape_percentile(0.4), 1);
+        assert_eq!(h.escape_percentile(0.5), 2);
+        assert_eq!(h.escape_percentile(1.0), 9);
+        assert_eq!(h.buckets(5), vec![2, 2, 0, 0, 1]);
+    }
+}
@@ -0,0 +1,96 @@
+// Clean up one frame's worth of terminal events before they become actions: key releases
+// are dropped (Windows reports them), a held key counts once per frame no matter how many
+// repeats queued up while the frame rendered, and a burst of typed characters is treated as
+// text pasted into a terminal without bracketed paste support
+use crossterm::event::{Event, KeyCode, KeyEventKind, KeyModifiers};
+
+// More plain characters than this in one frame can't be typed by hand
+const PASTE_BURST: usize = 8;
+
+// Without `collapse`, as while typing into the command line, repeats are kept
+pub fn batch(events: impl IntoIterator<Item = Event>, collapse: bool) -> Vec<Event> {
+    let mut events: Vec<Event> = events
+        .into_iter()
+        .filter(|ev| match ev {
+            Event::Key(k) => k.kind != KeyEventKind::Release,
+            Event::Resize(..) | Event::Paste(_) | Event::FocusGained | Event::FocusLost => true,
+            // Mouse reports aren't used
+            _ => false,
+        })
+        .collect();
+    let typed = |ev: &Event| match ev {
+        Event::Key(k) => {
+            matches!(k.code, KeyCode::Char(_))
+                && !k
+                    .modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+        }
+        _ => false,
+    };
+    if events.iter().filter(|e| typed(e)).count() > PASTE_BURST {
+        let text: String = events
+            .iter()
+            .filter(|e| typed(e))
+            .filter_map(|e| match e {
+                Event::Key(k) => k.code.as_char(),
+                _ => None,
+            })
+            .collect();
+        events.retain(|e| !typed(e));
+        events.push(Event::Paste(text));
+    }
+    if !collapse {
+        return events;
+    }
+    // Repeats of the same key (consecutive, same modifiers) act once
+    let mut last_key: Option<(KeyCode, KeyModifiers)> = None;
+    events.retain(|ev| {
+        let key = match ev {
+            Event::Key(k) => Some((k.code, k.modifiers)),
+            _ => None,
+        };
+        let repeat = key.is_some() && key == last_key;
+        last_key = key;
+        !repeat
+    });
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyEvent;
+    fn key(c: char) -> Event {
+        Event::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE))
+    }
+    #[test]
+    fn collapses_repeats_and_releases() {
+        let mut release = KeyEvent::new(KeyCode::Char('v'), KeyModifiers::NONE);
+        release.kind = KeyEventKind::Release;
+        let evs = vec![
+            key('+'),
+            key('+'),
+            key('+'),
+            Event::Key(release),
+            key('v'),
+            key('+'),
+        ];
+        assert_eq!(batch(evs, true), vec![key('+'), key('v'), key('+')]);
+    }
+    #[test]
+    fn typing_keeps_repeats() {
+        // "ll" into the command line in one frame
+        let evs = vec![key('l'), key('l')];
+        assert_eq!(batch(evs.clone(), false), evs);
+        assert_eq!(batch(evs, true), vec![key('l')]);
+    }
+    #[test]
+    fn burst_becomes_paste() {
+        let ctrl_c = Event::Key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL));
+        let mut evs: Vec<Event> = "hello world".chars().map(key).collect();
+        evs.push(ctrl_c.clone());
+        let out = batch(evs, true);
+        // Ctrl+C still gets through
+        assert_eq!(out, vec![ctrl_c, Event::Paste("hello world".into())]);
+    }
+}
@@ -0,0 +1,116 @@
+// Kaleidoscope post-process: each cell of a pane is replaced by the cell of one wedge,
+// mirrored and repeated around the center, and the wedge turns slowly so the pattern
+// keeps changing. Works on whatever was drawn, so any rendering mode can go through it.
+use crate::compositor::Layer;
+use crate::fractal::CELL_ASPECT;
+use crate::layout::Rect;
+use std::f64::consts::{PI, TAU};
+
+// Segment counts `next` steps through; after the last the kaleidoscope turns off
+const SEGMENTS: [usize; 5] = [3, 4, 6, 8, 12];
+pub const DEFAULT_SPEED: f64 = 0.1;
+// Speed change per key press, in radians per second
+pub const SPEED_STEP: f64 = 0.05;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Kaleidoscope {
+    // Mirror images around the circle: a wedge of π/segments appears 2×segments times
+    pub segments: usize,
+    // Turning speed of the wedge in radians per second (negative turns the other way)
+    pub speed: f64,
+    angle: f64,
+}
+
+impl Kaleidoscope {
+    pub fn new(segments: usize, speed: f64) -> Self {
+        Kaleidoscope {
+            segments: segments.max(1),
+            speed,
+            angle: 0.0,
+        }
+    }
+
+    // Next segment count, or None after the last one
+    pub fn next(self) -> Option<Kaleidoscope> {
+        let i = SEGMENTS.iter().position(|&s| s == self.segments)?;
+        SEGMENTS
+            .get(i + 1)
+            .map(|&segments| Kaleidoscope { segments, ..self })
+    }
+
+    pub fn first() -> Kaleidoscope {
+        Kaleidoscope::new(SEGMENTS[0], DEFAULT_SPEED)
+    }
+
+    pub fn advance(&mut self, dt: f64) {
+        self.angle = (self.angle + self.speed * dt).rem_euclid(TAU);
+    }
+
+    // Cell of a w×h area whose content shows at (x, y). Angles are taken on screen, with
+    // rows twice as tall as columns are wide, so the wedges come out even.
+    pub fn source(&self, x: usize, y: usize, w: usize, h: usize) -> (usize, usize) {
+        let dx = x as f64 + 0.5 - w as f64 / 2.0;
+        let dy = (y as f64 + 0.5 - h as f64 / 2.0) * CELL_ASPECT;
+        let r = dx.hypot(dy);
+        let wedge = PI / self.segments as f64;
+        // Fold into [0, 2·wedge), then mirror the second half onto the first
+        let mut t = (dy.atan2(dx) - self.angle).rem_euclid(2.0 * wedge);
+        if t > wedge {
+            t = 2.0 * wedge - t;
+        }
+        let a = self.angle + t;
+        let sx = w as f64 / 2.0 + r * a.cos() - 0.5;
+        let sy = h as f64 / 2.0 + r * a.sin() / CELL_ASPECT - 0.5;
+        // Long radii of a wide pane reach past its short sides: stop at the edge
+        let clamp = |v: f64, n: usize| (v.round().max(0.0) as usize).min(n.saturating_sub(1));
+        (clamp(sx, w), clamp(sy, h))
+    }
+
+    // Rewrite `rect` of `layer` in place
+    pub fn apply(&self, layer: &mut Layer, rect: Rect) {
+        let (w, h) = (rect.w as usize, rect.h as usize);
+        let cells: Vec<_> = (0..w * h)
+            .map(|i| layer.get(rect.x + (i % w) as u16, rect.y + (i / w) as u16))
+            .collect();
+        for y in 0..h {
+            for x in 0..w {
+                let (sx, sy) = self.source(x, y, w, h);
+                if let Some(cell) = cells[sy * w + sx] {
+                    layer.put(rect.x + x as u16, rect.y + y as u16, cell);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn mirrors_around_the_center() {
+        let k = Kaleidoscope::new(4, 0.0);
+        let (w, h) = (40, 20);
+        // The first wedge (right of center, just below the axis on screen) shows itself
+        assert_eq!(k.source(30, 10, w, h), (30, 10));
+        // The opposite side and the mirror across the horizontal axis come from it too
+        assert_eq!(k.source(9, 10, w, h), (30, 10));
+        assert_eq!(k.source(30, 9, w, h), (30, 10));
+        // Every source is inside the area
+        for y in 0..h {
+            for x in 0..w {
+                let (sx, sy) = k.source(x, y, w, h);
+                assert!(sx < w && sy < h);
+            }
+        }
+    }
+    #[test]
+    fn cycles_segment_counts() {
+        let mut k = Some(Kaleidoscope::first());
+        let mut seen = Vec::new();
+        while let Some(x) = k {
+            seen.push(x.segments);
+            k = x.next();
+        }
+        assert_eq!(seen, SEGMENTS);
+    }
+}
@@ -0,0 +1,197 @@
+// Pane layout: what each pane shows and where it sits on screen
+use crate::color::Palette;
+use crate::fractal::{CELL_ASPECT, Fractal, Viewport};
+use crate::location::Location;
+use crate::warp::Warp;
+use num_complex::Complex64;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rect {
+    pub x: u16,
+    pub y: u16,
+    pub w: u16,
+    pub h: u16,
+}
+
+impl Rect {
+    pub fn new(x: u16, y: u16, w: u16, h: u16) -> Self {
+        Rect { x, y, w, h }
+    }
+}
+
+// Presets cycled at runtime (width:height in pixels, not cells)
+pub const ASPECTS: [(u16, u16); 4] = [(16, 9), (4, 3), (1, 1), (21, 9)];
+
+// Largest centered rectangle of `area` with the given pixel aspect ratio (W/H)
+pub fn letterbox(area: Rect, aspect: f64) -> Rect {
+    let cols_per_row = aspect * CELL_ASPECT;
+    let want_w = area.h as f64 * cols_per_row;
+    if want_w <= area.w as f64 {
+        let w = want_w.round() as u16;
+        Rect::new(area.x + (area.w - w) / 2, area.y, w, area.h)
+    } else {
+        let h = ((area.w as f64 / cols_per_row).round() as u16).min(area.h);
+        Rect::new(area.x, area.y + (area.h - h) / 2, area.w, h)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Layout {
+    Single,
+    // Two panes side by side with a one-column divider; `left` is the left pane's share in %
+    Split { left: u16 },
+    // cols×rows tiles separated by one blank column/row
+    Grid { cols: u16, rows: u16 },
+}
+
+impl Layout {
+    pub const SPLIT: Layout = Layout::Split { left: 50 };
+
+    pub fn pane_count(self) -> usize {
+        match self {
+            Layout::Single => 1,
+            Layout::Split { .. } => 2,
+            Layout::Grid { cols, rows } => (cols * rows) as usize,
+        }
+    }
+
+    // Move the split divider by `delta` percent
+    pub fn resize(self, delta: i16) -> Layout {
+        match self {
+            Layout::Split { left } => Layout::Split {
+                left: (left as i16 + delta).clamp(10, 90) as u16,
+            },
+            other => other,
+        }
+    }
+
+    pub fn rects(self, area: Rect) -> Vec<Rect> {
+        match self {
+            Layout::Single => vec![area],
+            Layout::Split { left } => {
+                let left = (area.w.saturating_sub(1) as u32 * left as u32 / 100) as u16;
+                let right = area.w.saturating_sub(left + 1);
+                vec![
+                    Rect::new(area.x, area.y, left, area.h),
+                    Rect::new(area.x + left + 1, area.y, right, area.h),
+                ]
+            }
+            Layout::Grid { cols, rows } => {
+                let tw = area.w.saturating_sub(cols - 1) / cols;
+                let th = area.h.saturating_sub(rows - 1) / rows;
+                (0..rows)
+                    .flat_map(|r| {
+                        (0..cols).map(move |c| {
+                            Rect::new(area.x + c * (tw + 1), area.y + r * (th + 1), tw, th)
+                        })
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Pane {
+    pub fractal: Fractal,
+    pub view: Viewport,
+    // Render with the conjugate of the shared c (mirror image of the other pane)
+    pub mirror: bool,
+    pub palette: Palette,
+    // Screen-to-plane transform (inversion, Möbius, log-polar)
+    pub warp: Warp,
+}
+
+impl Pane {
+    pub fn new(fractal: Fractal) -> Self {
+        Pane {
+            fractal,
+            view: fractal.home(),
+            mirror: false,
+            palette: Palette::default(),
+            warp: Warp::None,
+        }
+    }
+
+    // Plane point shown at cell (x, y) of a w×h pane
+    #[inline]
+    pub fn point(&self, x: usize, y: usize, w: usize, h: usize) -> Complex64 {
+        self.warp.apply(&self.view, self.view.point(x, y, w, h))
+    }
+
+    // Same for fractional pixel (x, y) of a w×h image with square pixels
+    pub fn pixel(&self, x: f64, y: f64, w: usize, h: usize) -> Complex64 {
+        self.warp.apply(&self.view, self.view.pixel(x, y, w, h))
+    }
+
+    // Fractional cell showing plane point `p`
+    pub fn cell_of(&self, p: Complex64, w: usize, h: usize) -> (f64, f64) {
+        self.view.cell_of(self.warp.invert(&self.view, p), w, h)
+    }
+
+    pub fn param(&self, c: Complex64) -> Complex64 {
+        if self.mirror { c.conj() } else { c }
+    }
+
+    // Where the pane is looking, for sharing or the exit summary
+    pub fn location(&self, c: Complex64, max_iters: usize) -> Location {
+        Location {
+            fractal: self.fractal,
+            view: self.view,
+            c: self.param(c),
+            max_iters,
+        }
+    }
+
+    // Cycle what the pane shows: Julia -> mirrored Julia -> Mandelbrot
+    pub fn cycle_source(&mut self) {
+        let (fractal, mirror) = match (self.fractal, self.mirror) {
+            (Fractal::Julia, false) => (Fractal::Julia, true),
+            (Fractal::Julia, true) => (Fractal::Mandelbrot, false),
+            (Fractal::Mandelbrot, _) => (Fractal::Julia, false),
+        };
+        if fractal != self.fractal {
+            self.view = fractal.home();
+        }
+        self.fractal = fractal;
+        self.mirror = mirror;
+    }
+
+    pub fn label(&self) -> &'static str {
+        match (self.fractal, self.mirror) {
+            (Fractal::Julia, true) => "Julia (mirrored c)",
+            (f, _) => f.name(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn split_covers_area() {
+        let area = Rect::new(0, 0, 81, 20);
+        let r = Layout::SPLIT.resize(15).rects(area);
+        assert_eq!(r.len(), 2);
+        assert_eq!(r[0].w + 1 + r[1].w, area.w);
+        assert_eq!(r[1].x, r[0].w + 1);
+        assert_eq!(r[0].w, 52);
+        assert_eq!(Layout::SPLIT.resize(-60), Layout::Split { left: 10 });
+    }
+    #[test]
+    fn letterbox_keeps_aspect() {
+        // 16:9 pixels = 32:9 cells with the 2:1 cell aspect
+        let r = letterbox(Rect::new(0, 0, 200, 30), 16.0 / 9.0);
+        assert_eq!(r, Rect::new(46, 0, 107, 30));
+        let r = letterbox(Rect::new(0, 0, 64, 40), 16.0 / 9.0);
+        assert_eq!(r, Rect::new(0, 11, 64, 18));
+    }
+    #[test]
+    fn grid_tiles_fit() {
+        let area = Rect::new(0, 0, 80, 23);
+        let r = Layout::Grid { cols: 4, rows: 3 }.rects(area);
+        assert_eq!(r.len(), 12);
+        let last = r[11];
+        assert!(last.x + last.w <= area.w && last.y + last.h <= area.h);
+    }
+}
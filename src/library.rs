@@ -0,0 +1,92 @@
+// Named library of well-known places, reachable with `:goto NAME` or the picker ('o'). Each
+// comes with an iteration count and palette that suit it.
+use crate::playlist::{self, Entry};
+
+// name | description | view in the playlist format
+const LIBRARY: &str = "\
+mandelbrot        | The whole Mandelbrot set                          | fractal=mandelbrot iters=120 palette=rainbow
+seahorse-valley   | Seahorse Valley, between the cardioid and bulb    | fractal=mandelbrot center=-0.745,0.105 zoom=20 iters=400 palette=rainbow
+elephant-valley   | Elephant Valley, east end of the cardioid         | fractal=mandelbrot center=0.282,0.011 zoom=30 iters=400 palette=ocean
+scepter-valley    | Scepter Valley, left of the period-2 bulb         | fractal=mandelbrot center=-1.36,0.005 zoom=20 iters=300 palette=fire
+mini-mandelbrot   | The period-3 copy of the set near -1.768          | fractal=mandelbrot center=-1.7685,0 zoom=40 iters=500 palette=rainbow
+triple-spiral     | Triple spiral valley near the top bulb            | fractal=mandelbrot center=-0.088,0.654 zoom=50 iters=500 palette=fire
+double-spiral     | Double spirals deep in Seahorse Valley            | fractal=mandelbrot center=-0.7453,0.1127 zoom=300 iters=600 palette=ocean
+spiral-galaxy     | Julia set of spiral galaxies                      | fractal=julia c=-0.7269,0.1889 iters=300 palette=ocean
+douady-rabbit     | Douady's rabbit                                   | fractal=julia c=-0.123,0.745 iters=160 palette=fire
+basilica          | The basilica, c = -1                              | fractal=julia c=-1,0 iters=200 palette=rainbow
+san-marco         | San Marco dragon, c = -3/4                        | fractal=julia c=-0.75,0 iters=300 palette=gray
+siegel-disk       | Siegel disk around a rotating fixed point         | fractal=julia c=-0.391,-0.587 iters=300 palette=ocean
+dendrite          | Dendrite, c = i (no interior at all)              | fractal=julia c=0,1 iters=200 palette=gray
+";
+
+#[derive(Clone, Debug)]
+pub struct Place {
+    pub name: &'static str,
+    pub about: &'static str,
+    pub entry: Entry,
+}
+
+pub fn all() -> Vec<Place> {
+    LIBRARY
+        .lines()
+        .map(|line| {
+            let mut cols = line.split('|').map(str::trim);
+            let (Some(name), Some(about), Some(view)) = (cols.next(), cols.next(), cols.next())
+            else {
+                panic!("bad library line '{line}'");
+            };
+            let entry = playlist::parse_entry(view).expect("library views are valid");
+            Place { name, about, entry }
+        })
+        .collect()
+}
+
+// By name, ignoring case, spaces and dashes; a unique prefix is enough
+pub fn find(query: &str) -> Result<Place, String> {
+    let key = |s: &str| -> String {
+        s.chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect()
+    };
+    let q = key(query);
+    let mut hits: Vec<Place> = all()
+        .into_iter()
+        .filter(|p| key(p.name).starts_with(&q))
+        .collect();
+    if let Some(i) = hits.iter().position(|p| key(p.name) == q) {
+        return Ok(hits.swap_remove(i));
+    }
+    match hits.len() {
+        1 => Ok(hits.remove(0)),
+        0 => Err(format!("no place called '{query}'")),
+        _ => {
+            let names: Vec<&str> = hits.iter().map(|p| p.name).collect();
+            Err(format!("'{query}' could be {}", names.join(", ")))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fractal::Fractal;
+    #[test]
+    fn finds_places_by_name() {
+        assert_eq!(all().len(), 13);
+        let p = find("Seahorse Valley").unwrap();
+        assert_eq!(p.entry.location.max_iters, 400);
+        assert_eq!(find("rabbit").unwrap_err(), "no place called 'rabbit'");
+        assert_eq!(
+            find("douady").unwrap().entry.location.fractal,
+            Fractal::Julia
+        );
+        assert!(
+            find("s")
+                .unwrap_err()
+                .contains("seahorse-valley, scepter-valley")
+        );
+        // An exact name wins over longer ones it prefixes
+        assert_eq!(find("mandelbrot").unwrap().name, "mandelbrot");
+    }
+}
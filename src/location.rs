@@ -0,0 +1,135 @@
+// A point in parameter space written as one line of `key=value` fields, e.g.
+// "fractal=julia c=-0.8,0.156 center=0,0 zoom=1 iters=120"; floats use Rust's shortest
+// round-trip form so deep zooms survive being copied around
+use crate::fractal::{Fractal, Viewport};
+use num_complex::Complex64;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Location {
+    pub fractal: Fractal,
+    pub view: Viewport,
+    // Julia parameter; ignored for the Mandelbrot set
+    pub c: Complex64,
+    pub max_iters: usize,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = self.fractal.name().to_lowercase();
+        write!(f, "fractal={name}")?;
+        if self.fractal == Fractal::Julia {
+            write!(f, " c={},{}", self.c.re, self.c.im)?;
+        }
+        let p = self.view.center;
+        write!(
+            f,
+            " center={},{} zoom={} iters={}",
+            p.re, p.im, self.view.zoom, self.max_iters
+        )
+    }
+}
+
+impl FromStr for Location {
+    type Err = String;
+
+    // `fractal` is required (and `c` for Julia); the view defaults to the fractal's home
+    fn from_str(s: &str) -> Result<Location, String> {
+        let mut fractal = None;
+        let (mut c, mut center, mut zoom, mut iters) = (None, None, None, None);
+        for field in s.split_whitespace() {
+            let (key, value) = field
+                .split_once('=')
+                .ok_or_else(|| format!("expected key=value, got '{field}'"))?;
+            let bad = || format!("invalid {key} '{value}'");
+            let pair = || {
+                let (re, im) = value.split_once(',').ok_or_else(bad)?;
+                match (re.trim().parse::<f64>(), im.trim().parse::<f64>()) {
+                    (Ok(re), Ok(im)) if re.is_finite() && im.is_finite() => {
+                        Ok(Complex64::new(re, im))
+                    }
+                    _ => Err(bad()),
+                }
+            };
+            match key {
+                "fractal" => {
+                    fractal = Some(match value.to_lowercase().as_str() {
+                        "julia" => Fractal::Julia,
+                        "mandelbrot" => Fractal::Mandelbrot,
+                        _ => return Err(bad()),
+                    })
+                }
+                "c" => c = Some(pair()?),
+                "center" => center = Some(pair()?),
+                "zoom" => match value.parse::<f64>() {
+                    Ok(z) if z.is_finite() && z > 0.0 => zoom = Some(z),
+                    _ => return Err(bad()),
+                },
+                "iters" => match value.parse::<usize>() {
+                    Ok(n) if n > 0 => iters = Some(n),
+                    _ => return Err(bad()),
+                },
+                _ => return Err(format!("unknown field '{key}'")),
+            }
+        }
+        let fractal = fractal.ok_or("missing fractal=julia|mandelbrot")?;
+        let c = match (fractal, c) {
+            (Fractal::Julia, None) => return Err("a Julia location needs c=RE,IM".to_string()),
+            (_, c) => c.unwrap_or_default(),
+        };
+        let home = fractal.home();
+        Ok(Location {
+            fractal,
+            view: Viewport {
+                center: center.unwrap_or(home.center),
+                zoom: zoom.unwrap_or(home.zoom),
+            },
+            c,
+            max_iters: iters.unwrap_or(crate::MAX_ITERS),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn formats_fields() {
+        let loc = Location {
+            fractal: Fractal::Mandelbrot,
+            view: Viewport {
+                center: Complex64::new(-0.743643887037151, 0.13182590420533),
+                zoom: 1e6,
+            },
+            c: Complex64::new(0.0, 0.0),
+            max_iters: 2000,
+        };
+        assert_eq!(
+            loc.to_string(),
+            "fractal=mandelbrot center=-0.743643887037151,0.13182590420533 zoom=1000000 iters=2000"
+        );
+        let julia = Location {
+            fractal: Fractal::Julia,
+            view: Fractal::Julia.home(),
+            c: Complex64::new(-0.8, 0.156),
+            max_iters: 120,
+        };
+        assert_eq!(
+            julia.to_string(),
+            "fractal=julia c=-0.8,0.156 center=0,0 zoom=1 iters=120"
+        );
+        assert_eq!(loc.to_string().parse(), Ok(loc));
+        assert_eq!(julia.to_string().parse(), Ok(julia));
+    }
+    #[test]
+    fn parses_partial_and_rejects_bad() {
+        let m: Location = "fractal=mandelbrot zoom=4".parse().unwrap();
+        assert_eq!(m.view.center, Fractal::Mandelbrot.home().center);
+        assert_eq!(m.view.zoom, 4.0);
+        assert!("fractal=julia".parse::<Location>().is_err());
+        assert!("fractal=mandelbrot zoom=0".parse::<Location>().is_err());
+        assert!("fractal=mandelbrot hue=3".parse::<Location>().is_err());
+        assert!("center=0,0".parse::<Location>().is_err());
+    }
+}
@@ -1,35 +1,271 @@
-//! Simplified animated Julia fractal renderer (only 'q' or Ctrl+C to quit)
-mod color; // only shared module retained
+//! Animated Julia fractal renderer with an optional split view ('q' or Ctrl+C to quit)
+mod anaglyph;
+mod autosave;
+mod caps;
+mod cli;
+mod clipboard;
+mod clock;
+mod color;
+mod command;
+mod compare;
+mod compositor;
+mod console;
+mod daily;
+mod dimension;
+mod explore;
+mod export;
+mod flow;
+mod font;
+mod fractal;
+mod gallery;
+mod game;
+mod governor;
+mod histogram;
+mod input;
+mod kaleido;
+mod layout;
+mod library;
+mod location;
+mod orbit;
+mod perturb;
+mod playlist;
+mod png;
+mod power;
+mod present;
+mod rays;
+mod record;
+mod render;
+mod sequence;
+mod share;
+mod sonify;
+mod stats;
+mod stereo;
+mod strip;
+mod term;
+mod texture;
+mod warp;
+mod width;
 
-use color::{hsv_to_256, shade};
+use clock::FrameClock;
+use color::{Background, ColorLevel, Ramp};
+use command::Command;
+use compositor::{Cell, Compositor, Z};
+use console::Console;
 use crossterm::{
     cursor,
     event::{self, Event, KeyCode, KeyEvent, KeyModifiers},
-    execute, queue,
-    terminal::{self, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+    queue, terminal,
 };
+use export::Export;
+use fractal::{Fractal, Precision};
+use gallery::Gallery;
+use histogram::Histogram;
+use layout::{Layout, Pane, Rect};
 use num_complex::Complex64;
+use playlist::Screensaver;
 use std::{
-    io::{self, Write},
+    io::{self, IsTerminal, Write},
     sync::{
         Arc,
         atomic::{AtomicBool, Ordering},
     },
-    time::{Duration, Instant},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
+use warp::Warp;
 
-// RAII terminal restore
-struct TermGuard;
-impl Drop for TermGuard {
-    fn drop(&mut self) {
-        let _ = terminal::disable_raw_mode();
-        let mut o = io::stdout();
-        let _ = execute!(o, cursor::Show, LeaveAlternateScreen);
-    }
-}
+// Smallest full-screen terminal that still fits panes plus the HUD
+const MIN_SIZE: (u16, u16) = (20, 5);
+// Height of the console panel including its title row
+const CONSOLE_ROWS: u16 = 8;
+// Starting Julia parameter (center of the wander) and iteration budget
+const START_C: Complex64 = Complex64::new(-0.8, 0.156);
+const MAX_ITERS: usize = 120;
+// Most iterations `]` goes up to
+const ITERS_LIMIT: usize = 10_000;
+// Frame rate in eco mode (iterations are halved too)
+const ECO_FPS: f64 = 15.0;
+// Screen pixels per font pixel in `:shot` images
+const SHOT_SCALE: usize = 2;
+// Iterations of the --poster frame, as a multiple of the live view's
+const POSTER_ITERS: usize = 4;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Running flag (Ctrl+C)
+    // `zoom` renders a frame sequence to disk and exits, without touching the terminal
+    if std::env::args().nth(1).as_deref() == Some("zoom") {
+        let job = match cli::parse_zoom(std::env::args().skip(2)) {
+            Ok(Some(job)) => job,
+            Ok(None) => {
+                println!("{}", cli::ZOOM_USAGE);
+                return Ok(());
+            }
+            Err(e) => {
+                eprintln!("error: {e}\n\n{}", cli::ZOOM_USAGE);
+                std::process::exit(2);
+            }
+        };
+        if let Err(e) = sequence::run(&job) {
+            eprintln!("error: {e}");
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+    let opts = match cli::parse(std::env::args().skip(1)) {
+        Ok(o) => o,
+        Err(e) => {
+            eprintln!("error: {e}\n\n{}", cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+    if opts.help {
+        println!("{}", cli::USAGE);
+        return Ok(());
+    }
+    // NO_COLOR / FORCE_COLOR / COLORTERM / TERM's terminfo entry decide the color level,
+    // unless --colors settles it
+    let env = |k: &str| std::env::var(k).ok();
+    let mut colors = match (opts.no_color, opts.colors) {
+        (true, _) => ColorLevel::Mono,
+        (false, Some(level)) => level,
+        (false, None) => color::detect_color(env, caps::terminfo_colors),
+    };
+    // Light terminals get darker colors; the terminal itself is asked later where possible
+    let mut background = opts.background.unwrap_or_else(|| {
+        env("COLORFGBG")
+            .and_then(|v| Background::from_colorfgbg(&v))
+            .unwrap_or_default()
+    });
+    // Screensaver playlist, checked before anything is drawn
+    let saver_entries = match (&opts.playlist, opts.screensaver) {
+        (Some(path), _) => {
+            let text = std::fs::read_to_string(path).map_err(|e| e.to_string());
+            match text.and_then(|t| playlist::parse(&t)) {
+                Ok(entries) => Some(entries),
+                Err(e) => {
+                    eprintln!("error: {path}: {e}");
+                    std::process::exit(2);
+                }
+            }
+        }
+        (None, true) => Some(playlist::builtin()),
+        (None, false) => None,
+    };
+    // Presentation script, likewise
+    let script = opts.present.as_deref().map(|path| {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string());
+        text.and_then(|t| present::parse(&t)).unwrap_or_else(|e| {
+            eprintln!("error: {path}: {e}");
+            std::process::exit(2);
+        })
+    });
+    // Recording to replay, likewise; with --export it becomes frames on disk instead
+    let recording = opts.play.as_deref().map(|path| {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string());
+        text.and_then(|t| record::Recording::parse(&t))
+            .unwrap_or_else(|e| {
+                eprintln!("error: {path}: {e}");
+                std::process::exit(2);
+            })
+    });
+    if let (Some(rec), Some(dir)) = (&recording, &opts.export) {
+        let size = opts.export_size.unwrap_or(sequence::DEFAULT_SIZE);
+        let fps = opts.fps.unwrap_or(cli::DEFAULT_FPS);
+        if let Err(e) = record::export(rec, dir, size, fps) {
+            eprintln!("error: {e}");
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+    // Shared session, set up before the terminal is taken over so failures go to stderr
+    let host = opts.host.as_deref().map(|a| {
+        let addr = share::address(a, true);
+        share::Host::start(&addr).unwrap_or_else(|e| {
+            eprintln!("error: can't host on {addr}: {e}");
+            std::process::exit(2);
+        })
+    });
+    let mut viewer = opts.join.as_deref().map(|a| {
+        let addr = share::address(a, false);
+        share::Viewer::connect(&addr).unwrap_or_else(|e| {
+            eprintln!("error: can't join {addr}: {e}");
+            std::process::exit(2);
+        })
+    });
+    // --daily / --seed: shared starting c, palette and wander
+    let seeded = opts
+        .seed
+        .or(recording.as_ref().and_then(|r| r.seed))
+        .or(opts.daily.then(daily::today))
+        .map(daily::from_seed);
+    let start_c = seeded.as_ref().map_or(START_C, |s| s.c);
+    let start_pane = Pane {
+        palette: seeded.as_ref().map_or_else(Default::default, |s| s.palette),
+        ..Pane::new(Fractal::Julia)
+    };
+    // --strip: a small frame every --refresh seconds, until whoever reads it goes away
+    if opts.strip {
+        let ramp = match &opts.ramp {
+            Some(r) => r.clone(),
+            None => Ramp::parse(strip::RAMP)?,
+        };
+        let size = (
+            opts.width.unwrap_or(strip::DEFAULT_SIZE.0),
+            opts.height.unwrap_or(strip::DEFAULT_SIZE.1),
+        );
+        let pane = strip::fit(&start_pane, size);
+        let refresh = Duration::from_secs_f64(opts.refresh.unwrap_or(strip::DEFAULT_REFRESH));
+        let format = opts.strip_format.unwrap_or_default();
+        loop {
+            let now = SystemTime::now().duration_since(UNIX_EPOCH);
+            let c = strip::param(start_c, now.unwrap_or_default().as_secs_f64());
+            let mut text = Vec::new();
+            write_frame(&mut text, colors, background, size, |layer, rect| {
+                render::draw_poster(layer, rect, &pane, c, (MAX_ITERS, MAX_ITERS), &ramp)
+            })?;
+            let text = strip::convert(&String::from_utf8_lossy(&text), format);
+            let mut out = io::stdout().lock();
+            if out
+                .write_all(text.as_bytes())
+                .and_then(|()| out.flush())
+                .is_err()
+                || opts.one_frame
+            {
+                return Ok(());
+            }
+            drop(out);
+            std::thread::sleep(refresh);
+        }
+    }
+    // Piped, redirected or --one-frame: no raw mode or cursor movement, just one frame of text
+    if opts.one_frame || !io::stdout().is_terminal() {
+        let ramp = opts.ramp.clone().unwrap_or_default();
+        return Ok(print_static_frame(
+            colors,
+            background,
+            &ramp,
+            &start_pane,
+            start_c,
+            (opts.width, opts.height),
+        )?);
+    }
+
+    // View of an earlier run that was killed or crashed, offered back when exploring
+    let mut restore = if opts.offers_restore() {
+        autosave::find_crashed()
+    } else {
+        None
+    };
+    // This run's own autosave, removed again on a clean exit
+    let mut autosave = Some(autosave::Autosave::new());
+    // `record FILE`: the session goes to FILE as it happens
+    let mut recorder = opts.record.as_deref().map(|path| {
+        record::Recorder::create(path, seeded.as_ref().map(|s| s.seed)).unwrap_or_else(|e| {
+            eprintln!("error: {path}: {e}");
+            std::process::exit(2);
+        })
+    });
+    // `play FILE`: the recording and how far into it the replay is
+    let mut player = recording.map(|rec| (rec, 0.0));
+    // Running flag, cleared by Ctrl+C, SIGTERM (service stop) and SIGHUP (window closed)
     let running = Arc::new(AtomicBool::new(true));
     {
         let r = running.clone();
@@ -38,25 +274,23 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         })?;
     }
 
-    // Terminal init
-    execute!(io::stdout(), EnterAlternateScreen, cursor::Hide)?;
-    terminal::enable_raw_mode()?;
-    let _g = TermGuard;
+    // Terminal init; widget mode (--region) draws in place on the normal screen
+    let alt_screen = opts.region.is_none();
+    let guard = term::enter(alt_screen)?;
     let mut out = io::stdout();
 
     // Constants
-    let max_iters: usize = 120;
-    let base_c = Complex64::new(-0.8, 0.156); // base Julia parameter center
+    let mut max_iters = MAX_ITERS;
+    let mut base_c = start_c; // base Julia parameter center
     // Smooth wandering (damped random walk) parameters
     let radius = 0.40; // soft bound for |offset|
     let accel_strength = 1.2; // random acceleration magnitude baseline
     let damping = 0.85; // velocity damping (0..1) higher => more damping
-    let mut last_time = Instant::now();
     // State for wandering c offset relative to base
     let mut offset = Complex64::new(0.0, 0.0);
     let mut vel = Complex64::new(0.0, 0.0);
     // Tiny PRNG (xorshift64*) to avoid external dependency
-    let mut rng: u64 = 0x9e3779b97f4a7c15; // seed
+    let mut rng: u64 = seeded.as_ref().map_or(0x9e3779b97f4a7c15, |s| s.rng); // seed
     #[inline]
     fn next_f(r: &mut u64) -> f64 {
         let mut x = *r;
@@ -70,44 +304,850 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
     let target_fps = 60.0;
     let target_dt = Duration::from_secs_f64(1.0 / target_fps);
+    // Eco mode: lower frame rate and iterations
+    let mut eco = opts.eco || power::on_battery();
+    // Nothing is drawn while the window is in the background, unless opted out. A host keeps
+    // going, as viewers would otherwise freeze whenever it switches to another window.
+    let pause_unfocused = !opts.no_focus_pause && host.is_none();
+    let mut focused = true;
     let mut frame: u64 = 0;
     let start = Instant::now();
-    let mut fps_smooth = target_fps;
+    // Animation steps and the HUD's FPS, both immune to stalls and clock jumps
+    let mut clock = FrameClock::new(start, target_fps);
+    // Frame times and work done, for the exit report
+    let mut session = stats::Session::default();
+    // Trades resolution, then iterations, for frame rate when frames run long for a while
+    let mut governor = governor::Governor::default();
+    // Julia parameter of the last frame
+    let mut c = base_c;
+    // Panes: the second one is only shown in the split layout
+    let mut layout = Layout::Single;
+    let mut panes = [start_pane, start_pane];
+    panes[1].mirror = true;
+    // Julia gallery overlaying the current layout while open
+    let mut gallery: Option<Gallery> = None;
+    // Julia parameter held at base_c instead of wandering around it
+    let mut frozen = false;
+    // "Find the minibrot" round in pane 1, if one is running
+    let mut hunt: Option<game::Hunt> = None;
+    // Step-through of one point's iteration over pane N; the wander holds still meanwhile
+    let mut teach: Option<(usize, orbit::Stepper)> = None;
+    // Automatic zooms into the most detailed part of pane N, one leg after another
+    let mut autopilot: Option<(usize, explore::Autopilot)> = None;
+    // Famous-places picker (cursor into the library) and the ':' command line, while open
+    let places = library::all();
+    let place_items: Vec<String> = places
+        .iter()
+        .map(|p| format!("{:<16} {}", p.name, p.about))
+        .collect();
+    let mut picker: Option<usize> = None;
+    let mut prompt: Option<String> = None;
+    // PNG export rendering in the background
+    let mut export: Option<Export> = None;
+    // HTML and font-rendered PNG files to save the next composed frame to (taken once the
+    // command line is gone)
+    let mut html: Option<String> = None;
+    let mut shot: Option<String> = None;
+    // Unattended playlist mode; Esc drops into the interactive view of the current entry
+    let interval = Duration::from_secs_f64(opts.interval.unwrap_or(20.0));
+    let mut saver = saver_entries.map(|e| Screensaver::new(e, interval, start));
+    // Scripted talk driving the first pane; Esc hands over to interactive exploring
+    let mut presenter = script.map(|s| present::Presenter::new(s, start));
+    let mut caption: Option<String> = None;
+    // Raw PCM sonification of a scanline over the focused pane
+    let mut sound = opts
+        .sonify
+        .clone()
+        .map(|p| sonify::Sonifier::start(p.into()));
+    let mut minimap = false;
+    // Panes drawn as a cross-eyed random-character stereogram instead of shaded
+    let mut stereogram = false;
+    // Panes drawn in red/cyan relief for 3D glasses
+    let mut relief = false;
+    // Box-counting dimension of the focused view's boundary, refreshed a few times a second
+    let mut dimension: Option<dimension::Meter> = None;
+    // Focused view drawn twice side by side with different render settings
+    let mut comparison: Option<compare::Compare> = None;
+    // Scanline or grain texture over the whole frame
+    let mut texture: Option<texture::Texture> = None;
+    // Kaleidoscope folding every pane's rendering around its center
+    let mut kaleidoscope: Option<kaleido::Kaleidoscope> = None;
+    // Particles streaming down the potential, one swarm per pane while on
+    let mut flows: Vec<flow::Flow> = Vec::new();
+    // Ray/equipotential overlay for Mandelbrot panes; rays are traced once
+    let mut show_rays = false;
+    let ray_paths: Vec<Vec<Complex64>> = opts
+        .rays
+        .as_deref()
+        .unwrap_or(&rays::DEFAULT_ANGLES)
+        .iter()
+        .map(|&a| rays::trace_ray(a, 40, 8))
+        .collect();
+    // Pane receiving navigation input, and its size in cells as of the last frame
+    let mut focus = 0usize;
+    let mut focus_dims = (80usize, 24usize);
+    // Iteration histogram of the visible panes, optionally shown as a bottom panel
+    let mut hist = Histogram::new(max_iters);
+    let mut show_hist = false;
+    // Event log, shown as a scrollable panel or briefly in the HUD
+    let mut console = Console::new(start);
+    let mut show_console = false;
+    // Ask the terminal what it supports. Only on the alternate screen, where anything a
+    // terminal echoes instead of answering is painted over by the first frame.
+    if alt_screen && colors > ColorLevel::Mono {
+        let probe = caps::probe(Duration::from_millis(200));
+        if probe.truecolor && opts.colors.is_none() {
+            colors = ColorLevel::TrueColor;
+        }
+        if let (None, Some(rgb)) = (opts.background, probe.background) {
+            background = Background::of_rgb(rgb);
+        }
+        let extra: Vec<&str> = [(probe.sixel, "sixel"), (probe.kitty, "kitty graphics")]
+            .iter()
+            .filter_map(|&(on, name)| on.then_some(name))
+            .collect();
+        let extra: String = extra.iter().map(|e| format!(", {e}")).collect();
+        console.push(format!(
+            "Colors: {colors:?}{extra}, {background:?} background"
+        ));
+    }
+    // Ambiguous-width glyphs like '█' take two cells in some CJK setups; check the first
+    // one the ramp uses where the first frame will cover it, and fall back to ASCII
+    let mut ramp = opts.ramp.clone().unwrap_or_default();
+    let ambiguous = ramp.chars().iter().find(|&&c| width::is_ambiguous(c));
+    if let Some(&ch) = ambiguous.filter(|_| width::cjk_locale(|k| std::env::var(k).ok())) {
+        let (x, y) = opts.region.map_or((0, 0), |r| (r.x, r.y));
+        if term::probe_width(ch, x, y) == Some(2) {
+            ramp = ramp.narrowed();
+            console.push(format!(
+                "{ch:?} renders double-width here, using an ASCII ramp"
+            ));
+        }
+    }
+    // Optional fixed aspect ratio for the pane area (letterboxed)
+    let mut aspect = opts.aspect;
+    // Terminal size, updated from resize events instead of being polled every frame
+    let mut size = terminal::size().unwrap_or((80, 24));
+    // Frame buffers for the fractal, overlay and chrome layers
+    let mut comp = Compositor::new(0, 0);
+    comp.set_background(background);
+
+    if eco {
+        console.push(if opts.eco {
+            "Eco mode on"
+        } else {
+            "On battery, eco mode on (b to turn off)"
+        });
+    }
 
     while running.load(Ordering::SeqCst) {
-        frame += 1;
-        let now = Instant::now();
-        let dt = (now - last_time).as_secs_f64();
-        last_time = now;
-        let frame_start = now;
+        let frame_start = Instant::now();
+        let dt = clock.tick(frame_start);
+        // Session time, the clock of recordings
+        let since_start = frame_start.duration_since(start).as_secs_f64();
 
-        // Input (only quit)
+        // Input
+        let mut resized = false;
+        let mut resumed = false;
+        let mut goto: Option<library::Place> = None;
+        let mut pending = Vec::new();
         while event::poll(Duration::from_millis(0))? {
-            if let Event::Key(KeyEvent {
+            pending.push(event::read()?);
+        }
+        // A replay types the recorded keys; of the real ones only q, Ctrl+C and Esc count
+        let mut replayed = None;
+        if let Some((rec, at)) = player.as_mut() {
+            let mut stop = false;
+            pending.retain(|ev| {
+                let Event::Key(k) = ev else {
+                    return !matches!(ev, Event::Paste(_));
+                };
+                match k.code {
+                    KeyCode::Char('q') => running.store(false, Ordering::SeqCst),
+                    KeyCode::Char('c') if k.modifiers.contains(KeyModifiers::CONTROL) => {
+                        running.store(false, Ordering::SeqCst)
+                    }
+                    KeyCode::Esc => stop = true,
+                    _ => {}
+                }
+                false
+            });
+            replayed = Some(rec.events(*at, since_start));
+            *at = since_start;
+            if stop {
+                player = None;
+                console.push("Replay stopped");
+            }
+        }
+        // Recorded events were batched as they were recorded, and keys typed into the
+        // command line are text, so neither has its repeats collapsed
+        let events = match replayed {
+            Some(events) => events,
+            None => input::batch(pending, prompt.is_none()),
+        };
+        for ev in events {
+            if let Some(r) = recorder.as_mut() {
+                r.event(since_start, &ev);
+            }
+            if let Event::Paste(text) = &ev {
+                // Only the command line takes text; don't let it turn into key presses
+                if let Some(line) = prompt.as_mut() {
+                    line.extend(text.chars().filter(|c| !c.is_control()));
+                    continue;
+                }
+                console.push(format!(
+                    "Ignored pasted text ({} chars)",
+                    text.chars().count()
+                ));
+                continue;
+            }
+            match ev {
+                Event::FocusGained => focused = true,
+                Event::FocusLost => focused = false,
+                _ => {}
+            }
+            if let Event::Resize(w, h) = ev {
+                // Several resizes can queue up while dragging; only the last one matters
+                size = (w, h);
+                resized = true;
+                continue;
+            }
+            let Event::Key(KeyEvent {
                 code, modifiers, ..
-            }) = event::read()?
-            {
-                if (modifiers.contains(KeyModifiers::CONTROL) && code == KeyCode::Char('c'))
-                    || code == KeyCode::Char('q')
-                {
+            }) = ev
+            else {
+                continue;
+            };
+            if let Some(p) = presenter.as_mut() {
+                let now = Instant::now();
+                match code {
+                    KeyCode::Char('q') => {
+                        running.store(false, Ordering::SeqCst);
+                        break;
+                    }
+                    KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+                        running.store(false, Ordering::SeqCst);
+                        break;
+                    }
+                    // Presentation clickers send PageDown/PageUp
+                    KeyCode::Right | KeyCode::PageDown | KeyCode::Enter => p.next(now),
+                    KeyCode::Left | KeyCode::PageUp => p.prev(now),
+                    KeyCode::Char(' ') => p.toggle_pause(now),
+                    KeyCode::Esc => {
+                        presenter = None;
+                        caption = None;
+                        console.push("Presentation stopped");
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+            if let Some(s) = saver.as_mut() {
+                match code {
+                    KeyCode::Char('q') => {
+                        running.store(false, Ordering::SeqCst);
+                        break;
+                    }
+                    KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+                        running.store(false, Ordering::SeqCst);
+                        break;
+                    }
+                    KeyCode::Char(' ') | KeyCode::Right => s.skip(1, Instant::now()),
+                    KeyCode::Left => s.skip(-1, Instant::now()),
+                    KeyCode::Esc => {
+                        show_entry(s.current(), &mut panes[0], &mut base_c, &mut max_iters);
+                        layout = Layout::Single;
+                        focus = 0;
+                        saver = None;
+                        console.push("Screensaver stopped");
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+            if let Some(r) = restore.as_ref() {
+                match code {
+                    KeyCode::Char('y') | KeyCode::Enter => {
+                        let s = r.state;
+                        for (p, n) in panes.iter_mut().zip(s.panes) {
+                            *p = n;
+                        }
+                        layout = s.layout;
+                        base_c = s.c;
+                        max_iters = s.max_iters;
+                        // Held still, so a Julia view comes back exactly as it was
+                        frozen = true;
+                        offset = Complex64::new(0.0, 0.0);
+                        vel = Complex64::new(0.0, 0.0);
+                        focus = 0;
+                        restore = None;
+                        console.push("Restored the autosaved view, wander paused (w resumes)");
+                    }
+                    KeyCode::Char('n') | KeyCode::Esc => {
+                        restore = None;
+                        console.push("Discarded the autosaved view");
+                    }
+                    KeyCode::Char('q') => {
+                        running.store(false, Ordering::SeqCst);
+                        break;
+                    }
+                    KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+                        running.store(false, Ordering::SeqCst);
+                        break;
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+            if let Some(line) = prompt.as_mut() {
+                match code {
+                    KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+                        running.store(false, Ordering::SeqCst);
+                        break;
+                    }
+                    KeyCode::Char(ch) => line.push(ch),
+                    // Backspace on an empty line closes it, like in vi
+                    KeyCode::Backspace if line.is_empty() => prompt = None,
+                    KeyCode::Backspace => {
+                        line.pop();
+                    }
+                    KeyCode::Esc => prompt = None,
+                    KeyCode::Enter => {
+                        let line = prompt.take().unwrap_or_default();
+                        match command::parse(&line) {
+                            Ok(None) => {}
+                            Ok(Some(Command::Goto(None))) => picker = Some(0),
+                            Ok(Some(Command::Goto(Some(name)))) => match library::find(&name) {
+                                Ok(p) => goto = Some(p),
+                                Err(e) => console.push(e),
+                            },
+                            Ok(Some(
+                                Command::Export { .. } | Command::Html(_) | Command::Shot(_),
+                            )) if player.is_some() => {
+                                console.push("Not writing files during a replay")
+                            }
+                            Ok(Some(Command::Export { .. })) if export.is_some() => {
+                                console.push("An export is already running")
+                            }
+                            Ok(Some(Command::Export { size, path })) => {
+                                let path = path.unwrap_or_else(|| stamped_name("png"));
+                                console.push(format!("Exporting {}x{} to {path}", size.0, size.1));
+                                let pane = panes[focus];
+                                export = Some(Export::start(pane, c, max_iters, size, path));
+                            }
+                            Ok(Some(Command::Html(path))) => {
+                                html = Some(path.unwrap_or_else(|| stamped_name("html")));
+                            }
+                            Ok(Some(Command::Shot(path))) => {
+                                shot = Some(path.unwrap_or_else(|| stamped_name("png")));
+                            }
+                            Ok(Some(Command::Texture(t))) => {
+                                texture = t;
+                                console.push(match &texture {
+                                    Some(t) => format!("Texture: {}", t.name()),
+                                    None => "Texture off".to_string(),
+                                });
+                            }
+                            Ok(Some(Command::Compare(Some(sides)))) => {
+                                let cmp = compare::Compare::new(sides);
+                                console.push(cmp.summary(max_iters));
+                                // Both halves show what the focused pane shows
+                                panes[0] = panes[focus];
+                                comparison = Some(cmp);
+                            }
+                            Ok(Some(Command::Compare(None))) => {
+                                if comparison.take().is_some() {
+                                    layout = Layout::Single;
+                                    console.push("Comparison off");
+                                }
+                            }
+                            Ok(Some(Command::Warp(w))) => {
+                                panes[focus].warp = w;
+                                console.push(format!("Pane {} warp: {}", focus + 1, w.name()));
+                            }
+                            Err(e) => console.push(e),
+                        }
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+            if let Some(i) = picker.as_mut() {
+                match code {
+                    KeyCode::Up => *i = i.saturating_sub(1),
+                    KeyCode::Down => *i = (*i + 1).min(places.len() - 1),
+                    KeyCode::Enter => {
+                        goto = Some(places[*i].clone());
+                        picker = None;
+                    }
+                    KeyCode::Esc | KeyCode::Char('o') => picker = None,
+                    KeyCode::Char('q') => {
+                        running.store(false, Ordering::SeqCst);
+                        break;
+                    }
+                    KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+                        running.store(false, Ordering::SeqCst);
+                        break;
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+            if let Some(g) = gallery.as_mut() {
+                match code {
+                    KeyCode::Left => g.move_cursor(-1, 0),
+                    KeyCode::Right => g.move_cursor(1, 0),
+                    KeyCode::Up => g.move_cursor(0, -1),
+                    KeyCode::Down => g.move_cursor(0, 1),
+                    KeyCode::Enter | KeyCode::Char(' ') => {
+                        // Promote: restart the wander from the chosen c in a single pane, or
+                        // hold c right there with Space
+                        base_c = g.selected();
+                        frozen = code == KeyCode::Char(' ');
+                        console.push(format!(
+                            "Promoted {} c=({:+.3},{:+.3}){}",
+                            g.names.get(g.cursor).unwrap_or(&"gallery"),
+                            base_c.re,
+                            base_c.im,
+                            if frozen {
+                                ", wander paused (w resumes)"
+                            } else {
+                                ""
+                            }
+                        ));
+                        offset = Complex64::new(0.0, 0.0);
+                        vel = Complex64::new(0.0, 0.0);
+                        layout = Layout::Single;
+                        panes[0] = Pane::new(Fractal::Julia);
+                        focus = 0;
+                        gallery = None;
+                    }
+                    KeyCode::Esc | KeyCode::Char('g') | KeyCode::Char('j') => {
+                        gallery = None;
+                    }
+                    _ => {}
+                }
+                if matches!(code, KeyCode::Char('q')) {
                     running.store(false, Ordering::SeqCst);
                     break;
                 }
+                continue;
+            }
+            if let Some((i, st)) = teach.as_mut() {
+                let view = panes[*i].view;
+                match code {
+                    KeyCode::Char(' ') => st.paused = !st.paused,
+                    KeyCode::Enter | KeyCode::Char('.') => {
+                        st.paused = true;
+                        st.step();
+                    }
+                    KeyCode::Left | KeyCode::Right | KeyCode::Up | KeyCode::Down => {
+                        // A fortieth of the view per press, then start over from z0
+                        let step = 0.075 / view.zoom;
+                        let d = match code {
+                            KeyCode::Left => Complex64::new(-step, 0.0),
+                            KeyCode::Right => Complex64::new(step, 0.0),
+                            KeyCode::Up => Complex64::new(0.0, step),
+                            _ => Complex64::new(0.0, -step),
+                        };
+                        st.restart(st.point + d);
+                    }
+                    KeyCode::Backspace => st.restart(st.point),
+                    KeyCode::Esc | KeyCode::Char('i') => teach = None,
+                    KeyCode::Char('q') => {
+                        running.store(false, Ordering::SeqCst);
+                        break;
+                    }
+                    KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+                        running.store(false, Ordering::SeqCst);
+                        break;
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+            match code {
+                KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+                    running.store(false, Ordering::SeqCst);
+                    break;
+                }
+                KeyCode::Char('q') => {
+                    running.store(false, Ordering::SeqCst);
+                    break;
+                }
+                #[cfg(unix)]
+                KeyCode::Char('z') if modifiers.contains(KeyModifiers::CONTROL) => {
+                    term::suspend()?;
+                    // The terminal may have been resized or drawn over while we were stopped
+                    size = terminal::size().unwrap_or(size);
+                    resumed = true;
+                    break;
+                }
+                KeyCode::Char('v') => {
+                    layout = match layout {
+                        Layout::Single => Layout::SPLIT,
+                        _ => Layout::Single,
+                    };
+                    console.push(match layout {
+                        Layout::Single => "Single view",
+                        _ => "Split view",
+                    });
+                    focus = 0;
+                }
+                KeyCode::Tab => focus = (focus + 1) % layout.pane_count(),
+                KeyCode::Left | KeyCode::Right if modifiers.contains(KeyModifiers::CONTROL) => {
+                    layout = layout.resize(if code == KeyCode::Left { -5 } else { 5 });
+                }
+                // Pane-local input below only affects the focused pane
+                KeyCode::Char('m') => {
+                    panes[focus].cycle_source();
+                    console.push(format!("Pane {} shows {}", focus + 1, panes[focus].label()));
+                }
+                KeyCode::Left | KeyCode::Right | KeyCode::Up | KeyCode::Down => {
+                    autopilot = None;
+                    let (fx, fy) = match code {
+                        KeyCode::Left => (-0.1, 0.0),
+                        KeyCode::Right => (0.1, 0.0),
+                        KeyCode::Up => (0.0, 0.1),
+                        _ => (0.0, -0.1),
+                    };
+                    panes[focus].view.pan(fx, fy);
+                }
+                KeyCode::Char('+') | KeyCode::Char('=') | KeyCode::Char('-') => {
+                    autopilot = None;
+                    let f = if code == KeyCode::Char('-') {
+                        0.8
+                    } else {
+                        1.25
+                    };
+                    panes[focus].view.zoom_by(f);
+                }
+                KeyCode::Char('0') => {
+                    autopilot = None;
+                    panes[focus].view = panes[focus].fractal.home();
+                }
+                KeyCode::Char('x') | KeyCode::Char('p') => {
+                    let (w, h) = focus_dims;
+                    let found = explore::find_target(&panes[focus], c, max_iters, w, h);
+                    match (code, found) {
+                        (KeyCode::Char('p'), _) if autopilot.is_some() => {
+                            autopilot = None;
+                            console.push("Autopilot off");
+                        }
+                        (_, None) => console.push("Nothing detailed in view to zoom into"),
+                        (KeyCode::Char('x'), Some((to, bits))) => {
+                            panes[focus].view = to;
+                            console
+                                .push(format!("Jumped to the most detailed tile ({bits:.1} bits)"));
+                        }
+                        (_, Some((to, _))) => {
+                            autopilot =
+                                Some((focus, explore::Autopilot::new(panes[focus].view, to)));
+                            console.push("Autopilot on (p or any navigation key stops it)");
+                        }
+                    }
+                }
+                KeyCode::Char('d') => {
+                    dimension = match dimension {
+                        Some(_) => None,
+                        None => Some(dimension::Meter::default()),
+                    };
+                    console.push(if dimension.is_some() {
+                        "Dimension estimate on"
+                    } else {
+                        "Dimension estimate off"
+                    });
+                }
+                KeyCode::Char('s') => {
+                    stereogram = !stereogram;
+                    console.push(if stereogram {
+                        "Stereogram on: cross your eyes until the two marks make three"
+                    } else {
+                        "Stereogram off"
+                    });
+                }
+                KeyCode::Char('r') => {
+                    relief = !relief;
+                    console.push(match (relief, colors) {
+                        (false, _) => "Anaglyph off",
+                        (true, ColorLevel::TrueColor) => {
+                            "Anaglyph on: red/cyan glasses, red on the left"
+                        }
+                        (true, _) => "Anaglyph on (coarse: the terminal reports no truecolor)",
+                    });
+                }
+                KeyCode::Char('u') => {
+                    texture = match texture.map(|t| t.pattern) {
+                        None => Some(texture::Texture::new(texture::Pattern::Scanlines)),
+                        Some(texture::Pattern::Scanlines) => texture.map(|t| texture::Texture {
+                            pattern: texture::Pattern::Noise,
+                            ..t
+                        }),
+                        Some(texture::Pattern::Noise) => None,
+                    };
+                    console.push(match &texture {
+                        Some(t) => format!("Texture: {} (:texture to adjust)", t.name()),
+                        None => "Texture off".to_string(),
+                    });
+                }
+                KeyCode::Char('y') => {
+                    kaleidoscope = match kaleidoscope {
+                        None => Some(kaleido::Kaleidoscope::first()),
+                        Some(k) => k.next(),
+                    };
+                    console.push(match kaleidoscope {
+                        Some(k) => format!("Kaleidoscope: {} segments", k.segments),
+                        None => "Kaleidoscope off".to_string(),
+                    });
+                }
+                KeyCode::Char('<') | KeyCode::Char('>') if kaleidoscope.is_some() => {
+                    if let Some(k) = kaleidoscope.as_mut() {
+                        let step = if code == KeyCode::Char('<') {
+                            -1.0
+                        } else {
+                            1.0
+                        };
+                        k.speed =
+                            ((k.speed / kaleido::SPEED_STEP).round() + step) * kaleido::SPEED_STEP;
+                        console.push(format!("Kaleidoscope turning {:+.2} rad/s", k.speed));
+                    }
+                }
+                KeyCode::Char('k') => {
+                    let w = panes[focus].warp.next();
+                    panes[focus].warp = w;
+                    console.push(format!("Pane {} warp: {}", focus + 1, w.name()));
+                }
+                KeyCode::Char('t') => {
+                    flows = if flows.is_empty() {
+                        (0..panes.len() as u64)
+                            .map(|i| flow::Flow::new(rng ^ i))
+                            .collect()
+                    } else {
+                        Vec::new()
+                    };
+                    console.push(if flows.is_empty() {
+                        "Flow particles off"
+                    } else {
+                        "Flow particles on"
+                    });
+                }
+                KeyCode::Char('n') => {
+                    minimap = !minimap;
+                    console.push(if minimap { "Minimap on" } else { "Minimap off" });
+                }
+                KeyCode::Char('e') => {
+                    show_rays = !show_rays;
+                    console.push(if show_rays {
+                        "Ray overlay on"
+                    } else {
+                        "Ray overlay off"
+                    });
+                }
+                KeyCode::Char('l') => show_console = !show_console,
+                KeyCode::Char('a') => {
+                    // Cycle off -> presets -> off
+                    let pos = layout::ASPECTS
+                        .iter()
+                        .position(|&(w, h)| aspect == Some(w as f64 / h as f64));
+                    let next = match pos {
+                        None if aspect.is_none() => Some(0),
+                        Some(i) if i + 1 < layout::ASPECTS.len() => Some(i + 1),
+                        _ => None,
+                    };
+                    aspect = next.map(|i| {
+                        let (w, h) = layout::ASPECTS[i];
+                        w as f64 / h as f64
+                    });
+                    console.push(match next {
+                        Some(i) => format!(
+                            "Aspect locked to {}:{}",
+                            layout::ASPECTS[i].0,
+                            layout::ASPECTS[i].1
+                        ),
+                        None => "Aspect unlocked".to_string(),
+                    });
+                }
+                KeyCode::PageUp | KeyCode::PageDown if show_console => {
+                    let step = CONSOLE_ROWS as isize - 1;
+                    let delta = if code == KeyCode::PageUp { step } else { -step };
+                    console.scroll_by(delta, CONSOLE_ROWS as usize - 1);
+                }
+                KeyCode::Char('h') => show_hist = !show_hist,
+                KeyCode::Char('[') | KeyCode::Char(']') => {
+                    let f = if code == KeyCode::Char('[') {
+                        0.8
+                    } else {
+                        1.25
+                    };
+                    max_iters = ((max_iters as f64 * f).round() as usize).clamp(16, ITERS_LIMIT);
+                    console.push(format!("Max iterations {max_iters}"));
+                }
+                KeyCode::Char('o') => picker = Some(0),
+                KeyCode::Char(':') => prompt = Some(String::new()),
+                KeyCode::Char('g') => {
+                    gallery = Some(Gallery::cardioid(4, 3));
+                    console.push("Gallery opened");
+                }
+                KeyCode::Char('j') => {
+                    gallery = Some(Gallery::presets());
+                    console.push("Julia presets opened");
+                }
+                KeyCode::Char('w') => {
+                    frozen = !frozen;
+                    console.push(if frozen {
+                        format!("Wander paused at c=({:+.3},{:+.3})", base_c.re, base_c.im)
+                    } else {
+                        "Wander resumed".to_string()
+                    });
+                }
+                KeyCode::Char('b') => {
+                    eco = !eco;
+                    console.push(if eco { "Eco mode on" } else { "Eco mode off" });
+                }
+                KeyCode::Char('c') => {
+                    // OSC 52: the terminal puts it on the clipboard, if it allows that
+                    let text = panes[focus].location(c, max_iters).to_string();
+                    let tmux = std::env::var_os("TMUX").is_some();
+                    out.write_all(clipboard::osc52(&text, tmux).as_bytes())?;
+                    console.push(format!("Copied {text}"));
+                }
+                KeyCode::Char('i') => {
+                    // Starts at the center of the focused pane
+                    let pane = &panes[focus];
+                    let st = orbit::Stepper::new(pane.fractal, pane.view.center, pane.param(c));
+                    teach = Some((focus, st));
+                    console.push("Step-through: iterating the point at the center");
+                }
+                KeyCode::Char('f') => match hunt.take() {
+                    Some(h) => console.push(format!("Gave up, the minibrot was at {}", h.target)),
+                    None => {
+                        // Every round starts from the whole set in a single pane
+                        layout = Layout::Single;
+                        focus = 0;
+                        panes[0] = Pane::new(Fractal::Mandelbrot);
+                        let h = game::Hunt::new((rng >> 32) as usize, &panes[0].view, frame_start);
+                        max_iters = max_iters.max(h.target.max_iters);
+                        hunt = Some(h);
+                        console.push("Find the minibrot: zoom in on the hidden target");
+                    }
+                },
+                _ => {}
             }
         }
 
-        let (tw, th) = terminal::size().unwrap_or((80, 24));
-        let width = tw as usize;
-        let height = th.saturating_sub(1) as usize; // leave last line for HUD
+        if let Some(p) = goto {
+            // Full screen from the place's own view, iterations and palette
+            show_entry(&p.entry, &mut panes[0], &mut base_c, &mut max_iters);
+            offset = Complex64::new(0.0, 0.0);
+            vel = Complex64::new(0.0, 0.0);
+            layout = Layout::Single;
+            focus = 0;
+            autopilot = None;
+            console.push(format!("{}: {}", p.name, p.about));
+        }
+        if let Some(done) = export.as_mut().and_then(Export::poll) {
+            let path = export.take().map(|e| e.path).unwrap_or_default();
+            console.push(match done {
+                Ok(()) => format!("Exported {path}"),
+                Err(e) => format!("Export to {path} failed: {e}"),
+            });
+        }
+        if resized || resumed {
+            // Wipe whatever the terminal reflowed; the compositor reallocates in `begin`
+            if alt_screen {
+                queue!(out, terminal::Clear(terminal::ClearType::All))?;
+            }
+        }
+        if let Some(h) = hunt.as_mut()
+            && panes[0].fractal == Fractal::Mandelbrot
+            && let Some(secs) = h.update(&panes[0].view, frame_start)
+        {
+            console.push(format!(
+                "Found the minibrot in {secs:.1}s, score {}",
+                game::score(secs)
+            ));
+            hunt = None;
+        }
+        if resized {
+            console.push(format!("Resized to {}x{}", size.0, size.1));
+        }
+        if resumed {
+            // Time spent stopped is not animation time
+            clock.resync(Instant::now());
+            console.push("Resumed");
+        }
+        if pause_unfocused && !focused {
+            // Sleep until something happens; waits this long don't count toward the FPS,
+            // and the animation carries on from where it stopped
+            event::poll(Duration::from_secs(1))?;
+            continue;
+        }
+        frame += 1;
+        let frame_dt = if eco {
+            Duration::from_secs_f64(1.0 / ECO_FPS)
+        } else {
+            target_dt
+        };
+        let budget = |n: usize| governor.iters(if eco { (n / 2).max(16) } else { n });
+        let iters = budget(max_iters);
+        // Cells per sample side, raised by the governor under sustained load
+        let block = governor.rung().block;
+        let (tw, th) = size;
+        let screen = match opts.region {
+            // Clamp the widget region to the screen
+            Some(r) => {
+                let x = r.x.min(tw.saturating_sub(1));
+                let y = r.y.min(th.saturating_sub(1));
+                Rect::new(x, y, r.w.min(tw - x), r.h.min(th - y))
+            }
+            None => Rect::new(0, 0, tw, th),
+        };
+        // Layers use coordinates local to `screen`; the last line is the HUD outside widget mode
+        let hud_rows = if opts.region.is_none() { 1 } else { 0 };
+        let area = Rect::new(0, 0, screen.w, screen.h.saturating_sub(hud_rows));
+        comp.begin(screen.w, screen.h);
+        comp.set_texture(texture);
+        if opts.region.is_none() && (screen.w < MIN_SIZE.0 || screen.h < MIN_SIZE.1) {
+            // Placeholder until the terminal grows again; the animation holds still meanwhile
+            render::draw_too_small(comp.layer(Z::Chrome), screen, MIN_SIZE);
+            comp.compose();
+            comp.present(&mut out, 0, 0, colors)?;
+            out.flush()?;
+            std::thread::sleep(frame_dt);
+            continue;
+        }
 
+        if let Some((_, st)) = teach.as_mut() {
+            st.tick(dt);
+        }
+        if let Some(k) = kaleidoscope.as_mut() {
+            k.advance(dt);
+        }
+        if let Some(t) = texture.as_mut() {
+            t.time += dt;
+        }
+        if let Some((i, ap)) = autopilot.as_mut() {
+            let (view, done) = ap.advance(dt);
+            panes[*i].view = view;
+            if done {
+                // Next leg from wherever this one ended up
+                let (w, h) = focus_dims;
+                match explore::find_target(&panes[*i], c, iters, w, h) {
+                    Some((to, _)) => *ap = explore::Autopilot::new(view, to),
+                    None => {
+                        console.push("Autopilot stopped: nothing detailed left in view");
+                        autopilot = None;
+                    }
+                }
+            }
+        }
         // Advance wandering animation
-        let dt_c = dt.min(0.1); // clamp large pauses
         let ax = next_f(&mut rng) * accel_strength;
         let ay = next_f(&mut rng) * accel_strength;
         let acc = Complex64::new(ax, ay);
         // Damped velocity + random acceleration
-        vel = vel * (1.0 - damping * dt_c) + acc * dt_c;
-        offset += vel * dt_c;
+        vel = vel * (1.0 - damping * dt) + acc * dt;
+        offset += vel * dt;
         // Soft boundary: if outside radius, pull inward (spring-like)
         let rlen = offset.norm();
         if rlen > radius {
@@ -121,74 +1161,535 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         if vel.norm() > radius * 2.0 {
             vel *= 0.5;
         }
-        let c = base_c + offset;
-
-        // Render Julia (no diffing; redraw whole frame)
-        queue!(out, cursor::MoveTo(0, 0))?;
-        for y in 0..height {
-            let im = (y as f64 / height as f64) * 2.0 - 1.0; // [-1,1]
-            let mut prev_color: Option<u8> = None;
-            for x in 0..width {
-                let re = (x as f64 / width as f64) * 3.0 - 1.5; // [-1.5,1.5]
-                let mut z = Complex64::new(re, im);
-                let mut iters = 0usize;
-                while z.norm_sqr() <= 4.0 && iters < max_iters {
-                    z = z * z + c;
-                    iters += 1;
-                }
-                if iters >= max_iters {
-                    if prev_color.is_some() {
-                        write!(out, "\x1b[0m")?;
-                        prev_color = None;
-                    }
-                    out.write_all(b" ")?;
-                } else {
-                    let norm = iters as f64 / max_iters as f64;
-                    let color = hsv_to_256(norm * 360.0, 0.9, 1.0);
-                    if prev_color != Some(color) {
-                        write!(out, "\x1b[38;5;{color}m")?;
-                        prev_color = Some(color);
+        if let Some(p) = presenter.as_mut() {
+            let scene = p.scene(frame_start);
+            show_entry(&scene.entry, &mut panes[0], &mut base_c, &mut max_iters);
+            layout = Layout::Single;
+            focus = 0;
+            caption = scene.caption;
+        }
+        // A viewer shows what the host sends, c included; local changes last until the next update
+        if let Some(v) = viewer.as_ref() {
+            match v.poll() {
+                Ok(Some(s)) => {
+                    // Warps are a local way of looking at the shared view
+                    for (p, n) in panes.iter_mut().zip(s.panes) {
+                        *p = Pane { warp: p.warp, ..n };
                     }
-                    let ch = shade(norm);
-                    write!(out, "{ch}")?;
+                    layout = s.layout;
+                    base_c = s.c;
+                    max_iters = s.max_iters;
+                    focus = focus.min(layout.pane_count() - 1);
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    console.push(format!("Left the session: {e}"));
+                    viewer = None;
                 }
             }
-            if prev_color.is_some() {
-                write!(out, "\x1b[0m")?;
+        }
+        // A replay shows the recorded view, warps aside, like a viewer
+        if let Some((rec, at)) = player.as_ref() {
+            let s = rec.state_at(*at);
+            for (p, n) in panes.iter_mut().zip(s.panes) {
+                *p = Pane { warp: p.warp, ..n };
+            }
+            layout = s.layout;
+            base_c = s.c;
+            max_iters = s.max_iters;
+            focus = focus.min(layout.pane_count() - 1);
+            if *at > rec.duration() {
+                player = None;
+                console.push("Replay finished");
+            }
+        }
+        if comparison.is_some() {
+            // The split can still be moved; navigation goes to the left pane and is mirrored
+            if !matches!(layout, Layout::Split { .. }) {
+                layout = Layout::SPLIT;
+            }
+            panes[1] = panes[0];
+            focus = 0;
+        }
+        if teach.is_none() {
+            c = if frozen || viewer.is_some() || presenter.is_some() || player.is_some() {
+                base_c
+            } else {
+                base_c + offset
+            };
+        }
+        let state = share::State {
+            c,
+            max_iters,
+            layout,
+            panes,
+        };
+        if let Some(h) = host.as_ref() {
+            h.publish(&state);
+        }
+        // Only views explored here are worth saving, not what a playlist, script or host shows
+        let exploring =
+            saver.is_none() && presenter.is_none() && viewer.is_none() && player.is_none();
+        let saved = autosave
+            .as_mut()
+            .filter(|_| exploring)
+            .map(|a| a.update(frame_start, &state));
+        if let Some(Err(e)) = saved {
+            console.push(format!("Autosave off: {e}"));
+            // An earlier save left behind would pass this run off as a crash next time
+            if let Some(a) = autosave.take() {
+                a.finish();
             }
-            out.write_all(b"\n")?;
+        }
+        let recorded = recorder
+            .as_mut()
+            .map(|r| r.frame(since_start, &state, running.load(Ordering::SeqCst)));
+        if let Some(Err(e)) = recorded {
+            console.push(format!("Recording stopped: {e}"));
+            recorder = None;
         }
 
-        // HUD
-        let frame_time = frame_start.elapsed().as_secs_f64();
-        let fps_inst = if frame_time > 0.0 {
-            1.0 / frame_time
+        // Render panes; they split the same cell area so the frame budget is shared
+        // Bottom panels take rows from the pane area: console above histogram
+        let hist_rows = if show_hist { 7.min(area.h / 3) } else { 0 };
+        let console_rows = if show_console {
+            CONSOLE_ROWS.min(area.h / 3)
         } else {
-            target_fps
+            0
         };
-        fps_smooth = fps_smooth * 0.85 + fps_inst * 0.15;
-        queue!(out, cursor::MoveTo(0, th.saturating_sub(1)))?;
-        queue!(out, terminal::Clear(ClearType::CurrentLine))?;
-        write!(
-            out,
-            "Julia anim | c=({:+.3},{:+.3}) | Frame {} | FPS {:.1} (q/Ctrl+C to quit)",
-            c.re, c.im, frame, fps_smooth
-        )?;
+        let panel = Rect::new(area.x, area.y + area.h - hist_rows, area.w, hist_rows);
+        let log_panel = Rect::new(area.x, panel.y - console_rows, area.w, console_rows);
+        let area = Rect::new(area.x, area.y, area.w, log_panel.y - area.y);
+        let area = match aspect {
+            Some(a) => {
+                // Bars stay on the fractal layer so panels can still cover them
+                comp.layer(Z::Fractal).fill(
+                    area,
+                    Cell {
+                        bg: Some(16),
+                        ..Cell::BLANK
+                    },
+                );
+                layout::letterbox(area, a)
+            }
+            None => area,
+        };
+        hist.reset(iters);
+        // Fractal cells drawn this frame and the deepest zoom among them, for the session stats
+        let mut drawn: u64 = 0;
+        let mut deepest: f64 = 0.0;
+        let cells = |r: &Rect| r.w as u64 * r.h as u64;
+        if let Some(g) = &gallery {
+            let tiles = Layout::Grid {
+                cols: g.cols,
+                rows: g.rows,
+            }
+            .rects(area);
+            let tile = Pane::new(Fractal::Julia);
+            drawn += tiles.iter().map(cells).sum::<u64>();
+            deepest = tile.view.zoom;
+            for (i, rect) in tiles.iter().enumerate() {
+                let p = g.params[i];
+                let fractal = comp.layer(Z::Fractal);
+                let hist = Some(&mut hist);
+                render::draw_pane_blocks(fractal, *rect, &tile, p, iters, &ramp, hist, block);
+                render::draw_label(comp.layer(Z::Chrome), *rect, &g.label(i), i == g.cursor);
+            }
+        }
+        if let Some(s) = saver.as_mut() {
+            let fade = s.update(frame_start);
+            let cur = *s.current();
+            hist.reset(budget(cur.location.max_iters));
+            let (pane, p) = saver_view(&cur, s.elapsed(frame_start), offset);
+            drawn += cells(&area);
+            deepest = pane.view.zoom;
+            let iters = budget(cur.location.max_iters);
+            let fractal = comp.layer(Z::Fractal);
+            let hist = Some(&mut hist);
+            render::draw_pane_blocks(fractal, area, &pane, p, iters, &ramp, hist, block);
+            if let Some(t) = fade {
+                // Incoming entry on the overlay, revealed cell by cell
+                let next = *s.next();
+                let (pane, p) = saver_view(&next, 0.0, offset);
+                let overlay = comp.layer(Z::Overlay);
+                let iters = budget(next.location.max_iters);
+                render::draw_pane_blocks(overlay, area, &pane, p, iters, &ramp, None, block);
+                overlay.mask(area, |x, y| playlist::dissolved(x, y, t));
+            }
+        }
+        let rects = if gallery.is_some() || saver.is_some() {
+            Vec::new()
+        } else {
+            layout.rects(area)
+        };
+        if let Some(r) = rects.get(focus) {
+            focus_dims = (r.w as usize, r.h as usize);
+        }
+        for (i, rect) in rects.iter().enumerate() {
+            let pane = &panes[i];
+            drawn += cells(rect);
+            deepest = deepest.max(pane.view.zoom);
+            if let Some(cmp) = comparison.as_mut() {
+                let drawn = Instant::now();
+                let fractal = comp.layer(Z::Fractal);
+                let side = cmp.sides[i];
+                compare::draw(fractal, *rect, pane, c, iters, &ramp, Some(&mut hist), side);
+                cmp.record(i, drawn.elapsed());
+            } else if stereogram {
+                stereo::draw(comp.layer(Z::Fractal), *rect, pane, c, iters);
+            } else if relief {
+                anaglyph::draw(comp.layer(Z::Fractal), *rect, pane, c, iters);
+            } else {
+                render::draw_pane_blocks(
+                    comp.layer(Z::Fractal),
+                    *rect,
+                    pane,
+                    c,
+                    iters,
+                    &ramp,
+                    Some(&mut hist),
+                    block,
+                );
+            }
+            if let Some(k) = &kaleidoscope {
+                k.apply(comp.layer(Z::Fractal), *rect);
+            }
+            let overlay = comp.layer(Z::Overlay);
+            if show_rays && pane.fractal == Fractal::Mandelbrot {
+                render::draw_rays_overlay(overlay, *rect, pane, &ray_paths, iters);
+            }
+            if let Some(f) = flows.get_mut(i) {
+                f.step(pane, c, iters, rect.w as usize, rect.h as usize, dt);
+                f.draw(overlay, *rect);
+            }
+            if minimap && pane.view.zoom >= 2.0 {
+                render::draw_minimap(overlay, *rect, pane, c, iters, &ramp);
+            }
+            if let Some((_, st)) = teach.as_ref().filter(|(t, _)| *t == i) {
+                render::draw_orbit(overlay, *rect, pane, st);
+                render::draw_info(comp.layer(Z::Chrome), *rect, &st.lines());
+            }
+            let chrome = comp.layer(Z::Chrome);
+            if let Some(cmp) = &comparison {
+                render::draw_label(chrome, *rect, &cmp.label(i, iters), false);
+            } else if rects.len() > 1 {
+                render::draw_label(chrome, *rect, pane.label(), i == focus);
+            }
+            if i > 0 {
+                render::draw_divider(chrome, *rect);
+            }
+        }
+        if let (Some(m), Some(r)) = (dimension.as_mut(), rects.get(focus)) {
+            let (w, h) = (r.w as usize, r.h as usize);
+            m.update(frame_start, &panes[focus], c, iters, w, h);
+            let lines = match m.value {
+                Some(e) => vec![
+                    format!("Box-counting dimension {:.3}", e.dim),
+                    format!("fit R² {:.3}, boxes 1-{} px", e.r2, e.max_box),
+                ],
+                None => vec!["No boundary in view to measure".to_string()],
+            };
+            render::draw_info(comp.layer(Z::Chrome), *r, &lines);
+        }
+        if let Some(snd) = sound.as_mut() {
+            let target = rects.get(focus).map(|r| (&panes[focus], c, *r));
+            if let Err(e) = snd.feed(target, iters, dt) {
+                console.push(format!("Sonification stopped: {e}"));
+                sound = None;
+            } else if let Some((_, _, r)) = target {
+                // Mark the row being played at the pane's left edge
+                let y = r.y + ((snd.scan * r.h as f64) as u16).min(r.h.saturating_sub(1));
+                comp.layer(Z::Overlay)
+                    .put(r.x, y, Cell::new('▶', Some(231)).bold());
+            }
+        }
+        if let Some(text) = &caption {
+            render::draw_caption(comp.layer(Z::Chrome), area, text);
+        }
+        if let Some(i) = picker {
+            let title = "Famous places (Enter go, Esc close)";
+            render::draw_menu(comp.layer(Z::Chrome), area, title, &place_items, i);
+        }
+        if show_hist {
+            render::draw_histogram(comp.layer(Z::Chrome), panel, &hist);
+        }
+        if show_console {
+            render::draw_console(comp.layer(Z::Chrome), log_panel, &console);
+        }
+
+        // HUD
+        if hud_rows > 0 {
+            let hud = if let Some(s) = &saver {
+                let e = s.current();
+                format!(
+                    "Screensaver {}/{} | {} | {} | zoom {} | Space next, Esc explore, q quit",
+                    s.index + 1,
+                    s.entries.len(),
+                    e.location.fractal.name(),
+                    e.palette.name(),
+                    fractal::format_zoom(saver_view(e, s.elapsed(frame_start), offset).0.view.zoom)
+                )
+            } else if let Some(p) = &presenter {
+                let (n, total) = p.slide();
+                let state = if p.ended() {
+                    " | end"
+                } else if p.paused() {
+                    " | paused"
+                } else {
+                    ""
+                };
+                format!(
+                    "Slide {}/{total}{state} | zoom {} | Right next, Left back, Space pause, Esc explore, q quit",
+                    n.min(total),
+                    fractal::format_zoom(panes[0].view.zoom)
+                )
+            } else if let Some(r) = &restore {
+                format!(
+                    "The last session ended abnormally. Restore its view from {} ago? y restore, n discard",
+                    autosave::format_age(r.age)
+                )
+            } else if let Some(line) = &prompt {
+                format!(":{line}█")
+            } else if let Some(g) = &gallery {
+                let p = g.selected();
+                let name = g
+                    .names
+                    .get(g.cursor)
+                    .map_or(String::new(), |n| format!(" {n}"));
+                format!(
+                    "Gallery | tile {}/{}{name} c=({:+.3},{:+.3}) | arrows select, Enter promote, Space freeze, Esc back",
+                    g.cursor + 1,
+                    g.params.len(),
+                    p.re,
+                    p.im
+                )
+            } else {
+                let pane = &panes[focus];
+                let hint = match console.toast() {
+                    Some(msg) => format!("| {msg}"),
+                    None => "(q quit)".to_string(),
+                };
+                // The seed goes first so it survives a narrow terminal
+                let seed = seeded
+                    .as_ref()
+                    .map_or(String::new(), |s| format!("seed {} | ", s.seed));
+                let exporting = export.as_ref().map_or(String::new(), |e| {
+                    format!("Export {:.0}% | ", e.progress() * 100.0)
+                });
+                let session = match (&host, &viewer) {
+                    (Some(h), _) => format!("Hosting {} ({} watching) | ", h.addr, h.viewers()),
+                    (_, Some(v)) => format!("Watching {} | ", v.addr),
+                    _ => String::new(),
+                };
+                let warp = match pane.warp {
+                    Warp::None => String::new(),
+                    w => format!(" ({})", w.name()),
+                };
+                let dim = match dimension.as_ref().map(|m| m.value) {
+                    Some(Some(e)) => format!(" | dim {:.2}", e.dim),
+                    Some(None) => " | dim -".to_string(),
+                    None => String::new(),
+                };
+                let game = hunt.as_ref().map_or(String::new(), |h| {
+                    format!("Hunt {:.0}s: {} | ", h.elapsed(frame_start), h.hint())
+                });
+                // Magnification, size of one cell in complex units, and how much f64 is left
+                let (w, h) = focus_dims;
+                let limit = match pane.view.precision(w, h) {
+                    Precision::Fine => "",
+                    Precision::Near => " near f64 limit!",
+                    Precision::Lost => " f64 limit reached!",
+                };
+                format!(
+                    "{session}{exporting}{game}{seed}{}{warp} | c=({:+.3},{:+.3}) | zoom {} px {}{limit}{dim} | iters {}{} | Frame {} | FPS {:.1} {hint}",
+                    pane.label(),
+                    c.re,
+                    c.im,
+                    fractal::format_zoom(pane.view.zoom),
+                    fractal::sci(pane.view.cell_size(w, h)),
+                    iters,
+                    if eco { " eco" } else { "" },
+                    frame,
+                    clock.fps()
+                )
+            };
+            let chrome = comp.layer(Z::Chrome);
+            let y = screen.h - 1;
+            chrome.fill(Rect::new(0, y, screen.w, 1), Cell::BLANK);
+            chrome.text(0, y, &hud, Cell::BLANK, screen.w);
+        }
+        comp.compose();
+        comp.present(&mut out, screen.x, screen.y, colors)?;
         out.flush()?;
+        if let Some(path) = html.take() {
+            let mut page = Vec::new();
+            comp.write_html(&mut page, colors)?;
+            console.push(match std::fs::write(&path, page) {
+                Ok(()) => format!("Saved the frame as {path}"),
+                Err(e) => format!("Could not write {path}: {e}"),
+            });
+        }
+        if let Some(path) = shot.take() {
+            let (w, h, rgb) = comp.rasterize(colors, SHOT_SCALE);
+            console.push(match std::fs::write(&path, png::encode_rgb(w, h, &rgb)) {
+                Ok(()) => format!("Saved a {w}x{h} picture of the frame as {path}"),
+                Err(e) => format!("Could not write {path}: {e}"),
+            });
+        }
+
+        session.record(frame_start.elapsed(), frame_dt, drawn, deepest);
+        if let Some(rung) = governor.record(Instant::now(), frame_start.elapsed(), frame_dt) {
+            console.push(format!("Frame load: quality now {}", rung.name));
+        }
 
         // Frame pacing
         let used = frame_start.elapsed();
-        if used < target_dt {
-            std::thread::sleep(target_dt - used);
+        if used < frame_dt {
+            std::thread::sleep(frame_dt - used);
         }
     }
 
-    let total = start.elapsed().as_secs_f64();
-    let avg = if total > 0.0 {
-        frame as f64 / total
-    } else {
-        0.0
+    let location = match &saver {
+        Some(s) => s.current().location,
+        None => panes[focus].location(c, max_iters),
+    };
+    if let Some(a) = autosave {
+        a.finish();
+    }
+    let recorded = recorder.map(|r| r.finish(start.elapsed().as_secs_f64()));
+    // What was on screen last, for --poster
+    let (last_pane, last_c) = match &saver {
+        Some(s) => saver_view(s.current(), s.elapsed(Instant::now()), offset),
+        None => (panes[focus], c),
     };
-    println!("Exited. Frames: {frame} Time: {total:.2}s Avg FPS: {avg:.2}");
+    let report = stats::Report {
+        frames: frame,
+        seconds: start.elapsed().as_secs_f64(),
+        session: &session,
+        location: location.to_string(),
+        cell_size: location.view.cell_size(focus_dims.0, focus_dims.1),
+    };
+    // Restore the terminal first so the summary lands on the normal screen
+    if let Some(r) = opts.region {
+        let th = size.1;
+        queue!(
+            out,
+            cursor::MoveTo(0, (r.y + r.h).min(th.saturating_sub(1)))
+        )?;
+        out.flush()?;
+    }
+    drop(guard);
+    // After SIGHUP there may be no terminal left to print to; that must not panic
+    let summary = report.text();
+    let _ = writeln!(io::stdout(), "{summary}");
+    if opts.poster {
+        // Full width under the summary, leaving a line for the prompt
+        let (tw, th) = terminal::size().unwrap_or(size);
+        let h = th.saturating_sub(summary.lines().count() as u16 + 1).max(1);
+        let iters = (location.max_iters * POSTER_ITERS, location.max_iters);
+        let _ = print_frame(colors, background, (tw.max(1), h), |layer, rect| {
+            render::draw_poster(layer, rect, &last_pane, last_c, iters, &ramp)
+        });
+    }
+    match opts.exit_report.as_deref() {
+        Some("-") => {
+            let _ = io::stdout().write_all(report.json().as_bytes());
+        }
+        Some(path) => {
+            if let Err(e) = std::fs::write(path, report.json()) {
+                let _ = writeln!(io::stderr(), "could not write {path}: {e}");
+            }
+        }
+        None => {}
+    }
+    if let (Some(path), Some(done)) = (&opts.record, recorded) {
+        let _ = match done {
+            Ok(()) => writeln!(io::stderr(), "Recorded to {path} (play {path} replays it)"),
+            Err(e) => writeln!(io::stderr(), "could not write {path}: {e}"),
+        };
+    }
     Ok(())
 }
+
+// Default export file name, e.g. "fractal-1760486400.png"
+fn stamped_name(ext: &str) -> String {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    format!("fractal-{secs}.{ext}")
+}
+
+// Single pane showing a playlist or library entry; a Julia entry's c becomes the wander's center
+fn show_entry(e: &playlist::Entry, pane: &mut Pane, base_c: &mut Complex64, max_iters: &mut usize) {
+    *pane = Pane {
+        view: e.location.view,
+        palette: e.palette,
+        warp: pane.warp,
+        ..Pane::new(e.location.fractal)
+    };
+    if e.location.fractal == Fractal::Julia {
+        *base_c = e.location.c;
+    }
+    *max_iters = e.location.max_iters;
+}
+
+// Pane and Julia parameter for a screensaver entry `secs` into its turn: Julia entries
+// wander around their c, every entry zooms in slowly
+fn saver_view(e: &playlist::Entry, secs: f64, offset: Complex64) -> (Pane, Complex64) {
+    let mut view = e.location.view;
+    view.zoom *= 1.03f64.powf(secs);
+    let pane = Pane {
+        view,
+        palette: e.palette,
+        ..Pane::new(e.location.fractal)
+    };
+    (pane, e.location.c + offset)
+}
+
+// Single frame for scripts and non-TTY output, sized from --width/--height, COLUMNS/LINES
+// or the controlling terminal
+fn print_static_frame(
+    colors: ColorLevel,
+    background: Background,
+    ramp: &Ramp,
+    pane: &Pane,
+    c: Complex64,
+    (width, height): (Option<u16>, Option<u16>),
+) -> io::Result<()> {
+    let env_dim = |k: &str| std::env::var(k).ok().and_then(|v| v.parse::<u16>().ok());
+    let (tw, th) = terminal::size().unwrap_or((80, 24));
+    let w = width.or(env_dim("COLUMNS")).unwrap_or(tw).max(1);
+    // One line short so the shell prompt doesn't scroll the top row away
+    let h = height
+        .unwrap_or_else(|| env_dim("LINES").unwrap_or(th).saturating_sub(1))
+        .max(1);
+    print_frame(colors, background, (w, h), |layer, rect| {
+        render::draw_pane(layer, rect, pane, c, MAX_ITERS, ramp, None)
+    })
+}
+
+// A w×h frame drawn by `draw` as lines of text on stdout
+fn print_frame(
+    colors: ColorLevel,
+    background: Background,
+    size: (u16, u16),
+    draw: impl FnOnce(&mut compositor::Layer, Rect),
+) -> io::Result<()> {
+    let mut out = io::stdout().lock();
+    write_frame(&mut out, colors, background, size, draw)?;
+    out.flush()
+}
+
+fn write_frame(
+    out: &mut impl Write,
+    colors: ColorLevel,
+    background: Background,
+    (w, h): (u16, u16),
+    draw: impl FnOnce(&mut compositor::Layer, Rect),
+) -> io::Result<()> {
+    let mut comp = Compositor::new(w, h);
+    comp.set_background(background);
+    draw(comp.layer(Z::Fractal), Rect::new(0, 0, w, h));
+    comp.compose();
+    comp.write_lines(out, colors)
+}
@@ -0,0 +1,143 @@
+// Step-through teaching mode: the escape-time iteration for one point, one step at a time,
+// with the orbit plotted over the pane and the bailout test spelled out
+use crate::fractal::Fractal;
+use num_complex::Complex64;
+
+// Automatic steps per second while playing
+const STEPS_PER_SEC: f64 = 2.0;
+// Orbits that survive this long are shown as (probably) inside the set
+const LIMIT: usize = 100;
+
+pub struct Stepper {
+    pub fractal: Fractal,
+    // The chosen point of the plane: z0 for Julia, c for Mandelbrot
+    pub point: Complex64,
+    // Julia parameter at the time the point was chosen
+    julia_c: Complex64,
+    // z0, z1, ... so far
+    pub orbit: Vec<Complex64>,
+    pub paused: bool,
+    due: f64,
+}
+
+impl Stepper {
+    pub fn new(fractal: Fractal, point: Complex64, julia_c: Complex64) -> Self {
+        let mut s = Stepper {
+            fractal,
+            point,
+            julia_c,
+            orbit: Vec::new(),
+            paused: false,
+            due: 0.0,
+        };
+        s.restart(point);
+        s
+    }
+
+    pub fn restart(&mut self, point: Complex64) {
+        self.point = point;
+        let z0 = match self.fractal {
+            Fractal::Julia => point,
+            Fractal::Mandelbrot => Complex64::new(0.0, 0.0),
+        };
+        self.orbit = vec![z0];
+        self.due = 0.0;
+    }
+
+    // Constant added each step
+    pub fn c(&self) -> Complex64 {
+        match self.fractal {
+            Fractal::Julia => self.julia_c,
+            Fractal::Mandelbrot => self.point,
+        }
+    }
+
+    pub fn z(&self) -> Complex64 {
+        *self.orbit.last().expect("orbit starts with z0")
+    }
+
+    pub fn escaped(&self) -> bool {
+        self.z().norm_sqr() > 4.0
+    }
+
+    pub fn done(&self) -> bool {
+        self.escaped() || self.orbit.len() > LIMIT
+    }
+
+    pub fn step(&mut self) {
+        if !self.done() {
+            let z = self.z();
+            self.orbit.push(z * z + self.c());
+        }
+    }
+
+    // Advance by real time while playing
+    pub fn tick(&mut self, dt: f64) {
+        if self.paused {
+            return;
+        }
+        self.due += dt * STEPS_PER_SEC;
+        while self.due >= 1.0 {
+            self.due -= 1.0;
+            self.step();
+        }
+    }
+
+    // The explanation panel, one line per row
+    pub fn lines(&self) -> Vec<String> {
+        let n = self.orbit.len() - 1;
+        let (c, z) = (self.c(), self.z());
+        let start = match self.fractal {
+            Fractal::Julia => "Julia: z0 = the point, c fixed",
+            Fractal::Mandelbrot => "Mandelbrot: z0 = 0, c = the point",
+        };
+        let test = if self.escaped() {
+            format!("|z|^2 = {:.4} > 4: escaped after {n} steps", z.norm_sqr())
+        } else if n >= LIMIT {
+            format!("no escape in {LIMIT} steps: counted as inside")
+        } else {
+            format!("|z|^2 = {:.4} <= 4: still bounded", z.norm_sqr())
+        };
+        let keys = if self.paused {
+            "Space play, Enter step, arrows move point, Esc close"
+        } else {
+            "Space pause, arrows move point, Esc close"
+        };
+        vec![
+            "z(n+1) = z(n)^2 + c".to_string(),
+            start.to_string(),
+            format!("c = {:+.5} {:+.5}i", c.re, c.im),
+            format!("n = {n}"),
+            format!("z = {:+.5} {:+.5}i", z.re, z.im),
+            test,
+            keys.to_string(),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn steps_until_bailout() {
+        // c = 1: 0, 1, 2, 5 escapes on the third step
+        let mut s = Stepper::new(
+            Fractal::Mandelbrot,
+            Complex64::new(1.0, 0.0),
+            Complex64::default(),
+        );
+        s.tick(0.75);
+        assert_eq!(s.orbit.len(), 2);
+        s.tick(10.0);
+        assert_eq!(
+            s.orbit,
+            [0.0, 1.0, 2.0, 5.0].map(|re| Complex64::new(re, 0.0))
+        );
+        assert!(s.lines()[5].contains("escaped after 3 steps"));
+        // Inside: stops at the limit
+        s.restart(Complex64::new(-1.0, 0.0));
+        s.tick(1000.0);
+        assert_eq!(s.orbit.len(), LIMIT + 1);
+        assert!(s.done() && !s.escaped());
+    }
+}
@@ -0,0 +1,147 @@
+// Screensaver playlist: locations with a palette, shown in turn with a dithered crossfade.
+// One entry per line in the location format plus `palette=NAME`; '#' starts a comment.
+use crate::color::Palette;
+use crate::location::Location;
+use std::time::{Duration, Instant};
+
+// Length of the crossfade at the end of each entry
+const FADE: Duration = Duration::from_secs(2);
+
+const BUILTIN: &str = "\
+# Curated tour
+fractal=julia c=-0.8,0.156 palette=rainbow
+fractal=mandelbrot center=-0.5,0 iters=200 palette=fire
+fractal=julia c=0.285,0.01 iters=200 palette=ocean
+fractal=mandelbrot center=-0.745,0.105 zoom=20 iters=400 palette=rainbow   # seahorse valley
+fractal=julia c=-0.123,0.745 iters=160 palette=fire                        # Douady rabbit
+fractal=mandelbrot center=0.282,0.011 zoom=30 iters=400 palette=ocean     # elephant valley
+fractal=julia c=0,1 iters=200 palette=gray                                # dendrite
+fractal=mandelbrot center=-0.088,0.654 zoom=50 iters=500 palette=fire     # triple spiral
+";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Entry {
+    pub location: Location,
+    pub palette: Palette,
+}
+
+pub fn builtin() -> Vec<Entry> {
+    parse(BUILTIN).expect("built-in playlist is valid")
+}
+
+pub fn parse(text: &str) -> Result<Vec<Entry>, String> {
+    let mut entries = Vec::new();
+    for (n, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+        entries.push(parse_entry(line).map_err(|e| format!("line {}: {e}", n + 1))?);
+    }
+    if entries.is_empty() {
+        return Err("playlist has no entries".to_string());
+    }
+    Ok(entries)
+}
+
+// One view: a location plus an optional `palette=NAME` field anywhere in it
+pub fn parse_entry(line: &str) -> Result<Entry, String> {
+    let mut palette = Palette::default();
+    let mut rest = Vec::new();
+    for field in line.split_whitespace() {
+        match field.strip_prefix("palette=") {
+            Some(name) => palette = Palette::parse(name)?,
+            None => rest.push(field),
+        }
+    }
+    let location = rest.join(" ").parse()?;
+    Ok(Entry { location, palette })
+}
+
+pub struct Screensaver {
+    pub entries: Vec<Entry>,
+    interval: Duration,
+    pub index: usize,
+    since: Instant,
+}
+
+impl Screensaver {
+    pub fn new(entries: Vec<Entry>, interval: Duration, now: Instant) -> Self {
+        Screensaver {
+            entries,
+            interval: interval.max(FADE * 2),
+            index: 0,
+            since: now,
+        }
+    }
+
+    pub fn current(&self) -> &Entry {
+        &self.entries[self.index]
+    }
+
+    pub fn next(&self) -> &Entry {
+        &self.entries[(self.index + 1) % self.entries.len()]
+    }
+
+    // Seconds the current entry has been showing
+    pub fn elapsed(&self, now: Instant) -> f64 {
+        now.saturating_duration_since(self.since).as_secs_f64()
+    }
+
+    pub fn skip(&mut self, delta: isize, now: Instant) {
+        let n = self.entries.len() as isize;
+        self.index = (self.index as isize + delta).rem_euclid(n) as usize;
+        self.since = now;
+    }
+
+    // Advance when the current entry's time is up; returns the crossfade progress (0..1)
+    // toward the next entry while it is running
+    pub fn update(&mut self, now: Instant) -> Option<f64> {
+        if now.saturating_duration_since(self.since) >= self.interval {
+            self.skip(1, now);
+        }
+        let left = self
+            .interval
+            .saturating_sub(now.saturating_duration_since(self.since));
+        (left < FADE).then(|| 1.0 - left.as_secs_f64() / FADE.as_secs_f64())
+    }
+}
+
+// Whether cell (x, y) already shows the incoming frame at crossfade progress `t`
+// (ordered 4x4 Bayer dither, so the dissolve is even rather than blotchy)
+pub fn dissolved(x: u16, y: u16, t: f64) -> bool {
+    const BAYER: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+    let threshold = (BAYER[y as usize % 4][x as usize % 4] as f64 + 0.5) / 16.0;
+    t >= threshold
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fractal::Fractal;
+    #[test]
+    fn parses_playlists() {
+        assert_eq!(builtin().len(), 8);
+        let p = parse("# tour\n\nfractal=mandelbrot zoom=2 palette=gray # note\n").unwrap();
+        assert_eq!(p[0].location.fractal, Fractal::Mandelbrot);
+        assert_eq!(p[0].palette, Palette::Gray);
+        let err = parse("fractal=mandelbrot\nfractal=julia palette=fire\n").unwrap_err();
+        assert!(err.starts_with("line 2:"), "{err}");
+        assert!(parse("# nothing\n").is_err());
+    }
+    #[test]
+    fn advances_with_crossfade() {
+        let t0 = Instant::now();
+        let mut s = Screensaver::new(builtin(), Duration::from_secs(10), t0);
+        assert_eq!(s.update(t0 + Duration::from_secs(5)), None);
+        let t = s.update(t0 + Duration::from_secs(9)).unwrap();
+        assert!((t - 0.5).abs() < 1e-9);
+        assert_eq!(s.index, 0);
+        s.update(t0 + Duration::from_secs(10));
+        assert_eq!(s.index, 1);
+        s.skip(-2, t0);
+        assert_eq!(s.index, 7);
+        let shown = (0..16).filter(|&i| dissolved(i % 4, i / 4, 0.5)).count();
+        assert_eq!(shown, 8);
+    }
+}
@@ -0,0 +1,51 @@
+// Whether the machine runs on battery, for switching eco mode on by itself. Only Linux
+// exposes this without a platform API (sysfs power supplies); elsewhere it's never detected.
+use std::path::Path;
+
+pub fn on_battery() -> bool {
+    let Ok(dir) = std::fs::read_dir("/sys/class/power_supply") else {
+        return false;
+    };
+    let read = |p: &Path, f: &str| {
+        std::fs::read_to_string(p.join(f))
+            .map(|s| s.trim().to_string())
+            .unwrap_or_default()
+    };
+    let supplies: Vec<(String, String, String)> = dir
+        .flatten()
+        .map(|e| {
+            let p = e.path();
+            (read(&p, "type"), read(&p, "online"), read(&p, "status"))
+        })
+        .collect();
+    discharging(&supplies)
+}
+
+// (type, online, status) per supply: on battery when a battery is discharging and no
+// mains adapter is online. Desktops (no battery) and unknown states count as plugged in.
+fn discharging(supplies: &[(String, String, String)]) -> bool {
+    let mains_online = supplies
+        .iter()
+        .any(|(t, online, _)| t == "Mains" && online == "1");
+    let draining = supplies
+        .iter()
+        .any(|(t, _, status)| t == "Battery" && status == "Discharging");
+    draining && !mains_online
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn supply(t: &str, online: &str, status: &str) -> (String, String, String) {
+        (t.into(), online.into(), status.into())
+    }
+    #[test]
+    fn reads_supply_states() {
+        let laptop = |online, status| [supply("Mains", online, ""), supply("Battery", "", status)];
+        assert!(discharging(&laptop("0", "Discharging")));
+        assert!(!discharging(&laptop("1", "Charging")));
+        assert!(!discharging(&[supply("Mains", "1", "")]));
+        assert!(!discharging(&[supply("Battery", "", "Unknown")]));
+        assert!(!discharging(&[]));
+    }
+}
@@ -0,0 +1,328 @@
+// Presentation scripts: timed steps that drive the view for talks, one per line.
+//
+//   show 10 fractal=mandelbrot center=-0.5,0 palette=fire   # jump there and hold 10 s
+//   caption The seahorse valley
+//   zoom 8 center=-0.745,0.105 zoom=200 iters=400           # fly there over 8 s
+//   palette ocean
+//   wait 5
+//
+// `show`, `zoom` and `wait` take time and are the slides that next/previous step between;
+// `palette` and `caption` (empty to clear) take effect at once.
+use crate::color::Palette;
+use crate::fractal::{Fractal, Viewport};
+use crate::location::Location;
+use crate::playlist::{self, Entry};
+use num_complex::Complex64;
+use std::time::Instant;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Step {
+    Show { secs: f64, entry: Entry },
+    Zoom { secs: f64, to: ZoomTo },
+    Wait(f64),
+    Palette(Palette),
+    Caption(String),
+}
+
+// Fields a zoom changes; the rest stays as it is
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ZoomTo {
+    pub center: Option<Complex64>,
+    pub zoom: Option<f64>,
+    pub iters: Option<usize>,
+}
+
+impl Step {
+    fn secs(&self) -> Option<f64> {
+        match self {
+            Step::Show { secs, .. } | Step::Zoom { secs, .. } | Step::Wait(secs) => Some(*secs),
+            Step::Palette(_) | Step::Caption(_) => None,
+        }
+    }
+}
+
+// What is on screen at some point of the script
+#[derive(Clone, Debug, PartialEq)]
+pub struct Scene {
+    pub entry: Entry,
+    pub caption: Option<String>,
+}
+
+pub fn parse(text: &str) -> Result<Vec<Step>, String> {
+    let mut steps = Vec::new();
+    for (n, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+        steps.push(parse_step(line).map_err(|e| format!("line {}: {e}", n + 1))?);
+    }
+    if !steps.iter().any(|s| s.secs().is_some()) {
+        return Err("script has no show, zoom or wait steps".to_string());
+    }
+    Ok(steps)
+}
+
+fn parse_step(line: &str) -> Result<Step, String> {
+    let (verb, rest) = line.split_once(' ').unwrap_or((line, ""));
+    let rest = rest.trim();
+    // Timed steps start with their duration
+    let timed = || -> Result<(f64, &str), String> {
+        let (secs, rest) = rest.split_once(' ').unwrap_or((rest, ""));
+        match secs.parse::<f64>() {
+            Ok(s) if s.is_finite() && s >= 0.0 => Ok((s, rest.trim())),
+            _ => Err(format!("{verb} needs a duration in seconds, got '{secs}'")),
+        }
+    };
+    match verb {
+        "show" => {
+            let (secs, rest) = timed()?;
+            Ok(Step::Show {
+                secs,
+                entry: playlist::parse_entry(rest)?,
+            })
+        }
+        "zoom" => {
+            let (secs, rest) = timed()?;
+            let mut to = ZoomTo::default();
+            // Borrow the location parser for the fields a zoom may set
+            let loc: Location = format!("fractal=mandelbrot {rest}").parse()?;
+            for field in rest.split_whitespace() {
+                match field.split_once('=').map(|f| f.0) {
+                    Some("center") => to.center = Some(loc.view.center),
+                    Some("zoom") => to.zoom = Some(loc.view.zoom),
+                    Some("iters") => to.iters = Some(loc.max_iters),
+                    _ => return Err(format!("zoom takes center, zoom and iters, got '{field}'")),
+                }
+            }
+            Ok(Step::Zoom { secs, to })
+        }
+        "wait" => Ok(Step::Wait(timed()?.0)),
+        "palette" => Ok(Step::Palette(Palette::parse(rest)?)),
+        "caption" => Ok(Step::Caption(rest.to_string())),
+        _ => Err(format!(
+            "unknown step '{verb}' (expected show, zoom, wait, palette or caption)"
+        )),
+    }
+}
+
+pub struct Presenter {
+    steps: Vec<Step>,
+    // Running timed step, or steps.len() once the script is over
+    index: usize,
+    since: Instant,
+    paused: Option<Instant>,
+}
+
+impl Presenter {
+    pub fn new(steps: Vec<Step>, now: Instant) -> Self {
+        let mut p = Presenter {
+            steps,
+            index: 0,
+            since: now,
+            paused: None,
+        };
+        p.index = p.timed_from(0);
+        p
+    }
+
+    // First timed step at or after `i`
+    fn timed_from(&self, i: usize) -> usize {
+        (i..self.steps.len())
+            .find(|&j| self.steps[j].secs().is_some())
+            .unwrap_or(self.steps.len())
+    }
+
+    fn elapsed(&self, now: Instant) -> f64 {
+        let at = self.paused.unwrap_or(now);
+        at.saturating_duration_since(self.since).as_secs_f64()
+    }
+
+    pub fn ended(&self) -> bool {
+        self.index == self.steps.len()
+    }
+
+    pub fn paused(&self) -> bool {
+        self.paused.is_some()
+    }
+
+    // Slide number (from 1) and count
+    pub fn slide(&self) -> (usize, usize) {
+        let timed = |s: &[Step]| s.iter().filter(|s| s.secs().is_some()).count();
+        (timed(&self.steps[..self.index]) + 1, timed(&self.steps))
+    }
+
+    pub fn next(&mut self, now: Instant) {
+        if !self.ended() {
+            self.index = self.timed_from(self.index + 1);
+        }
+        self.since = now;
+        self.paused = self.paused.map(|_| now);
+    }
+
+    pub fn prev(&mut self, now: Instant) {
+        self.index = (0..self.index)
+            .rev()
+            .find(|&j| self.steps[j].secs().is_some())
+            .unwrap_or(self.timed_from(0));
+        self.since = now;
+        self.paused = self.paused.map(|_| now);
+    }
+
+    pub fn toggle_pause(&mut self, now: Instant) {
+        match self.paused.take() {
+            // Shift the start so the paused time doesn't count
+            Some(at) => self.since += now.saturating_duration_since(at),
+            None => self.paused = Some(now),
+        }
+    }
+
+    // Scene at `now`, moving on to the next timed step when this one's time is up
+    pub fn scene(&mut self, now: Instant) -> Scene {
+        while let Some(secs) = self.steps.get(self.index).and_then(Step::secs) {
+            let t = self.elapsed(now);
+            if t < secs {
+                return self.scene_at(t / secs);
+            }
+            self.since += std::time::Duration::from_secs_f64(secs);
+            self.index = self.timed_from(self.index + 1);
+        }
+        self.scene_at(1.0)
+    }
+
+    // Every step before the running one done, and the running one `t` (0..1) of the way
+    fn scene_at(&self, t: f64) -> Scene {
+        let mut scene = Scene {
+            entry: Entry {
+                location: Location {
+                    fractal: Fractal::Mandelbrot,
+                    view: Fractal::Mandelbrot.home(),
+                    c: Complex64::new(0.0, 0.0),
+                    max_iters: crate::MAX_ITERS,
+                },
+                palette: Palette::default(),
+            },
+            caption: None,
+        };
+        let last = self.index.min(self.steps.len().saturating_sub(1));
+        for (i, step) in self.steps.iter().enumerate().take(last + 1) {
+            let t = if i < self.index { 1.0 } else { t };
+            let loc = &mut scene.entry.location;
+            match step {
+                Step::Show { entry, .. } => scene.entry = *entry,
+                Step::Zoom { to, .. } => {
+                    let target = Viewport {
+                        center: to.center.unwrap_or(loc.view.center),
+                        zoom: to.zoom.unwrap_or(loc.view.zoom),
+                    };
+                    loc.view = fly(loc.view, target, smoothstep(t));
+                    if t >= 1.0 {
+                        loc.max_iters = to.iters.unwrap_or(loc.max_iters);
+                    }
+                }
+                Step::Wait(_) => {}
+                Step::Palette(p) => scene.entry.palette = *p,
+                Step::Caption(text) => {
+                    scene.caption = (!text.is_empty()).then(|| text.clone());
+                }
+            }
+        }
+        scene
+    }
+}
+
+fn smoothstep(t: f64) -> f64 {
+    let t = t.clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+// View `t` of the way from `a` to `b`: zoom geometric, and the center moving in step
+// with it, so the target glides to the middle instead of racing out of view first
+fn fly(a: Viewport, b: Viewport, t: f64) -> Viewport {
+    let zoom = a.zoom * (b.zoom / a.zoom).powf(t);
+    let share = if (b.zoom / a.zoom - 1.0).abs() < 1e-9 {
+        t
+    } else {
+        (1.0 - a.zoom / zoom) / (1.0 - a.zoom / b.zoom)
+    };
+    Viewport {
+        center: a.center + (b.center - a.center) * share,
+        zoom,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    const SCRIPT: &str = "\
+show 10 fractal=julia c=-0.8,0.156 palette=fire
+caption Hello
+zoom 4 center=0.5,0 zoom=16   # closer
+palette ocean
+caption
+wait 2
+";
+    #[test]
+    fn parses_scripts() {
+        let steps = parse(SCRIPT).unwrap();
+        assert_eq!(steps.len(), 6);
+        assert_eq!(steps[4], Step::Caption(String::new()));
+        let err = parse("caption hi\nzoom fast zoom=2\n").unwrap_err();
+        assert!(err.starts_with("line 2:"), "{err}");
+        assert!(parse("zoom 2 c=1,1").is_err());
+        assert!(parse("caption only").is_err());
+    }
+    #[test]
+    fn runs_through_the_steps() {
+        let t0 = Instant::now();
+        let at = |s: f64| t0 + Duration::from_secs_f64(s);
+        let mut p = Presenter::new(parse(SCRIPT).unwrap(), t0);
+        let s = p.scene(at(5.0));
+        assert_eq!(s.entry.location.fractal, Fractal::Julia);
+        assert_eq!((s.caption, p.slide()), (None, (1, 3)));
+        // Halfway through the zoom, the caption is up and the palette still fire
+        let s = p.scene(at(12.0));
+        assert_eq!(s.caption.as_deref(), Some("Hello"));
+        assert_eq!(s.entry.palette, Palette::Fire);
+        assert!((s.entry.location.view.zoom - 4.0).abs() < 1e-9);
+        let s = p.scene(at(15.0));
+        assert_eq!((s.caption, s.entry.palette), (None, Palette::Ocean));
+        assert_eq!(s.entry.location.view.zoom, 16.0);
+        assert!(!p.ended());
+        p.scene(at(17.0));
+        assert!(p.ended());
+        // Back through the wait to the zoom, from its start
+        p.prev(at(20.0));
+        assert_eq!(p.slide(), (3, 3));
+        p.prev(at(20.0));
+        assert_eq!(p.slide(), (2, 3));
+        assert_eq!(p.scene(at(20.0)).entry.location.view.zoom, 1.0);
+        p.next(at(21.0));
+        p.toggle_pause(at(21.0));
+        assert!(!p.ended());
+        p.scene(at(100.0));
+        assert_eq!(p.slide(), (3, 3));
+    }
+    #[test]
+    fn flight_keeps_the_target_in_place() {
+        let a = Viewport {
+            center: Complex64::new(0.0, 0.0),
+            zoom: 1.0,
+        };
+        let b = Viewport {
+            center: Complex64::new(1.0, 0.0),
+            zoom: 100.0,
+        };
+        assert_eq!(fly(a, b, 1.0).center, b.center);
+        // On screen the target only ever moves toward the middle
+        let mut last = 1.0;
+        for i in 1..=10 {
+            let v = fly(a, b, i as f64 / 10.0);
+            let off = (b.center - v.center).re * v.zoom;
+            assert!(off <= last + 1e-9, "{i}: {off}");
+            last = off;
+        }
+        assert!((fly(a, b, 0.5).zoom - 10.0).abs() < 1e-9);
+    }
+}
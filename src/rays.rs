@@ -0,0 +1,109 @@
+// External rays and equipotentials of the Mandelbrot set (Douady–Hubbard structure)
+use crate::fractal::Fractal;
+use num_complex::Complex64;
+use std::f64::consts::TAU;
+
+// Angles in turns; rational angles with odd denominators land on bulb roots
+pub const DEFAULT_ANGLES: [f64; 10] = [
+    0.0,
+    1.0 / 2.0,
+    1.0 / 3.0,
+    2.0 / 3.0,
+    1.0 / 7.0,
+    2.0 / 7.0,
+    3.0 / 7.0,
+    4.0 / 7.0,
+    5.0 / 7.0,
+    6.0 / 7.0,
+];
+// Radius where the Böttcher map is close enough to the identity to start a ray
+const RAY_START: f64 = 64.0;
+// Bailout for the smooth count; large so the level sets are round equipotentials
+const POTENTIAL_BAILOUT: f64 = 1e4;
+
+// Trace the external ray at `angle` inward. Each step lowers the target potential by
+// 2^(-1/sharpness) and solves z_n(c) = target with a few Newton iterations.
+pub fn trace_ray(angle: f64, depth: usize, sharpness: usize) -> Vec<Complex64> {
+    let mut c = Complex64::from_polar(RAY_START, TAU * angle);
+    let mut points = vec![c];
+    for j in 1..=depth * sharpness {
+        let n = j.div_ceil(sharpness);
+        // Böttcher radius at this step, raised to 2^n so |z_n| stays in [R, R²)
+        let radius = RAY_START.powf(2f64.powf(n as f64 - j as f64 / sharpness as f64));
+        let mut turns = angle;
+        for _ in 0..n {
+            turns = (turns * 2.0).fract();
+        }
+        let target = Complex64::from_polar(radius, TAU * turns);
+        for _ in 0..8 {
+            let (mut z, mut dz) = (c, Complex64::new(1.0, 0.0));
+            for _ in 0..n {
+                dz = z * dz * 2.0 + 1.0;
+                z = z * z + c;
+            }
+            let step = (z - target) / dz;
+            if !step.re.is_finite() || !step.im.is_finite() {
+                return points;
+            }
+            c -= step;
+            if step.norm_sqr() < 1e-24 {
+                break;
+            }
+        }
+        points.push(c);
+    }
+    points
+}
+
+// Continuous Mandelbrot escape count at the potential's bailout; None for points that
+// never escape
+#[inline]
+pub fn smooth_escape(c: Complex64, max_iters: usize) -> Option<f64> {
+    let zero = Complex64::new(0.0, 0.0);
+    Fractal::Mandelbrot.smooth_count(c, zero, max_iters + 1, POTENTIAL_BAILOUT)
+}
+
+// Parse "1/3,2/3,0.25" into angles in turns
+pub fn parse_angles(s: &str) -> Result<Vec<f64>, String> {
+    s.split(',')
+        .map(|part| {
+            let part = part.trim();
+            let value = match part.split_once('/') {
+                Some((p, q)) => match (p.parse::<f64>(), q.parse::<f64>()) {
+                    (Ok(p), Ok(q)) if q != 0.0 => Some(p / q),
+                    _ => None,
+                },
+                None => part.parse::<f64>().ok(),
+            };
+            value
+                .filter(|v| v.is_finite())
+                .map(|v| v.rem_euclid(1.0))
+                .ok_or_else(|| format!("invalid ray angle '{part}'"))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn ray_zero_follows_real_axis() {
+        let ray = trace_ray(0.0, 12, 4);
+        let end = *ray.last().unwrap();
+        assert!(ray.iter().all(|p| p.im.abs() < 1e-9));
+        assert!(end.re > 0.25 && end.re < 0.6);
+    }
+    #[test]
+    fn third_ray_heads_for_period_two_root() {
+        let end = *trace_ray(1.0 / 3.0, 40, 8).last().unwrap();
+        assert!((end - Complex64::new(-0.75, 0.0)).norm() < 0.1);
+        assert!(end.im > 0.0);
+    }
+    #[test]
+    fn parses_fractions() {
+        assert_eq!(parse_angles("1/4, 0.5,3/2").unwrap(), vec![0.25, 0.5, 0.5]);
+        assert!(parse_angles("1/0").is_err());
+        assert!(parse_angles("nan").is_err());
+        assert!(parse_angles("0.5,inf").is_err());
+    }
+}
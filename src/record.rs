@@ -0,0 +1,347 @@
+// .tfrec recordings: a session as a timeline of parameters and key presses, small enough
+// to share, and replayed at any terminal size or exported as frames later. Plain text, one
+// timestamped line each after a header:
+//
+//   tfrec 1
+//   seed 20251015
+//   0.000 state c=-0.8,0.156 iters=120 split=0 | fractal=julia ... palette=fire
+//   0.000 c -0.8,0.156
+//   0.100 c -0.79812,0.15733
+//   2.417 key s
+//   3.050 key ctrl+right
+//   4.210 paste goto seahorse
+//   12.500 end
+//
+// `state` lines (the shared-session format) come whenever panes, split or iterations
+// change; `c` samples the wandering Julia parameter ten times a second and is interpolated
+// in between. Keys and pasted text replay display toggles, menus and commands that the
+// parameters don't capture. The seed is that of --seed or --daily, if one was given.
+use crate::share::State;
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use num_complex::Complex64;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+const MAGIC: &str = "tfrec 1";
+// Seconds between samples of c while it moves
+const C_INTERVAL: f64 = 0.1;
+// Seconds between flushes to disk, so a crash loses little
+const FLUSH_INTERVAL: f64 = 1.0;
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Recording {
+    pub seed: Option<u64>,
+    states: Vec<(f64, State)>,
+    cs: Vec<(f64, Complex64)>,
+    events: Vec<(f64, Event)>,
+    end: f64,
+}
+
+impl Recording {
+    pub fn parse(text: &str) -> Result<Recording, String> {
+        let mut lines = text.lines().enumerate();
+        if lines.next().map(|(_, l)| l.trim()) != Some(MAGIC) {
+            return Err(format!(
+                "not a recording (expected '{MAGIC}' on the first line)"
+            ));
+        }
+        let mut rec = Recording::default();
+        for (n, line) in lines {
+            let line = line.trim_end();
+            if line.is_empty() {
+                continue;
+            }
+            let err = |e: String| format!("line {}: {e}", n + 1);
+            if let Some(seed) = line.strip_prefix("seed ") {
+                rec.seed = Some(
+                    seed.parse()
+                        .map_err(|_| err(format!("bad seed '{seed}'")))?,
+                );
+                continue;
+            }
+            let (t, rest) = line.split_once(' ').unwrap_or((line, ""));
+            let t = match t.parse::<f64>() {
+                Ok(t) if t.is_finite() && t >= rec.end => t,
+                _ => return Err(err(format!("bad or decreasing time '{t}'"))),
+            };
+            rec.end = t;
+            let (kind, arg) = rest.split_once(' ').unwrap_or((rest, ""));
+            match kind {
+                "state" => rec.states.push((t, State::decode(arg).map_err(err)?)),
+                "c" => {
+                    let c = arg.split_once(',').and_then(|(re, im)| {
+                        Some(Complex64::new(re.parse().ok()?, im.parse().ok()?))
+                    });
+                    rec.cs
+                        .push((t, c.ok_or_else(|| err(format!("bad c '{arg}'")))?));
+                }
+                "key" => {
+                    let key = parse_key(arg).ok_or_else(|| err(format!("bad key '{arg}'")))?;
+                    rec.events.push((t, key));
+                }
+                "paste" => rec.events.push((t, Event::Paste(arg.to_string()))),
+                "end" => {}
+                _ => return Err(err(format!("unknown entry '{kind}'"))),
+            }
+        }
+        if rec.states.is_empty() {
+            return Err("the recording has no state".to_string());
+        }
+        Ok(rec)
+    }
+
+    // Seconds from the start to the last entry
+    pub fn duration(&self) -> f64 {
+        self.end
+    }
+
+    // Shared state at `t` seconds, with c between the samples around it
+    pub fn state_at(&self, t: f64) -> State {
+        let i = self.states.partition_point(|(at, _)| *at <= t);
+        let mut state = self.states[i.saturating_sub(1)].1;
+        let j = self.cs.partition_point(|(at, _)| *at <= t);
+        state.c = match (j.checked_sub(1).map(|k| self.cs[k]), self.cs.get(j)) {
+            (Some((t0, a)), Some(&(t1, b))) if t1 > t0 => a + (b - a) * ((t - t0) / (t1 - t0)),
+            (Some((_, a)), _) => a,
+            (None, Some(&(_, b))) => b,
+            (None, None) => state.c,
+        };
+        state
+    }
+
+    // Keys and pasted text recorded after `from` and up to `to` seconds
+    pub fn events(&self, from: f64, to: f64) -> Vec<Event> {
+        self.events
+            .iter()
+            .filter(|(t, _)| *t > from && *t <= to)
+            .map(|(_, e)| e.clone())
+            .collect()
+    }
+}
+
+pub struct Recorder {
+    out: BufWriter<File>,
+    // State without c as last written, and the last c sample
+    last_state: String,
+    last_c: Option<(f64, Complex64)>,
+    // c of the previous frame, to pin down where a pause of the wander ended
+    prev_c: Option<(f64, Complex64)>,
+    // Lines of this frame's input, written with the frame unless it ended the session
+    pending: Vec<String>,
+    flushed: f64,
+}
+
+impl Recorder {
+    pub fn create(path: &str, seed: Option<u64>) -> io::Result<Recorder> {
+        let mut out = BufWriter::new(File::create(path)?);
+        writeln!(out, "{MAGIC}")?;
+        if let Some(seed) = seed {
+            writeln!(out, "seed {seed}")?;
+        }
+        Ok(Recorder {
+            out,
+            last_state: String::new(),
+            last_c: None,
+            prev_c: None,
+            pending: Vec::new(),
+            flushed: 0.0,
+        })
+    }
+
+    // An input event at `t`; only keys and pasted text are kept
+    pub fn event(&mut self, t: f64, ev: &Event) {
+        match ev {
+            Event::Key(k) => {
+                if let Some(key) = key_name(k) {
+                    self.pending.push(format!("{t:.3} key {key}"));
+                }
+            }
+            Event::Paste(text) => {
+                let text: String = text.chars().filter(|c| !c.is_control()).collect();
+                self.pending.push(format!("{t:.3} paste {text}"));
+            }
+            _ => {}
+        }
+    }
+
+    // The frame at `t`. Input that quit the session is left out, so a replay doesn't quit.
+    pub fn frame(&mut self, t: f64, state: &State, running: bool) -> io::Result<()> {
+        let shape = State {
+            c: Complex64::new(0.0, 0.0),
+            ..*state
+        }
+        .encode();
+        let c = state.c;
+        if shape != self.last_state {
+            writeln!(self.out, "{t:.3} state {}", state.encode())?;
+            self.last_state = shape;
+            self.sample(t, c)?;
+        } else if let Some((t0, c0)) = self.last_c
+            && c != c0
+            && t - t0 >= C_INTERVAL
+        {
+            // c held still until the previous frame: keep it there instead of letting the
+            // interpolation drift across the pause
+            if let Some((tp, cp)) = self.prev_c
+                && cp == c0
+                && tp > t0
+            {
+                self.sample(tp, cp)?;
+            }
+            self.sample(t, c)?;
+        }
+        self.prev_c = Some((t, c));
+        // After the samples, which may go back to the previous frame
+        if running {
+            for line in self.pending.drain(..) {
+                writeln!(self.out, "{line}")?;
+            }
+        }
+        self.pending.clear();
+        if t - self.flushed >= FLUSH_INTERVAL {
+            self.out.flush()?;
+            self.flushed = t;
+        }
+        Ok(())
+    }
+
+    fn sample(&mut self, t: f64, c: Complex64) -> io::Result<()> {
+        writeln!(self.out, "{t:.3} c {},{}", c.re, c.im)?;
+        self.last_c = Some((t, c));
+        Ok(())
+    }
+
+    pub fn finish(mut self, t: f64) -> io::Result<()> {
+        writeln!(self.out, "{t:.3} end")?;
+        self.out.flush()
+    }
+}
+
+// "s", "space", "left", "ctrl+right"; None for keys not worth replaying (Ctrl+C, Ctrl+Z
+// and anything the session doesn't use)
+fn key_name(k: &KeyEvent) -> Option<String> {
+    let ctrl = k.modifiers.contains(KeyModifiers::CONTROL);
+    let name = match k.code {
+        KeyCode::Char('c' | 'z') if ctrl => return None,
+        KeyCode::Char(' ') => "space".to_string(),
+        KeyCode::Char(ch) => ch.to_string(),
+        KeyCode::Left => "left".to_string(),
+        KeyCode::Right => "right".to_string(),
+        KeyCode::Up => "up".to_string(),
+        KeyCode::Down => "down".to_string(),
+        KeyCode::Enter => "enter".to_string(),
+        KeyCode::Esc => "esc".to_string(),
+        KeyCode::Tab => "tab".to_string(),
+        KeyCode::Backspace => "backspace".to_string(),
+        KeyCode::PageUp => "pageup".to_string(),
+        KeyCode::PageDown => "pagedown".to_string(),
+        _ => return None,
+    };
+    Some(if ctrl { format!("ctrl+{name}") } else { name })
+}
+
+fn parse_key(s: &str) -> Option<Event> {
+    let (modifiers, name) = match s.strip_prefix("ctrl+") {
+        Some(name) => (KeyModifiers::CONTROL, name),
+        None => (KeyModifiers::NONE, s),
+    };
+    let code = match name {
+        "space" => KeyCode::Char(' '),
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "enter" => KeyCode::Enter,
+        "esc" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "backspace" => KeyCode::Backspace,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        _ => {
+            let mut chars = name.chars();
+            match (chars.next(), chars.next()) {
+                (Some(ch), None) => KeyCode::Char(ch),
+                _ => return None,
+            }
+        }
+    };
+    Some(Event::Key(KeyEvent::new(code, modifiers)))
+}
+
+// Exports frames of the left (or only) pane of `rec`, `fps` a second, into `out`
+pub fn export(rec: &Recording, out: &str, size: (u32, u32), fps: f64) -> Result<(), String> {
+    let frames = (rec.duration() * fps).floor() as usize + 1;
+    let (w, h) = (size.0 as usize, size.1 as usize);
+    let describe = |i| format!("at {:.1}s", i as f64 / fps);
+    let draw = |i| {
+        let s = rec.state_at(i as f64 / fps);
+        let done = std::sync::atomic::AtomicUsize::new(0);
+        crate::export::render(&s.panes[0], s.c, s.max_iters, w, h, &done)
+    };
+    crate::sequence::write_frames(out, size, frames, describe, draw)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fractal::Fractal;
+    use crate::layout::{Layout, Pane};
+    fn state(c: Complex64, layout: Layout) -> State {
+        State {
+            c,
+            max_iters: 200,
+            layout,
+            panes: [Pane::new(Fractal::Julia); 2],
+        }
+    }
+    #[test]
+    fn records_and_replays() {
+        let path = std::env::temp_dir().join(format!("tf-record-{}.tfrec", std::process::id()));
+        let path = path.display().to_string();
+        let mut r = Recorder::create(&path, Some(7)).unwrap();
+        let (a, b) = (Complex64::new(-0.8, 0.15), Complex64::new(-0.7, 0.25));
+        r.frame(0.0, &state(a, Layout::Single), true).unwrap();
+        // Held at a for a while, then moving
+        r.frame(1.0, &state(a, Layout::Single), true).unwrap();
+        r.event(
+            1.5,
+            &Event::Key(KeyEvent::new(KeyCode::Char('s'), KeyModifiers::NONE)),
+        );
+        r.frame(1.5, &state((a + b) / 2.0, Layout::Single), true)
+            .unwrap();
+        r.frame(2.0, &state(b, Layout::Split { left: 50 }), true)
+            .unwrap();
+        // The key that quit is not kept
+        r.event(
+            2.5,
+            &Event::Key(KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE)),
+        );
+        r.frame(2.5, &state(b, Layout::Split { left: 50 }), false)
+            .unwrap();
+        r.finish(2.5).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let rec = Recording::parse(&text).unwrap();
+        assert_eq!((rec.seed, rec.duration()), (Some(7), 2.5));
+        assert_eq!(rec.state_at(0.5).c, a);
+        assert!((rec.state_at(1.25).c - (a * 3.0 + b) / 4.0).norm() < 1e-12);
+        assert_eq!(rec.state_at(0.5).layout, Layout::Single);
+        assert_eq!(rec.state_at(2.2).layout, Layout::Split { left: 50 });
+        let keys = rec.events(1.0, 3.0);
+        assert_eq!(keys.len(), 1);
+        assert_eq!(rec.events(1.5, 3.0), vec![]);
+    }
+    #[test]
+    fn keys_round_trip() {
+        for name in ["s", "space", ":", "ctrl+left", "pagedown", "<"] {
+            let Some(Event::Key(k)) = parse_key(name) else {
+                panic!("{name}");
+            };
+            assert_eq!(key_name(&k).as_deref(), Some(name));
+        }
+        let ctrl_z = KeyEvent::new(KeyCode::Char('z'), KeyModifiers::CONTROL);
+        assert_eq!(key_name(&ctrl_z), None);
+        assert!(Recording::parse("0.0 state x").is_err());
+        assert!(Recording::parse("tfrec 1\n1.0 c 0,0\n0.5 c 1,1\n").is_err());
+    }
+}
@@ -0,0 +1,408 @@
+// Draws panes, overlays and panels into compositor layers
+use crate::color::{Ramp, hsv_to_256, rgb_of};
+use crate::compositor::{Cell, Layer};
+use crate::console::Console;
+use crate::histogram::Histogram;
+use crate::layout::{Pane, Rect};
+use crate::orbit::Stepper;
+use crate::rays;
+use crate::warp::Warp;
+use num_complex::Complex64;
+
+pub fn draw_pane(
+    layer: &mut Layer,
+    rect: Rect,
+    pane: &Pane,
+    c: Complex64,
+    max_iters: usize,
+    ramp: &Ramp,
+    hist: Option<&mut Histogram>,
+) {
+    draw_pane_blocks(layer, rect, pane, c, max_iters, ramp, hist, 1);
+}
+
+// Like `draw_pane`, sampling once per `block`×`block` cells (the quality governor's
+// lower resolutions); the histogram counts samples, not cells
+#[allow(clippy::too_many_arguments)]
+pub fn draw_pane_blocks(
+    layer: &mut Layer,
+    rect: Rect,
+    pane: &Pane,
+    c: Complex64,
+    max_iters: usize,
+    ramp: &Ramp,
+    mut hist: Option<&mut Histogram>,
+    block: usize,
+) {
+    let (w, h) = (rect.w as usize, rect.h as usize);
+    let c = pane.param(c);
+    let block = block.max(1);
+    for y in (0..h).step_by(block) {
+        for x in (0..w).step_by(block) {
+            // Sample the middle of the block, or of what is left of it at the edges
+            let sx = x + (block.min(w - x) - 1) / 2;
+            let sy = y + (block.min(h - y) - 1) / 2;
+            let p = pane.point(sx, sy, w, h);
+            let iters = pane.fractal.escape(p, c, max_iters);
+            if let Some(hist) = hist.as_deref_mut() {
+                hist.add(iters);
+            }
+            let cell = if iters >= max_iters {
+                Cell::BLANK
+            } else {
+                let norm = iters as f64 / max_iters as f64;
+                Cell::new(ramp.shade(norm), Some(pane.palette.color(norm)))
+            };
+            for by in y..(y + block).min(h) {
+                for bx in x..(x + block).min(w) {
+                    layer.put(rect.x + bx as u16, rect.y + by as u16, cell);
+                }
+            }
+        }
+    }
+}
+
+// `draw_pane` at the best quality a terminal can show, for a last frame worth keeping:
+// POSTER_SAMPLES² samples per square half of each cell, smooth coloring and `max_iters`
+// iterations, averaged into 24-bit colors (with the palette fallback below truecolor).
+// Colors are scaled to `scale_iters`, so points that only escape past the live view's
+// budget come out brightest instead of changing the whole gradient.
+pub fn draw_poster(
+    layer: &mut Layer,
+    rect: Rect,
+    pane: &Pane,
+    c: Complex64,
+    (max_iters, scale_iters): (usize, usize),
+    ramp: &Ramp,
+) {
+    const POSTER_SAMPLES: usize = 3;
+    let (w, h) = (rect.w as usize, rect.h as usize);
+    let c = pane.param(c);
+    // Square pixels: each cell is two of them stacked, split into samples
+    let n = POSTER_SAMPLES;
+    let (pw, ph) = (w * n, h * 2 * n);
+    for y in 0..h {
+        for x in 0..w {
+            let (mut sum, mut norm, mut escaped) = ([0u32; 3], 0.0, 0);
+            for sy in 0..2 * n {
+                for sx in 0..n {
+                    let fx = (x * n + sx) as f64 + 0.5;
+                    let fy = (y * 2 * n + sy) as f64 + 0.5;
+                    let nu = pane
+                        .fractal
+                        .escape_smooth(pane.pixel(fx, fy, pw, ph), c, max_iters);
+                    if nu >= max_iters as f64 {
+                        continue;
+                    }
+                    let t = (nu / scale_iters as f64).min(1.0);
+                    let (r, g, b) = rgb_of(pane.palette.color(t));
+                    sum = [sum[0] + r as u32, sum[1] + g as u32, sum[2] + b as u32];
+                    norm += t;
+                    escaped += 1;
+                }
+            }
+            if escaped == 0 {
+                continue;
+            }
+            // Interior samples count as black in the color, not in the shade
+            let count = (2 * n * n) as u32;
+            let [r, g, b] = sum.map(|v| ((v + count / 2) / count) as u8);
+            let ch = ramp.shade(norm / escaped as f64);
+            layer.put(
+                rect.x + x as u16,
+                rect.y + y as u16,
+                Cell::rgb(ch, (r, g, b)),
+            );
+        }
+    }
+}
+
+// Small label in the top-left corner of a pane, reversed when highlighted
+pub fn draw_label(layer: &mut Layer, rect: Rect, text: &str, highlight: bool) {
+    let style = if highlight {
+        Cell::BLANK.reverse()
+    } else {
+        Cell::new(' ', Some(250))
+    };
+    layer.text(rect.x, rect.y, text, style, rect.w);
+}
+
+// Vertical divider in the column just left of `rect`
+pub fn draw_divider(layer: &mut Layer, rect: Rect) {
+    if rect.x == 0 {
+        return;
+    }
+    for y in 0..rect.h {
+        layer.put(rect.x - 1, rect.y + y, Cell::new('│', Some(240)));
+    }
+}
+
+// Zoomed-out overview in the bottom-right corner of `rect`, with the current view outlined.
+// Rendered as a second low-resolution pass at reduced iterations.
+pub fn draw_minimap(
+    layer: &mut Layer,
+    rect: Rect,
+    pane: &Pane,
+    c: Complex64,
+    max_iters: usize,
+    ramp: &Ramp,
+) {
+    if rect.w < 4 || rect.h < 2 {
+        return;
+    }
+    let w = (rect.w / 4).max(18).min(rect.w);
+    let h = (w / 3).max(4).min(rect.h);
+    let mini = Rect::new(rect.x + rect.w - w, rect.y + rect.h - h, w, h);
+    let overview = Pane {
+        view: pane.fractal.home(),
+        warp: Warp::None,
+        ..*pane
+    };
+    let iters = (max_iters / 2).max(16);
+    draw_pane(layer, mini, &overview, c, iters, ramp, None);
+
+    let (rw, rh) = (rect.w as usize, rect.h as usize);
+    let (mw, mh) = (w as usize, h as usize);
+    let (x0, y0) = overview.view.cell_of(pane.view.point(0, 0, rw, rh), mw, mh);
+    let (x1, y1) = overview
+        .view
+        .cell_of(pane.view.point(rw - 1, rh - 1, rw, rh), mw, mh);
+    let (x0, y0, x1, y1) = (
+        x0.round() as i32,
+        y0.round() as i32,
+        x1.round() as i32,
+        y1.round() as i32,
+    );
+    let mut put = |x: i32, y: i32, ch: char| {
+        if x >= 0 && y >= 0 && x < w as i32 && y < h as i32 {
+            let cell = Cell::new(ch, Some(231)).bold();
+            layer.put(mini.x + x as u16, mini.y + y as u16, cell);
+        }
+    };
+    if x1 - x0 < 2 || y1 - y0 < 1 {
+        // View is smaller than a cell: mark its center
+        put((x0 + x1) / 2, (y0 + y1) / 2, '+');
+        return;
+    }
+    for x in x0 + 1..x1 {
+        put(x, y0, '─');
+        put(x, y1, '─');
+    }
+    for y in y0 + 1..y1 {
+        put(x0, y, '│');
+        put(x1, y, '│');
+    }
+    put(x0, y0, '┌');
+    put(x1, y0, '┐');
+    put(x0, y1, '└');
+    put(x1, y1, '┘');
+}
+
+// External rays and equipotential curves drawn over a Mandelbrot pane
+pub fn draw_rays_overlay(
+    layer: &mut Layer,
+    rect: Rect,
+    pane: &Pane,
+    rays: &[Vec<Complex64>],
+    max_iters: usize,
+) {
+    let (w, h) = (rect.w as usize, rect.h as usize);
+    if w == 0 || h == 0 {
+        return;
+    }
+    // Equipotentials: edges between bands of the smooth escape count. Each step of the
+    // count halves the potential; every other level keeps the curves apart at terminal size.
+    let band: Vec<Option<i64>> = (0..w * h)
+        .map(|i| {
+            let p = pane.point(i % w, i / w, w, h);
+            rays::smooth_escape(p, max_iters).map(|nu| (nu / 2.0).floor() as i64)
+        })
+        .collect();
+    for y in 0..h {
+        for x in 0..w {
+            let Some(b) = band[y * w + x] else { continue };
+            let right = (x + 1 < w).then(|| band[y * w + x + 1]).flatten();
+            let down = (y + 1 < h).then(|| band[(y + 1) * w + x]).flatten();
+            if right.is_some_and(|r| r != b) || down.is_some_and(|d| d != b) {
+                let cell = Cell::new('·', Some(250));
+                layer.put(rect.x + x as u16, rect.y + y as u16, cell);
+            }
+        }
+    }
+    // Rays: connect consecutive traced points in cell space
+    let ray_cell = Cell::new('•', Some(231)).bold();
+    for ray in rays {
+        let mut prev: Option<(f64, f64)> = None;
+        for (x1, y1) in ray.iter().map(|&p| pane.cell_of(p, w, h)) {
+            let (x0, y0) = prev.unwrap_or((x1, y1));
+            prev = Some((x1, y1));
+            let steps = (x1 - x0).abs().max((y1 - y0).abs()).ceil().min(4096.0) as usize;
+            for s in 0..=steps {
+                let t = if steps == 0 {
+                    1.0
+                } else {
+                    s as f64 / steps as f64
+                };
+                let (x, y) = ((x0 + (x1 - x0) * t).round(), (y0 + (y1 - y0) * t).round());
+                if x >= 0.0 && y >= 0.0 && (x as usize) < w && (y as usize) < h {
+                    layer.put(rect.x + x as u16, rect.y + y as u16, ray_cell);
+                }
+            }
+        }
+    }
+}
+
+// Teaching mode over a pane: the bailout circle |z| = 2, the orbit so far (z0 marked 'o',
+// the current z '@') and the chosen point as a crosshair
+pub fn draw_orbit(layer: &mut Layer, rect: Rect, pane: &Pane, stepper: &Stepper) {
+    let (w, h) = (rect.w as usize, rect.h as usize);
+    let mut put = |p: Complex64, cell: Cell| {
+        let (x, y) = pane.cell_of(p, w, h);
+        let (x, y) = (x.round(), y.round());
+        if x >= 0.0 && y >= 0.0 && (x as usize) < w && (y as usize) < h {
+            layer.put(rect.x + x as u16, rect.y + y as u16, cell);
+        }
+    };
+    for i in 0..256 {
+        let p = Complex64::from_polar(2.0, i as f64 / 256.0 * std::f64::consts::TAU);
+        put(p, Cell::new('·', Some(240)));
+    }
+    let n = stepper.orbit.len();
+    for (i, &z) in stepper.orbit.iter().enumerate() {
+        let cell = match i {
+            0 => Cell::new('o', Some(231)),
+            i if i + 1 == n => Cell::new('@', Some(226)).bold(),
+            _ => Cell::new('•', Some(220)),
+        };
+        put(z, cell);
+    }
+    put(stepper.point, Cell::new('+', Some(231)).bold());
+}
+
+// Boxed text panel in the top-right corner of `rect`
+pub fn draw_info(layer: &mut Layer, rect: Rect, lines: &[String]) {
+    let text_w = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0) as u16;
+    let (w, h) = ((text_w + 2).min(rect.w), (lines.len() as u16).min(rect.h));
+    let x = rect.x + rect.w - w;
+    let style = Cell {
+        bg: Some(235),
+        ..Cell::new(' ', Some(252))
+    };
+    layer.fill(Rect::new(x, rect.y, w, h), style);
+    for (i, line) in lines.iter().take(h as usize).enumerate() {
+        layer.text(x + 1, rect.y + i as u16, line, style, w.saturating_sub(1));
+    }
+}
+
+// Caption bar centered near the bottom of `rect`, for presentations
+pub fn draw_caption(layer: &mut Layer, rect: Rect, text: &str) {
+    if rect.h < 3 {
+        return;
+    }
+    let w = (text.chars().count() as u16 + 4).min(rect.w);
+    let (x, y) = (rect.x + (rect.w - w) / 2, rect.y + rect.h - 3);
+    let style = Cell {
+        bg: Some(235),
+        ..Cell::new(' ', Some(231))
+    };
+    layer.fill(Rect::new(x, y, w, 1), style);
+    layer.text(x + 2, y, text, style.bold(), w.saturating_sub(4));
+}
+
+// Boxed list centered in `rect` with the `cursor` row highlighted, scrolled to keep it in view
+pub fn draw_menu(layer: &mut Layer, rect: Rect, title: &str, items: &[String], cursor: usize) {
+    let text_w = items
+        .iter()
+        .map(|l| l.chars().count())
+        .chain([title.chars().count()])
+        .max()
+        .unwrap_or(0) as u16;
+    let w = (text_w + 2).min(rect.w);
+    let h = (items.len() as u16 + 1).min(rect.h);
+    if h < 2 {
+        return;
+    }
+    let (x, y) = (rect.x + (rect.w - w) / 2, rect.y + (rect.h - h) / 2);
+    let style = Cell {
+        bg: Some(235),
+        ..Cell::new(' ', Some(252))
+    };
+    layer.fill(Rect::new(x, y, w, h), style);
+    layer.text(x + 1, y, title, style.bold(), w.saturating_sub(1));
+    let rows = h as usize - 1;
+    let first = (cursor + 1).saturating_sub(rows);
+    for (i, item) in items.iter().enumerate().skip(first).take(rows) {
+        let row = y + 1 + (i - first) as u16;
+        let style = if i == cursor { style.reverse() } else { style };
+        layer.fill(Rect::new(x, row, w, 1), style);
+        layer.text(x + 1, row, item, style, w.saturating_sub(1));
+    }
+}
+
+// Histogram panel: one bar per bucket on a log scale, colored like the fractal
+pub fn draw_histogram(layer: &mut Layer, rect: Rect, hist: &Histogram) {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    layer.fill(rect, Cell::BLANK);
+    let (w, rows) = (rect.w as usize, rect.h.saturating_sub(1) as usize);
+    if w == 0 || rows == 0 {
+        return;
+    }
+    let max_iters = hist.max_iters();
+    let title = format!(
+        "Iterations 0..{max_iters} (log) | interior {:.1}% | p99 escape {} | [ ] max iters",
+        hist.interior_fraction() * 100.0,
+        hist.escape_percentile(0.99)
+    );
+    layer.text(rect.x, rect.y, &title, Cell::new(' ', Some(250)), rect.w);
+
+    let buckets = hist.buckets(w);
+    let peak = buckets.iter().copied().max().unwrap_or(0) as f64;
+    let scale = (peak + 1.0).ln().max(1e-9);
+    for (x, &n) in buckets.iter().enumerate() {
+        // Bar height in eighths of a row
+        let eighths = ((n as f64 + 1.0).ln() / scale * (rows * 8) as f64).round() as usize;
+        let color = hsv_to_256(x as f64 / w as f64 * 360.0, 0.9, 1.0);
+        for r in 0..rows {
+            let fill = eighths.saturating_sub(r * 8).min(8);
+            if fill == 0 {
+                break;
+            }
+            let y = rect.y + rect.h - 1 - r as u16;
+            layer.put(rect.x + x as u16, y, Cell::new(BARS[fill - 1], Some(color)));
+        }
+    }
+}
+
+// Console panel: title row plus the entries at the current scroll position
+pub fn draw_console(layer: &mut Layer, rect: Rect, console: &Console) {
+    layer.fill(rect, Cell::BLANK);
+    if rect.h == 0 {
+        return;
+    }
+    let rows = rect.h as usize - 1;
+    let title = match console.scroll {
+        0 => "Console (PgUp/PgDn scroll, l close)".to_string(),
+        n => format!("Console (scrolled back {n}, PgUp/PgDn scroll, l close)"),
+    };
+    layer.fill(Rect::new(rect.x, rect.y, rect.w, 1), Cell::BLANK.reverse());
+    layer.text(rect.x, rect.y, &title, Cell::BLANK.reverse(), rect.w);
+    for (i, e) in console.window(rows).enumerate() {
+        let secs = e.at.as_secs();
+        let line = format!("[{:02}:{:02}] {}", secs / 60, secs % 60, e.text);
+        layer.text(rect.x, rect.y + 1 + i as u16, &line, Cell::BLANK, rect.w);
+    }
+}
+
+// Centered notice shown instead of the fractal when the screen is below `min`
+pub fn draw_too_small(layer: &mut Layer, screen: Rect, min: (u16, u16)) {
+    let lines = [
+        "Terminal too small".to_string(),
+        format!("{}x{}, need {}x{}", screen.w, screen.h, min.0, min.1),
+    ];
+    let top = screen.h.saturating_sub(lines.len() as u16) / 2;
+    for (i, line) in lines.iter().enumerate() {
+        let len = line.chars().count() as u16;
+        let x = screen.w.saturating_sub(len) / 2;
+        layer.text(x, top + i as u16, line, Cell::new(' ', Some(250)), screen.w);
+    }
+}
@@ -0,0 +1,179 @@
+// Batch renderer for zoom videos: numbered PNG frames of an exponential zoom into one
+// point, with the iteration budget growing along the way. Finished frames are kept, and a
+// frame only gets its final name once it is completely written, so an interrupted run
+// carries on where it stopped when started again with the same arguments. Recordings are
+// exported through the same frame writer.
+use crate::export;
+use crate::fractal::{Precision, Viewport, format_zoom};
+use crate::layout::Pane;
+use crate::perturb::Reference;
+use crate::playlist::Entry;
+use crate::png;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::sync::atomic::AtomicUsize;
+
+pub const DEFAULT_FRAMES: usize = 300;
+pub const DEFAULT_SIZE: (u32, u32) = (1280, 720);
+pub const DEFAULT_ITERS_PER_DECADE: usize = 100;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Job {
+    // Fractal, center, palette, c and the iterations of the first frame
+    pub entry: Entry,
+    pub from: f64,
+    pub to: f64,
+    pub frames: usize,
+    pub size: (u32, u32),
+    pub out: String,
+    // Iterations added for every 10× of zoom past the first frame
+    pub iters_per_decade: usize,
+    // Perturbation kernel instead of the plain one
+    pub perturb: bool,
+}
+
+impl Job {
+    // View and iteration budget of frame `i` (from 0); the zoom grows by the same factor
+    // every frame, so the video moves in at a steady pace
+    pub fn frame(&self, i: usize) -> (Viewport, usize) {
+        let t = if self.frames > 1 {
+            i as f64 / (self.frames - 1) as f64
+        } else {
+            0.0
+        };
+        let zoom = self.from * (self.to / self.from).powf(t);
+        let decades = (zoom / self.from).log10().max(0.0);
+        let iters = self.entry.location.max_iters
+            + (decades * self.iters_per_decade as f64).round() as usize;
+        let view = Viewport {
+            center: self.entry.location.view.center,
+            zoom,
+        };
+        (view, iters)
+    }
+}
+
+// Renders every frame not already on disk, reporting progress on stderr
+pub fn run(job: &Job) -> Result<(), String> {
+    let (w, h) = (job.size.0 as usize, job.size.1 as usize);
+    let (last, _) = job.frame(job.frames - 1);
+    if !job.perturb && last.precision_at(last.pixel_size(w, h)) != Precision::Fine {
+        eprintln!(
+            "warning: the last frames go past what f64 can resolve ({}); add --perturb",
+            format_zoom(last.zoom)
+        );
+    }
+    let describe = |i| {
+        let (view, iters) = job.frame(i);
+        format!("zoom {} iters {iters}", format_zoom(view.zoom))
+    };
+    let draw = |i| {
+        let (view, iters) = job.frame(i);
+        render(job, view, iters, w, h)
+    };
+    write_frames(&job.out, job.size, job.frames, describe, draw)
+}
+
+// Numbered PNGs frame-00001.png, ... in `out`, drawn by `draw` as RGB triples. Frames
+// already there are kept, and each one is written under a temporary name first, so a
+// frame on disk is always a whole one.
+pub fn write_frames(
+    out: &str,
+    (w, h): (u32, u32),
+    frames: usize,
+    describe: impl Fn(usize) -> String,
+    draw: impl Fn(usize) -> Vec<u8>,
+) -> Result<(), String> {
+    std::fs::create_dir_all(out).map_err(|e| format!("{out}: {e}"))?;
+    let mut err = io::stderr();
+    let mut skipped = 0;
+    for i in 0..frames {
+        let path = frame_path(out, i);
+        if path.exists() {
+            skipped += 1;
+            continue;
+        }
+        let _ = write!(err, "\rframe {}/{frames} {}   ", i + 1, describe(i));
+        let _ = err.flush();
+        let rgb = draw(i);
+        let partial = path.with_extension("png.part");
+        std::fs::write(&partial, png::encode_rgb(w, h, &rgb))
+            .and_then(|()| std::fs::rename(&partial, &path))
+            .map_err(|e| format!("{}: {e}", path.display()))?;
+    }
+    let _ = writeln!(err);
+    if skipped > 0 {
+        eprintln!("kept {skipped} frames rendered by an earlier run");
+    }
+    eprintln!("{frames} frames in {out}");
+    Ok(())
+}
+
+pub fn frame_path(out: &str, i: usize) -> PathBuf {
+    PathBuf::from(out).join(format!("frame-{:05}.png", i + 1))
+}
+
+fn render(job: &Job, view: Viewport, iters: usize, w: usize, h: usize) -> Vec<u8> {
+    let loc = &job.entry.location;
+    let pane = Pane {
+        view,
+        palette: job.entry.palette,
+        ..Pane::new(loc.fractal)
+    };
+    let done = AtomicUsize::new(0);
+    if job.perturb {
+        let reference = Reference::new(loc.fractal, view.center, loc.c, iters);
+        let count = |x: f64, y: f64| reference.escape_smooth(view.pixel_offset(x, y, w, h), iters);
+        export::render_with(pane.palette, iters, w, h, &done, count)
+    } else {
+        export::render(&pane, loc.c, iters, w, h, &done)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::playlist;
+    fn job(out: String) -> Job {
+        Job {
+            entry: playlist::parse_entry("fractal=mandelbrot center=-0.745,0.105 iters=100")
+                .unwrap(),
+            from: 1.0,
+            to: 1000.0,
+            frames: 4,
+            size: (16, 9),
+            out,
+            iters_per_decade: 50,
+            perturb: false,
+        }
+    }
+    #[test]
+    fn zooms_exponentially() {
+        let j = job(String::new());
+        let zooms: Vec<f64> = (0..4).map(|i| j.frame(i).0.zoom).collect();
+        for (z, want) in zooms.iter().zip([1.0, 10.0, 100.0, 1000.0]) {
+            assert!((z - want).abs() < 1e-9 * want, "{zooms:?}");
+        }
+        assert_eq!(j.frame(0).1, 100);
+        assert_eq!(j.frame(3).1, 250);
+        assert_eq!(frame_path(&j.out, 0), PathBuf::from("frame-00001.png"));
+    }
+    #[test]
+    fn resumes_where_it_stopped() {
+        let dir = std::env::temp_dir().join(format!("tf-sequence-{}", std::process::id()));
+        let j = job(dir.display().to_string());
+        std::fs::create_dir_all(&dir).unwrap();
+        // A frame from an earlier run stays untouched
+        std::fs::write(frame_path(&j.out, 1), b"kept").unwrap();
+        run(&j).unwrap();
+        assert_eq!(std::fs::read(frame_path(&j.out, 1)).unwrap(), b"kept");
+        let frame = std::fs::read(frame_path(&j.out, 3)).unwrap();
+        assert!(frame.starts_with(b"\x89PNG"));
+        assert!(!frame_path(&j.out, 3).with_extension("png.part").exists());
+        let perturbed = Job { perturb: true, ..j };
+        std::fs::remove_file(frame_path(&perturbed.out, 3)).unwrap();
+        run(&perturbed).unwrap();
+        assert!(frame_path(&perturbed.out, 3).exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
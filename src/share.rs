@@ -0,0 +1,334 @@
+// Shared sessions over TCP: a host streams its view state, one line per change, and every
+// viewer renders the same panes locally from it. A line carries the shared c, the split
+// and each pane in the location format, e.g.
+// "c=-0.8,0.156 iters=120 split=0 | fractal=julia c=-0.8,0.156 center=0,0 zoom=1 iters=120 palette=fire"
+// with a trailing "mirror" on panes that show the conjugate of c.
+use crate::fractal::Fractal;
+use crate::layout::{Layout, Pane};
+use crate::location::Location;
+use crate::playlist;
+use num_complex::Complex64;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+
+pub const DEFAULT_PORT: u16 = 7878;
+// Longest line a viewer reads; a two-pane state takes a few hundred bytes
+const MAX_LINE: u64 = 4096;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct State {
+    pub c: Complex64,
+    pub max_iters: usize,
+    pub layout: Layout,
+    pub panes: [Pane; 2],
+}
+
+impl State {
+    pub fn encode(&self) -> String {
+        let split = match self.layout {
+            Layout::Split { left } => left,
+            _ => 0,
+        };
+        let mut line = format!(
+            "c={},{} iters={} split={split}",
+            self.c.re, self.c.im, self.max_iters
+        );
+        for pane in &self.panes[..self.layout.pane_count().min(2)] {
+            let loc = Location {
+                fractal: pane.fractal,
+                view: pane.view,
+                c: pane.param(self.c),
+                max_iters: self.max_iters,
+            };
+            line += &format!(" | {loc} palette={}", pane.palette.name());
+            if pane.mirror {
+                line += " mirror";
+            }
+        }
+        line
+    }
+
+    pub fn decode(line: &str) -> Result<State, String> {
+        let mut parts = line.split('|');
+        let head = parts.next().unwrap_or("");
+        let (mut c, mut max_iters, mut split) = (None, None, None);
+        for field in head.split_whitespace() {
+            match field.split_once('=') {
+                Some(("c", v)) => {
+                    c = v.split_once(',').and_then(|(re, im)| {
+                        Some(Complex64::new(re.parse().ok()?, im.parse().ok()?))
+                    })
+                }
+                Some(("iters", v)) => {
+                    max_iters = v
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|&n| n > 0)
+                        .map(|n| n.min(crate::ITERS_LIMIT))
+                }
+                Some(("split", v)) => split = v.parse::<u16>().ok(),
+                _ => return Err(format!("unknown field '{field}'")),
+            }
+        }
+        let (Some(c), Some(max_iters), Some(split)) = (c, max_iters, split) else {
+            return Err("missing c, iters or split".to_string());
+        };
+        let layout = match split {
+            0 => Layout::Single,
+            // Within the range the divider keys allow
+            left => Layout::Split {
+                left: left.clamp(10, 90),
+            },
+        };
+        let mut panes = [Pane::new(Fractal::Julia); 2];
+        let mut count = 0;
+        for (pane, part) in panes.iter_mut().zip(parts) {
+            let (part, mirror) = match part.trim().strip_suffix("mirror") {
+                Some(rest) => (rest, true),
+                None => (part, false),
+            };
+            let e = playlist::parse_entry(part)?;
+            *pane = Pane {
+                fractal: e.location.fractal,
+                view: e.location.view,
+                mirror,
+                palette: e.palette,
+                ..Pane::new(e.location.fractal)
+            };
+            count += 1;
+        }
+        if count < layout.pane_count() {
+            return Err("fewer panes than the layout shows".to_string());
+        }
+        Ok(State {
+            c,
+            max_iters,
+            layout,
+            panes,
+        })
+    }
+}
+
+// "7878" or ":7878" binds every interface; "host" without a port gets the default one
+pub fn address(s: &str, listen: bool) -> String {
+    let s = s.trim();
+    if s.parse::<u16>().is_ok() {
+        return format!("0.0.0.0:{s}");
+    }
+    if let Some(port) = s.strip_prefix(':') {
+        return format!("0.0.0.0:{port}");
+    }
+    if s.contains(':') {
+        return s.to_string();
+    }
+    let host = if s.is_empty() && listen { "0.0.0.0" } else { s };
+    format!("{host}:{DEFAULT_PORT}")
+}
+
+// Newest line and its sequence number, shared with the viewer threads
+type Latest = Arc<(Mutex<(u64, String)>, Condvar)>;
+
+pub struct Host {
+    pub addr: String,
+    latest: Latest,
+    viewers: Arc<AtomicUsize>,
+}
+
+impl Host {
+    // Accept viewers in the background; each gets its own thread that sends the newest
+    // line whenever it changes, so a slow viewer skips states instead of holding others up
+    pub fn start(addr: &str) -> io::Result<Host> {
+        let listener = TcpListener::bind(addr)?;
+        let addr = listener.local_addr()?.to_string();
+        let latest: Latest = Arc::new((Mutex::new((0, String::new())), Condvar::new()));
+        let viewers = Arc::new(AtomicUsize::new(0));
+        let (l, v) = (latest.clone(), viewers.clone());
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let (latest, viewers) = (l.clone(), v.clone());
+                viewers.fetch_add(1, Ordering::Relaxed);
+                thread::spawn(move || {
+                    let _ = serve(stream, &latest);
+                    viewers.fetch_sub(1, Ordering::Relaxed);
+                });
+            }
+        });
+        Ok(Host {
+            addr,
+            latest,
+            viewers,
+        })
+    }
+
+    pub fn viewers(&self) -> usize {
+        self.viewers.load(Ordering::Relaxed)
+    }
+
+    pub fn publish(&self, state: &State) {
+        let line = state.encode();
+        let (lock, ready) = &*self.latest;
+        let mut latest = lock.lock().unwrap_or_else(|e| e.into_inner());
+        if latest.1 != line {
+            *latest = (latest.0 + 1, line);
+            ready.notify_all();
+        }
+    }
+}
+
+fn serve(mut stream: TcpStream, latest: &Latest) -> io::Result<()> {
+    stream.set_nodelay(true)?;
+    let (lock, ready) = &**latest;
+    let mut sent = 0;
+    loop {
+        let line = {
+            let mut l = lock.lock().unwrap_or_else(|e| e.into_inner());
+            while l.0 == sent {
+                l = ready.wait(l).unwrap_or_else(|e| e.into_inner());
+            }
+            sent = l.0;
+            l.1.clone()
+        };
+        writeln!(stream, "{line}")?;
+    }
+}
+
+pub struct Viewer {
+    pub addr: String,
+    rx: Receiver<String>,
+}
+
+impl Viewer {
+    pub fn connect(addr: &str) -> io::Result<Viewer> {
+        let stream = TcpStream::connect(addr)?;
+        let addr = stream.peer_addr()?.to_string();
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let mut reader = BufReader::new(stream);
+            loop {
+                let mut line = String::new();
+                if !matches!((&mut reader).take(MAX_LINE).read_line(&mut line), Ok(n) if n > 0) {
+                    break;
+                }
+                // A line that runs past the limit isn't from a host; drop the connection
+                let Some(line) = line.strip_suffix('\n') else {
+                    break;
+                };
+                if tx.send(line.trim_end_matches('\r').to_string()).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(Viewer { addr, rx })
+    }
+
+    // Newest state received since the last call; Err once the host has gone away
+    pub fn poll(&self) -> Result<Option<State>, String> {
+        let mut newest = None;
+        loop {
+            match self.rx.try_recv() {
+                Ok(line) => newest = Some(line),
+                Err(mpsc::TryRecvError::Empty) => break,
+                Err(mpsc::TryRecvError::Disconnected) if newest.is_some() => break,
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    return Err("the host closed the session".to_string());
+                }
+            }
+        }
+        newest.map(|l| State::decode(&l)).transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Palette;
+    use std::time::Duration;
+    fn state() -> State {
+        let mut panes = [Pane::new(Fractal::Julia), Pane::new(Fractal::Mandelbrot)];
+        panes[0].mirror = true;
+        panes[0].palette = Palette::Fire;
+        panes[1].view.zoom_by(8.0);
+        State {
+            c: Complex64::new(-0.8, 0.156),
+            max_iters: 300,
+            layout: Layout::Split { left: 40 },
+            panes,
+        }
+    }
+    #[test]
+    fn round_trips_through_a_line() {
+        let s = state();
+        assert_eq!(State::decode(&s.encode()), Ok(s));
+        let single = State {
+            layout: Layout::Single,
+            ..s
+        };
+        let back = State::decode(&single.encode()).unwrap();
+        assert_eq!((back.layout, back.panes[0]), (Layout::Single, s.panes[0]));
+        assert!(State::decode("c=0,0 iters=10 split=50 | fractal=julia c=0,0").is_err());
+        assert!(State::decode("hello").is_err());
+    }
+    #[test]
+    fn clamps_out_of_range_values() {
+        let s = State::decode(
+            "c=0,0 iters=99999999 split=60000 | fractal=julia c=0,0 | fractal=julia c=0,0",
+        )
+        .unwrap();
+        assert_eq!(
+            (s.max_iters, s.layout),
+            (crate::ITERS_LIMIT, Layout::Split { left: 90 })
+        );
+        let s = State::decode("c=0,0 iters=10 split=1 | fractal=julia c=0,0 | fractal=julia c=0,0")
+            .unwrap();
+        assert_eq!((s.max_iters, s.layout), (10, Layout::Split { left: 10 }));
+        assert!(State::decode("c=0,0 iters=0 split=0 | fractal=julia c=0,0").is_err());
+        assert!(State::decode("c=0,0 iters=10 split=70000 | fractal=julia c=0,0").is_err());
+    }
+    #[test]
+    fn viewers_hang_up_on_overlong_lines() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let _ = stream.write_all(&vec![b'c'; 2 * MAX_LINE as usize]);
+            thread::sleep(Duration::from_secs(5));
+        });
+        let viewer = Viewer::connect(&addr).unwrap();
+        for _ in 0..200 {
+            match viewer.poll() {
+                Err(_) => return,
+                Ok(got) => assert_eq!(got, None),
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        panic!("viewer kept reading an overlong line");
+    }
+    #[test]
+    fn fills_in_addresses() {
+        assert_eq!(address("9000", true), "0.0.0.0:9000");
+        assert_eq!(address(":9000", false), "0.0.0.0:9000");
+        assert_eq!(address("pi.local", false), "pi.local:7878");
+        assert_eq!(address("10.0.0.2:80", false), "10.0.0.2:80");
+    }
+    #[test]
+    fn viewers_receive_the_newest_state() {
+        let host = Host::start("127.0.0.1:0").unwrap();
+        let viewer = Viewer::connect(&host.addr).unwrap();
+        let s = state();
+        let mut got = None;
+        for _ in 0..200 {
+            host.publish(&s);
+            if let Some(st) = viewer.poll().unwrap() {
+                got = Some(st);
+                break;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(got, Some(s));
+        assert_eq!(host.viewers(), 1);
+    }
+}
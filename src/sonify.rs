@@ -0,0 +1,139 @@
+// Sonification: a scanline sweeps down the focused pane and the escape counts at a few
+// points along it become a chord of sine voices. Written as raw PCM (signed 16-bit
+// little-endian, mono, RATE Hz) to a file or FIFO so any player can pick it up without
+// an audio dependency, e.g. `aplay -f S16_LE -r 22050 -c 1 FIFO`.
+use crate::layout::{Pane, Rect};
+use num_complex::Complex64;
+use std::f64::consts::TAU;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, SyncSender, TrySendError};
+
+const RATE: u32 = 22050;
+const VOICES: usize = 8;
+// Scanline speed in pane heights per second
+const SCAN_SPEED: f64 = 0.25;
+// Major pentatonic steps in semitones above A3
+const PENTATONIC: [u32; 5] = [0, 2, 4, 7, 9];
+
+#[derive(Clone, Copy, Default)]
+struct Voice {
+    phase: f64,
+    freq: f64,
+    amp: f64,
+}
+
+pub struct Sonifier {
+    tx: SyncSender<Vec<i16>>,
+    voices: [Voice; VOICES],
+    // Scanline position, 0..1 from the top of the pane
+    pub scan: f64,
+    // Fractional samples carried over between frames
+    carry: f64,
+}
+
+impl Sonifier {
+    // The writer thread opens `path` itself: opening a FIFO blocks until a reader shows up
+    pub fn start(path: PathBuf) -> Sonifier {
+        // About a second of audio in flight; beyond that chunks are dropped, not queued
+        let (tx, rx) = mpsc::sync_channel::<Vec<i16>>(60);
+        std::thread::spawn(move || {
+            let Ok(mut file) = std::fs::File::create(&path) else {
+                return;
+            };
+            for chunk in rx {
+                let bytes: Vec<u8> = chunk.iter().flat_map(|s| s.to_le_bytes()).collect();
+                if file.write_all(&bytes).is_err() {
+                    return;
+                }
+            }
+        });
+        Sonifier {
+            tx,
+            voices: [Voice::default(); VOICES],
+            scan: 0.0,
+            carry: 0.0,
+        }
+    }
+
+    // Produce `dt` seconds of sound for the scanline over `pane` (silence without one).
+    // Err once the output is gone (file could not be opened, reader closed).
+    pub fn feed(
+        &mut self,
+        target: Option<(&Pane, Complex64, Rect)>,
+        max_iters: usize,
+        dt: f64,
+    ) -> Result<(), String> {
+        self.scan = (self.scan + dt * SCAN_SPEED).fract();
+        let mut notes = [(0.0, 0.0); VOICES];
+        if let Some((pane, c, rect)) = target {
+            let (w, h) = (rect.w.max(1) as usize, rect.h.max(1) as usize);
+            let y = ((self.scan * h as f64) as usize).min(h - 1);
+            let c = pane.param(c);
+            for (i, note) in notes.iter_mut().enumerate() {
+                let x = (2 * i + 1) * w / (2 * VOICES);
+                let iters = pane.fractal.escape(pane.point(x, y, w, h), c, max_iters);
+                *note = pitch(iters, max_iters);
+            }
+        }
+        let total = dt * RATE as f64 + self.carry;
+        let n = total.floor() as usize;
+        self.carry = total - n as f64;
+        let samples = synth(&mut self.voices, &notes, n);
+        match self.tx.try_send(samples) {
+            Ok(()) | Err(TrySendError::Full(_)) => Ok(()),
+            Err(TrySendError::Disconnected(_)) => Err("sound output closed".to_string()),
+        }
+    }
+}
+
+// Frequency and loudness for an escape count: interior points are silent, fast escapes are
+// quiet and low, slow ones (near the boundary) loud and high on a pentatonic scale
+fn pitch(iters: usize, max_iters: usize) -> (f64, f64) {
+    if iters >= max_iters {
+        return (0.0, 0.0);
+    }
+    let step = iters % 15;
+    let semis = 12 * (step / 5) as u32 + PENTATONIC[step % 5];
+    let freq = 220.0 * 2f64.powf(semis as f64 / 12.0);
+    let amp = (iters as f64 / max_iters as f64).sqrt();
+    (freq, amp)
+}
+
+// `n` samples of the voices gliding toward `notes`; amplitude slews instead of jumping so
+// chord changes between frames don't click
+fn synth(voices: &mut [Voice], notes: &[(f64, f64)], n: usize) -> Vec<i16> {
+    let slew = 1.0 / (0.02 * RATE as f64);
+    let mut out = Vec::with_capacity(n);
+    for _ in 0..n {
+        let mut mix = 0.0;
+        for (v, &(freq, amp)) in voices.iter_mut().zip(notes) {
+            if amp > 0.0 {
+                v.freq = freq;
+            }
+            v.amp += (amp - v.amp).clamp(-slew, slew);
+            v.phase = (v.phase + v.freq / RATE as f64).fract();
+            mix += v.amp * (v.phase * TAU).sin();
+        }
+        let level = mix / voices.len() as f64 * 0.8;
+        out.push((level.clamp(-1.0, 1.0) * i16::MAX as f64) as i16);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn interior_is_silent() {
+        assert_eq!(pitch(100, 100), (0.0, 0.0));
+        assert_eq!(pitch(0, 100).0, 220.0);
+        assert_eq!(pitch(5, 100).0, 440.0); // next octave
+        let mut voices = [Voice::default(); VOICES];
+        let quiet = synth(&mut voices, &[(0.0, 0.0); VOICES], 100);
+        assert!(quiet.iter().all(|&s| s == 0));
+        let loud = synth(&mut voices, &[(440.0, 1.0); VOICES], 2000);
+        assert_eq!(loud.len(), 2000);
+        assert!(loud.iter().any(|&s| s > 10_000));
+    }
+}
@@ -0,0 +1,173 @@
+// Session statistics for the exit report: frame times, dropped frames, work done
+use crate::fractal;
+use std::fmt::Write;
+use std::time::Duration;
+
+// Frame times are counted in buckets of BUCKET_US microseconds, up to a second; anything
+// slower shares the last one. Memory stays the same however long the session runs.
+const BUCKET_US: u128 = 100;
+const BUCKETS: usize = 10_000;
+
+#[derive(Default)]
+pub struct Session {
+    // Presented frames per render-time bucket (empty before the first frame)
+    frame_counts: Vec<u64>,
+    frames: u64,
+    // Slowest frame, in milliseconds, reported for the last bucket
+    slowest_ms: f64,
+    // Frames that took longer than their budget
+    pub dropped: u64,
+    // Fractal cells drawn over the whole session
+    pub cells: u64,
+    pub deepest_zoom: f64,
+}
+
+impl Session {
+    pub fn record(&mut self, took: Duration, budget: Duration, cells: u64, zoom: f64) {
+        if self.frame_counts.is_empty() {
+            self.frame_counts = vec![0; BUCKETS];
+        }
+        let bucket = (took.as_micros() / BUCKET_US).min(BUCKETS as u128 - 1);
+        self.frame_counts[bucket as usize] += 1;
+        self.frames += 1;
+        self.slowest_ms = self.slowest_ms.max(took.as_secs_f64() * 1000.0);
+        if took > budget {
+            self.dropped += 1;
+        }
+        self.cells += cells;
+        self.deepest_zoom = self.deepest_zoom.max(zoom);
+    }
+
+    // Nearest-rank percentile of the frame times to the bucket (0.1 ms), 0 before the
+    // first frame
+    pub fn frame_percentile(&self, p: f64) -> f64 {
+        if self.frames == 0 {
+            return 0.0;
+        }
+        let rank = ((p * self.frames as f64).ceil() as u64).clamp(1, self.frames);
+        let mut seen = 0;
+        for (bucket, &count) in self.frame_counts.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                if bucket == BUCKETS - 1 {
+                    return self.slowest_ms;
+                }
+                return (bucket as u128 * BUCKET_US) as f64 / 1000.0;
+            }
+        }
+        self.slowest_ms
+    }
+}
+
+// Everything the exit summary prints, also written as JSON by --exit-report
+pub struct Report<'a> {
+    pub frames: u64,
+    pub seconds: f64,
+    pub session: &'a Session,
+    pub location: String,
+    // Complex units per cell of the final view
+    pub cell_size: f64,
+}
+
+impl Report<'_> {
+    fn avg_fps(&self) -> f64 {
+        if self.seconds > 0.0 {
+            self.frames as f64 / self.seconds
+        } else {
+            0.0
+        }
+    }
+
+    pub fn text(&self) -> String {
+        let s = self.session;
+        format!(
+            "Exited. Frames: {} Time: {:.2}s Avg FPS: {:.2}\n\
+             Frame time p50/p95/p99: {:.1}/{:.1}/{:.1} ms | Dropped: {} | Cells: {} | Deepest zoom: {} | Final cell size: {}\n\
+             Location: {}",
+            self.frames,
+            self.seconds,
+            self.avg_fps(),
+            s.frame_percentile(0.5),
+            s.frame_percentile(0.95),
+            s.frame_percentile(0.99),
+            s.dropped,
+            s.cells,
+            fractal::format_zoom(s.deepest_zoom),
+            fractal::sci(self.cell_size),
+            self.location
+        )
+    }
+
+    pub fn json(&self) -> String {
+        let s = self.session;
+        let mut out = String::from("{\n");
+        let _ = writeln!(out, "  \"frames\": {},", self.frames);
+        let _ = writeln!(out, "  \"seconds\": {:.3},", self.seconds);
+        let _ = writeln!(out, "  \"avg_fps\": {:.2},", self.avg_fps());
+        let _ = writeln!(
+            out,
+            "  \"frame_ms\": {{ \"p50\": {:.3}, \"p95\": {:.3}, \"p99\": {:.3} }},",
+            s.frame_percentile(0.5),
+            s.frame_percentile(0.95),
+            s.frame_percentile(0.99)
+        );
+        let _ = writeln!(out, "  \"dropped_frames\": {},", s.dropped);
+        let _ = writeln!(out, "  \"cells_rendered\": {},", s.cells);
+        let _ = writeln!(out, "  \"deepest_zoom\": {},", s.deepest_zoom);
+        let _ = writeln!(out, "  \"location\": \"{}\",", json_escape(&self.location));
+        let _ = writeln!(out, "  \"cell_size\": {:e}", self.cell_size);
+        out.push_str("}\n");
+        out
+    }
+}
+
+fn json_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for ch in s.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn percentiles_and_drops() {
+        let mut s = Session::default();
+        let budget = Duration::from_millis(16);
+        for ms in 1..=100 {
+            s.record(Duration::from_millis(ms), budget, 10, ms as f64 / 10.0);
+        }
+        assert_eq!(s.frame_percentile(0.5), 50.0);
+        assert_eq!(s.frame_percentile(0.99), 99.0);
+        assert_eq!(s.dropped, 84);
+        assert_eq!(s.cells, 1000);
+        assert_eq!(s.deepest_zoom, 10.0);
+        assert_eq!(Session::default().frame_percentile(0.5), 0.0);
+        // Past the last bucket the slowest frame is reported
+        s.record(Duration::from_secs(3), budget, 0, 0.0);
+        s.record(Duration::from_millis(2500), budget, 0, 0.0);
+        assert_eq!(s.frame_percentile(1.0), 3000.0);
+    }
+    #[test]
+    fn json_is_escaped() {
+        let session = Session::default();
+        let r = Report {
+            frames: 0,
+            seconds: 0.0,
+            session: &session,
+            location: "a\"b\\".to_string(),
+            cell_size: 2.5e-9,
+        };
+        assert!(r.json().contains(r#""location": "a\"b\\","#));
+        assert!(r.json().contains(r#""cell_size": 2.5e-9"#));
+    }
+}
@@ -0,0 +1,104 @@
+// Single-image random-character stereogram of the iteration field, for cross-eyed viewing.
+// Every cell repeats the cell one separation to its left, and the separation grows with
+// how near the point is (the interior nearest, then the escape bands), so crossing the eyes
+// by one period makes the fractal stand out of the page.
+use crate::color::Palette;
+use crate::compositor::{Cell, Layer};
+use crate::layout::{Pane, Rect};
+use num_complex::Complex64;
+
+// Separation in cells of the far plane, and how much wider the nearest one is
+pub const PERIOD: usize = 12;
+const DEPTH: usize = 4;
+const GLYPHS: [char; 12] = ['#', '@', '%', '&', '*', '+', '=', 'o', 'x', 'O', '$', '~'];
+
+// Depth 0 (far) ..= DEPTH (near): the interior is nearest, the exterior rises in steps
+// with the escape count so the boundary reads as a slope up to a plateau
+pub fn depth(iters: usize, max_iters: usize) -> usize {
+    if iters >= max_iters {
+        return DEPTH;
+    }
+    let norm = (iters as f64 / max_iters as f64).sqrt();
+    ((norm * DEPTH as f64) as usize).min(DEPTH - 1)
+}
+
+// Glyph index of every cell of one row; the first period is random (stable per `seed`)
+pub fn row(depths: &[usize], seed: u64) -> Vec<usize> {
+    let mut out: Vec<usize> = Vec::with_capacity(depths.len());
+    for (x, &d) in depths.iter().enumerate() {
+        let sep = PERIOD + d;
+        let g = if x >= sep {
+            out[x - sep]
+        } else {
+            (hash(seed ^ x as u64) % GLYPHS.len() as u64) as usize
+        };
+        out.push(g);
+    }
+    out
+}
+
+// splitmix64 finalizer
+fn hash(mut z: u64) -> u64 {
+    z = z.wrapping_add(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+// Stereogram of `pane` over `rect`, with two guide marks one period apart on the top row:
+// cross the eyes until they merge into three and the middle one is sharp
+pub fn draw(layer: &mut Layer, rect: Rect, pane: &Pane, c: Complex64, max_iters: usize) {
+    let (w, h) = (rect.w as usize, rect.h as usize);
+    let c = pane.param(c);
+    let color = |g: usize| color_of(pane.palette, g);
+    for y in 0..h {
+        let depths: Vec<usize> = (0..w)
+            .map(|x| {
+                depth(
+                    pane.fractal.escape(pane.point(x, y, w, h), c, max_iters),
+                    max_iters,
+                )
+            })
+            .collect();
+        for (x, g) in row(&depths, hash(y as u64)).into_iter().enumerate() {
+            let cell = Cell::new(GLYPHS[g], Some(color(g)));
+            layer.put(rect.x + x as u16, rect.y + y as u16, cell);
+        }
+    }
+    if w > PERIOD {
+        let left = (w - PERIOD) / 2;
+        for x in [left, left + PERIOD] {
+            layer.put(rect.x + x as u16, rect.y, Cell::new('▼', Some(231)).bold());
+        }
+    }
+}
+
+// The same glyph always gets the same color, or the repeats wouldn't match
+fn color_of(palette: Palette, g: usize) -> u8 {
+    palette.color((g as f64 + 0.5) / GLYPHS.len() as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn cells_repeat_one_separation_left() {
+        let depths: Vec<usize> = (0..80)
+            .map(|x| if (30..50).contains(&x) { DEPTH } else { 0 })
+            .collect();
+        let r = row(&depths, 7);
+        for x in PERIOD + DEPTH..80 {
+            assert_eq!(r[x], r[x - PERIOD - depths[x]], "x={x}");
+        }
+        // Stable for the same seed, different for another
+        assert_eq!(row(&depths, 7), r);
+        assert_ne!(row(&depths, 8), r);
+    }
+    #[test]
+    fn interior_is_nearest() {
+        assert_eq!(depth(100, 100), DEPTH);
+        assert_eq!(depth(0, 100), 0);
+        assert!(depth(99, 100) < DEPTH);
+        assert!(depth(50, 100) > depth(5, 100));
+    }
+}
@@ -0,0 +1,184 @@
+// Ambient strip for tmux status lines and shell prompts: a few cells of the fractal as
+// lines of text, redrawn every few seconds. The Julia parameter circles its start once
+// every few minutes by the wall clock, so separate runs (one per prompt) carry on where the
+// last one left off. Besides plain ANSI, the escapes can be written the way tmux and the
+// bash and zsh prompts want them.
+use crate::layout::Pane;
+use num_complex::Complex64;
+use std::f64::consts::TAU;
+
+pub const DEFAULT_SIZE: (u16, u16) = (40, 1);
+// Solid shades, so a strip shows its colors even where the normal ramp leaves blanks
+pub const RAMP: &str = "▒▓█";
+// Seconds between frames
+pub const DEFAULT_REFRESH: f64 = 5.0;
+// One turn of c around its start, in seconds, and how far from it c goes
+const PERIOD: f64 = 600.0;
+const RADIUS: f64 = 0.06;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Format {
+    // SGR escapes as a terminal takes them
+    #[default]
+    Ansi,
+    // #[fg=colour196] style, for #(...) in status-left/status-right
+    Tmux,
+    // Escapes between \001 and \002, so readline doesn't count them as prompt width
+    Bash,
+    // Escapes inside %{ %}
+    Zsh,
+}
+
+impl Format {
+    pub fn parse(s: &str) -> Result<Format, String> {
+        match s.trim() {
+            "ansi" => Ok(Format::Ansi),
+            "tmux" => Ok(Format::Tmux),
+            "bash" => Ok(Format::Bash),
+            "zsh" => Ok(Format::Zsh),
+            _ => Err(format!(
+                "unknown strip format '{s}' (ansi, tmux, bash or zsh)"
+            )),
+        }
+    }
+}
+
+// Julia parameter `secs` seconds after the epoch, on a small circle around `base`
+pub fn param(base: Complex64, secs: f64) -> Complex64 {
+    let angle = (secs / PERIOD).fract() * TAU;
+    base + Complex64::from_polar(RADIUS, angle)
+}
+
+// `pane` zoomed in until its width, rather than its height, fills a `w`×`h` strip
+pub fn fit(pane: &Pane, (w, h): (u16, u16)) -> Pane {
+    let (w, h) = (w.max(1) as usize, h.max(1) as usize);
+    let mut pane = *pane;
+    // Square pixels, two per cell; a square frame is limited by its width
+    pane.view.zoom *= pane.view.pixel_size(w, 2 * h) / pane.view.pixel_size(w, w);
+    pane
+}
+
+// `ansi` with every SGR escape rewritten for `format`, and characters that format treats
+// specially escaped
+pub fn convert(ansi: &str, format: Format) -> String {
+    if format == Format::Ansi {
+        return ansi.to_string();
+    }
+    let mut out = String::with_capacity(ansi.len());
+    let mut rest = ansi;
+    while let Some(start) = rest.find('\x1b') {
+        push_text(&mut out, &rest[..start], format);
+        let seq = &rest[start..];
+        // Anything but "ESC [ params m" is passed through
+        let end = match seq.strip_prefix("\x1b[").and_then(|s| s.find('m')) {
+            Some(i) => i + 3,
+            None => {
+                out.push('\x1b');
+                rest = &seq[1..];
+                continue;
+            }
+        };
+        let escape = &seq[..end];
+        match format {
+            Format::Tmux => out += &tmux_style(&escape[2..end - 1]),
+            Format::Bash => out += &format!("\x01{escape}\x02"),
+            Format::Zsh => out += &format!("%{{{escape}%}}"),
+            Format::Ansi => out += escape,
+        }
+        rest = &seq[end..];
+    }
+    push_text(&mut out, rest, format);
+    out
+}
+
+fn push_text(out: &mut String, text: &str, format: Format) {
+    match format {
+        Format::Tmux => *out += &text.replace('#', "##"),
+        Format::Zsh => *out += &text.replace('%', "%%"),
+        _ => *out += text,
+    }
+}
+
+// "38;5;196" -> "#[fg=colour196]"
+fn tmux_style(params: &str) -> String {
+    let codes: Vec<u16> = params.split(';').map(|p| p.parse().unwrap_or(0)).collect();
+    let mut styles = Vec::new();
+    let layer = |code: u16| {
+        if code < 40 || (90..98).contains(&code) {
+            "fg"
+        } else {
+            "bg"
+        }
+    };
+    let mut i = 0;
+    while i < codes.len() {
+        match codes[i] {
+            0 => styles.push("default".to_string()),
+            1 => styles.push("bold".to_string()),
+            7 => styles.push("reverse".to_string()),
+            code @ (38 | 48) if codes.get(i + 1) == Some(&5) && i + 2 < codes.len() => {
+                styles.push(format!("{}=colour{}", layer(code), codes[i + 2]));
+                i += 2;
+            }
+            code @ (38 | 48) if codes.get(i + 1) == Some(&2) && i + 4 < codes.len() => {
+                let [r, g, b] = [codes[i + 2], codes[i + 3], codes[i + 4]];
+                styles.push(format!("{}=#{r:02x}{g:02x}{b:02x}", layer(code)));
+                i += 4;
+            }
+            code @ (30..38 | 40..48) => {
+                styles.push(format!("{}=colour{}", layer(code), code % 10));
+            }
+            code @ (90..98 | 100..108) => {
+                styles.push(format!("{}=colour{}", layer(code), code % 10 + 8));
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    if styles.is_empty() {
+        return String::new();
+    }
+    format!("#[{}]", styles.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn rewrites_escapes_for_each_format() {
+        let ansi = "\x1b[0m\x1b[38;5;196m#%\x1b[0m \x1b[91;1m.\x1b[38;2;255;128;0m:\x1b[0m";
+        assert_eq!(convert(ansi, Format::Ansi), ansi);
+        assert_eq!(
+            convert(ansi, Format::Tmux),
+            "#[default]#[fg=colour196]##%#[default] #[fg=colour9,bold].#[fg=#ff8000]:#[default]"
+        );
+        assert_eq!(
+            convert("\x1b[38;5;196m%\x1b[0m", Format::Zsh),
+            "%{\x1b[38;5;196m%}%%%{\x1b[0m%}"
+        );
+        assert_eq!(convert("a\x1b[0mb", Format::Bash), "a\x01\x1b[0m\x02b");
+        assert_eq!(Format::parse("tmux"), Ok(Format::Tmux));
+        assert!(Format::parse("fish").is_err());
+    }
+    #[test]
+    fn fills_the_width() {
+        use crate::fractal::Fractal;
+        let pane = fit(&Pane::new(Fractal::Julia), (40, 1));
+        let left = pane.pixel(0.0, 1.0, 40, 2);
+        let right = pane.pixel(40.0, 1.0, 40, 2);
+        assert!((left.re + 1.5).abs() < 1e-12 && (right.re - 1.5).abs() < 1e-12);
+        // Taller than wide: nothing to do
+        assert_eq!(
+            fit(&Pane::new(Fractal::Julia), (10, 20)),
+            Pane::new(Fractal::Julia)
+        );
+    }
+    #[test]
+    fn c_circles_its_start() {
+        let base = Complex64::new(-0.8, 0.156);
+        let a = param(base, 1000.0);
+        assert!(((a - base).norm() - RADIUS).abs() < 1e-12);
+        assert!((param(base, 1000.0 + PERIOD) - a).norm() < 1e-9);
+        assert!((param(base, 1005.0) - a).norm() > 1e-4);
+    }
+}
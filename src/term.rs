@@ -0,0 +1,84 @@
+// Terminal setup and teardown, shared by the RAII guard and the panic hook
+use crossterm::{
+    cursor,
+    event::{DisableBracketedPaste, DisableFocusChange, EnableBracketedPaste, EnableFocusChange},
+    execute,
+    style::Print,
+    terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// Whether raw mode etc. are currently applied, and whether we own the alternate screen
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+static ALT_SCREEN: AtomicBool = AtomicBool::new(false);
+
+// RAII terminal restore
+pub struct TermGuard;
+impl Drop for TermGuard {
+    fn drop(&mut self) {
+        restore();
+    }
+}
+
+// Enter raw mode (and the alternate screen unless drawing in place) and make sure a
+// panic anywhere afterwards restores the terminal before its message is printed
+pub fn enter(alt_screen: bool) -> io::Result<TermGuard> {
+    apply(alt_screen)?;
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        restore();
+        default_hook(info);
+    }));
+    Ok(TermGuard)
+}
+
+fn apply(alt_screen: bool) -> io::Result<()> {
+    if alt_screen {
+        execute!(io::stdout(), EnterAlternateScreen)?;
+    }
+    execute!(io::stdout(), cursor::Hide)?;
+    // Pasted text arrives as one event instead of a flood of key presses (where supported)
+    let _ = execute!(io::stdout(), EnableBracketedPaste);
+    // Focus reports, to stop drawing for a window in the background
+    let _ = execute!(io::stdout(), EnableFocusChange);
+    terminal::enable_raw_mode()?;
+    ALT_SCREEN.store(alt_screen, Ordering::SeqCst);
+    ACTIVE.store(true, Ordering::SeqCst);
+    Ok(())
+}
+
+// Ctrl+Z: raw mode swallows the key instead of the tty sending SIGTSTP, so hand the
+// terminal back, stop ourselves, and set everything up again once the shell resumes us
+#[cfg(unix)]
+pub fn suspend() -> io::Result<()> {
+    let alt_screen = ALT_SCREEN.load(Ordering::SeqCst);
+    restore();
+    // Blocks here until SIGCONT
+    // SAFETY: raise only sends a signal to this process; SIGTSTP has its default handler
+    unsafe {
+        libc::raise(libc::SIGTSTP);
+    }
+    apply(alt_screen)
+}
+
+// Columns the terminal actually advanced for `c` printed at (x, y), via a cursor
+// position report. None if the terminal doesn't answer.
+pub fn probe_width(c: char, x: u16, y: u16) -> Option<u16> {
+    execute!(io::stdout(), cursor::MoveTo(x, y), Print(c)).ok()?;
+    let (cx, _) = cursor::position().ok()?;
+    Some(cx.saturating_sub(x))
+}
+
+// Idempotent: the panic hook and the guard's drop during unwinding both call this
+pub fn restore() {
+    if !ACTIVE.swap(false, Ordering::SeqCst) {
+        return;
+    }
+    let _ = terminal::disable_raw_mode();
+    let mut o = io::stdout();
+    let _ = execute!(o, DisableBracketedPaste, DisableFocusChange, cursor::Show);
+    if ALT_SCREEN.load(Ordering::SeqCst) {
+        let _ = execute!(o, LeaveAlternateScreen);
+    }
+}
@@ -0,0 +1,157 @@
+// Retro texture over the finished frame: CRT scanlines with a slowly rolling bright band,
+// or film-grain noise, blended into every colored glyph. Modulated colors go out as 24-bit
+// and fall back to the nearest palette entry like any other truecolor cell.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pattern {
+    Scanlines,
+    Noise,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Blend {
+    // Darkens by the texture
+    #[default]
+    Multiply,
+    // Darkens dark colors and brightens light ones, keeping contrast
+    Overlay,
+}
+
+pub const DEFAULT_INTENSITY: f64 = 0.5;
+// Rows per second the scanline roll moves down, and its height
+const ROLL_SPEED: f64 = 6.0;
+const ROLL_ROWS: f64 = 3.0;
+// Noise frames per second
+const GRAIN_FPS: f64 = 24.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Texture {
+    pub pattern: Pattern,
+    pub blend: Blend,
+    // 0 leaves colors alone, 1 applies the full blend
+    pub intensity: f64,
+    // Seconds since it was turned on, for the animation
+    pub time: f64,
+}
+
+impl Texture {
+    pub fn new(pattern: Pattern) -> Self {
+        Texture {
+            pattern,
+            blend: Blend::default(),
+            intensity: DEFAULT_INTENSITY,
+            time: 0.0,
+        }
+    }
+
+    pub fn name(&self) -> String {
+        let pattern = match self.pattern {
+            Pattern::Scanlines => "scanlines",
+            Pattern::Noise => "noise",
+        };
+        let blend = match self.blend {
+            Blend::Multiply => "multiply",
+            Blend::Overlay => "overlay",
+        };
+        format!("{pattern}, {blend}, {:.0}%", self.intensity * 100.0)
+    }
+
+    // "scanlines overlay 0.7", words in any order; None for "off"
+    pub fn parse(s: &str) -> Result<Option<Texture>, String> {
+        let mut t = Texture::new(Pattern::Scanlines);
+        for word in s.split_whitespace() {
+            match word.to_lowercase().as_str() {
+                "off" | "none" => return Ok(None),
+                "scanlines" | "crt" => t.pattern = Pattern::Scanlines,
+                "noise" | "grain" => t.pattern = Pattern::Noise,
+                "multiply" => t.blend = Blend::Multiply,
+                "overlay" => t.blend = Blend::Overlay,
+                other => match other.trim_end_matches('%').parse::<f64>() {
+                    Ok(v) if (0.0..=100.0).contains(&v) => {
+                        t.intensity = if other.ends_with('%') || v > 1.0 {
+                            v / 100.0
+                        } else {
+                            v
+                        }
+                    }
+                    _ => {
+                        return Err(format!(
+                            "unknown texture setting '{word}' (scanlines, noise, multiply, overlay, 0-1 or off)"
+                        ));
+                    }
+                },
+            }
+        }
+        Ok(Some(t))
+    }
+
+    // Texture brightness 0..=1 at cell (x, y) of a screen `h` rows tall
+    fn value(&self, x: usize, y: usize, h: usize) -> f64 {
+        match self.pattern {
+            Pattern::Scanlines => {
+                let line = if y.is_multiple_of(2) { 1.0 } else { 0.6 };
+                // A brighter band rolling down the screen, as on an old tube
+                let period = h as f64 + ROLL_ROWS * 4.0;
+                let band = (self.time * ROLL_SPEED).rem_euclid(period) - ROLL_ROWS * 2.0;
+                let d = (y as f64 - band).abs() / ROLL_ROWS;
+                (line + 0.4 * (1.0 - d).max(0.0)).min(1.0)
+            }
+            Pattern::Noise => {
+                let frame = (self.time * GRAIN_FPS) as u64;
+                let mut z = (x as u64) << 40 ^ (y as u64) << 20 ^ frame;
+                z = (z ^ (z >> 33)).wrapping_mul(0xff51afd7ed558ccd);
+                z = (z ^ (z >> 33)).wrapping_mul(0xc4ceb9fe1a85ec53);
+                0.4 + 0.6 * ((z >> 11) as f64 / (1u64 << 53) as f64)
+            }
+        }
+    }
+
+    // `rgb` at cell (x, y) with the texture blended in
+    pub fn apply(&self, rgb: (u8, u8, u8), x: usize, y: usize, h: usize) -> (u8, u8, u8) {
+        let t = self.value(x, y, h);
+        let blend = |c: u8| {
+            let b = c as f64 / 255.0;
+            let mixed = match self.blend {
+                Blend::Multiply => b * t,
+                Blend::Overlay if b < 0.5 => 2.0 * b * t,
+                Blend::Overlay => 1.0 - 2.0 * (1.0 - b) * (1.0 - t),
+            };
+            ((b + (mixed - b) * self.intensity) * 255.0)
+                .round()
+                .clamp(0.0, 255.0) as u8
+        };
+        (blend(rgb.0), blend(rgb.1), blend(rgb.2))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn darkens_every_other_row() {
+        let mut t = Texture::new(Pattern::Scanlines);
+        t.intensity = 1.0;
+        // At the start the rolling band is still above the screen
+        assert_eq!(t.apply((200, 100, 0), 3, 0, 10), (200, 100, 0));
+        assert_eq!(t.apply((200, 100, 0), 3, 1, 10), (120, 60, 0));
+        t.intensity = 0.0;
+        assert_eq!(t.apply((200, 100, 0), 3, 1, 10), (200, 100, 0));
+    }
+    #[test]
+    fn overlay_keeps_white_and_black() {
+        let mut t = Texture::new(Pattern::Noise);
+        t.blend = Blend::Overlay;
+        t.intensity = 1.0;
+        assert_eq!(t.apply((255, 0, 255), 5, 7, 24), (255, 0, 255));
+    }
+    #[test]
+    fn parses_settings() {
+        let t = Texture::parse("noise overlay 70%").unwrap().unwrap();
+        assert_eq!((t.pattern, t.blend), (Pattern::Noise, Blend::Overlay));
+        assert!((t.intensity - 0.7).abs() < 1e-9);
+        assert_eq!(Texture::parse("0.25").unwrap().unwrap().intensity, 0.25);
+        assert_eq!(Texture::parse("off"), Ok(None));
+        assert!(Texture::parse("sepia").is_err());
+        assert!(Texture::parse("150").is_err());
+    }
+}
@@ -0,0 +1,106 @@
+// View-space transforms between the screen and the plane. The offset from the view's
+// center is measured in view units (the screen spans about ±1.5 × ±1), mapped, and scaled
+// back, so every warp looks the same at any zoom and depth.
+use crate::fractal::Viewport;
+use num_complex::Complex64;
+use std::f64::consts::PI;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Warp {
+    #[default]
+    None,
+    // Reflection in the unit circle: the outside comes in and infinity sits at the center
+    Inversion,
+    // (1 + u) / (1 - u): the unit circle opens into the vertical axis
+    Mobius,
+    // exp(πu): across is log radius, up is angle, so every zoom level shows side by side
+    LogPolar,
+}
+
+const ALL: [Warp; 4] = [Warp::None, Warp::Inversion, Warp::Mobius, Warp::LogPolar];
+
+impl Warp {
+    pub fn name(self) -> &'static str {
+        match self {
+            Warp::None => "none",
+            Warp::Inversion => "inversion",
+            Warp::Mobius => "mobius",
+            Warp::LogPolar => "log-polar",
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<Warp, String> {
+        // Case, dashes, spaces and the umlaut don't matter
+        let key = s
+            .to_lowercase()
+            .replace('ö', "o")
+            .replace(['-', '_', ' '], "");
+        ALL.into_iter()
+            .find(|w| w.name().replace('-', "") == key)
+            .ok_or_else(|| format!("unknown warp '{s}' (none, inversion, mobius, log-polar)"))
+    }
+
+    pub fn next(self) -> Warp {
+        ALL[(ALL.iter().position(|&w| w == self).unwrap_or(0) + 1) % ALL.len()]
+    }
+
+    // Plane point shown where the unwarped view would show `p`
+    pub fn apply(self, view: &Viewport, p: Complex64) -> Complex64 {
+        if self == Warp::None {
+            return p;
+        }
+        let u = (p - view.center) * view.zoom;
+        let one = Complex64::new(1.0, 0.0);
+        let v = match self {
+            Warp::None => u,
+            Warp::Inversion => one / u.conj(),
+            Warp::Mobius => (one + u) / (one - u),
+            Warp::LogPolar => (u * PI).exp(),
+        };
+        view.center + v / view.zoom
+    }
+
+    // Where the unwarped view would show plane point `p` that the warp puts on screen
+    // (the principal branch for log-polar)
+    pub fn invert(self, view: &Viewport, p: Complex64) -> Complex64 {
+        if self == Warp::None {
+            return p;
+        }
+        let v = (p - view.center) * view.zoom;
+        let one = Complex64::new(1.0, 0.0);
+        let u = match self {
+            Warp::None => v,
+            Warp::Inversion => one / v.conj(),
+            Warp::Mobius => (v - one) / (v + one),
+            Warp::LogPolar => v.ln() / PI,
+        };
+        view.center + u / view.zoom
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn inverts_each_warp() {
+        let view = Viewport {
+            center: Complex64::new(-0.7, 0.2),
+            zoom: 40.0,
+        };
+        let p = Complex64::new(-0.69, 0.215);
+        for w in ALL {
+            let back = w.invert(&view, w.apply(&view, p));
+            assert!((back - p).norm() < 1e-12, "{w:?}: {back}");
+        }
+        // The unit circle of the inversion stays put
+        let on = view.center + Complex64::new(0.0, 1.0) / view.zoom;
+        assert!((Warp::Inversion.apply(&view, on) - on).norm() < 1e-12);
+    }
+    #[test]
+    fn parses_and_cycles() {
+        assert_eq!(Warp::parse("Möbius"), Ok(Warp::Mobius));
+        assert_eq!(Warp::parse("logpolar"), Ok(Warp::LogPolar));
+        assert!(Warp::parse("twirl").is_err());
+        assert_eq!(Warp::LogPolar.next(), Warp::None);
+    }
+}
@@ -0,0 +1,142 @@
+// Terminal column width of single characters (a small subset of UAX #11, no dependency).
+// Only what matters for user-supplied ramps: controls, combining marks, wide CJK/emoji
+// and the East Asian "ambiguous" symbols that CJK-configured terminals draw two cells wide.
+
+// Zero-width: combining marks, joiners and variation selectors
+const ZERO: &[(u32, u32)] = &[
+    (0x0300, 0x036F),
+    (0x0483, 0x0489),
+    (0x0591, 0x05BD),
+    (0x0610, 0x061A),
+    (0x064B, 0x065F),
+    (0x1AB0, 0x1AFF),
+    (0x1DC0, 0x1DFF),
+    (0x200B, 0x200F),
+    (0x2060, 0x2064),
+    (0x20D0, 0x20FF),
+    (0xFE00, 0xFE0F),
+    (0xFE20, 0xFE2F),
+    (0xE0100, 0xE01EF),
+];
+
+// East Asian Wide and Fullwidth
+const WIDE: &[(u32, u32)] = &[
+    (0x1100, 0x115F),
+    (0x231A, 0x231B),
+    (0x2329, 0x232A),
+    (0x23E9, 0x23EC),
+    (0x25FD, 0x25FE),
+    (0x2614, 0x2615),
+    (0x2648, 0x2653),
+    (0x26AA, 0x26AB),
+    (0x26BD, 0x26BE),
+    (0x26F5, 0x26F5),
+    (0x26FA, 0x26FA),
+    (0x2705, 0x2705),
+    (0x2728, 0x2728),
+    (0x274C, 0x274C),
+    (0x2B1B, 0x2B1C),
+    (0x2B50, 0x2B50),
+    (0x2E80, 0x303E),
+    (0x3041, 0x33FF),
+    (0x3400, 0x4DBF),
+    (0x4E00, 0x9FFF),
+    (0xA000, 0xA4CF),
+    (0xAC00, 0xD7A3),
+    (0xF900, 0xFAFF),
+    (0xFE30, 0xFE4F),
+    (0xFF00, 0xFF60),
+    (0xFFE0, 0xFFE6),
+    (0x1F300, 0x1F64F),
+    (0x1F680, 0x1F6FF),
+    (0x1F7E0, 0x1F7EB),
+    (0x1F900, 0x1F9FF),
+    (0x1FA70, 0x1FAFF),
+    (0x20000, 0x3FFFD),
+];
+
+// East Asian Ambiguous symbols likely to show up in ramps and chrome
+const AMBIGUOUS: &[(u32, u32)] = &[
+    (0x00A1, 0x00A1),
+    (0x00A4, 0x00A4),
+    (0x00A7, 0x00A8),
+    (0x00B0, 0x00B4),
+    (0x00B6, 0x00BA),
+    (0x00D7, 0x00D7),
+    (0x00F7, 0x00F7),
+    (0x2010, 0x2027),
+    (0x2030, 0x203E),
+    (0x2190, 0x21FF),
+    (0x2200, 0x22FF),
+    (0x2460, 0x24FF),
+    (0x2500, 0x257F),
+    (0x2580, 0x259F),
+    (0x25A0, 0x25FC),
+    (0x2605, 0x2606),
+    (0x2660, 0x266F),
+];
+
+fn in_table(table: &[(u32, u32)], c: char) -> bool {
+    let c = c as u32;
+    table.iter().any(|&(lo, hi)| (lo..=hi).contains(&c))
+}
+
+// Columns `c` occupies, or None for control characters that have no width at all
+pub fn char_width(c: char) -> Option<usize> {
+    let u = c as u32;
+    if u < 0x20 || (0x7F..0xA0).contains(&u) {
+        None
+    } else if in_table(ZERO, c) {
+        Some(0)
+    } else if in_table(WIDE, c) {
+        Some(2)
+    } else {
+        Some(1)
+    }
+}
+
+// One column in most terminals, two where the locale asks for East Asian widths
+pub fn is_ambiguous(c: char) -> bool {
+    in_table(AMBIGUOUS, c)
+}
+
+// Terminals configured for Chinese, Japanese or Korean are the ones that draw
+// ambiguous characters wide; only then is it worth asking the terminal
+pub fn cjk_locale(env: impl Fn(&str) -> Option<String>) -> bool {
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .find_map(|k| env(k).filter(|v| !v.is_empty()));
+    locale.is_some_and(|l| ["zh", "ja", "ko"].iter().any(|p| l.starts_with(p)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn classifies_widths() {
+        assert_eq!(char_width('#'), Some(1));
+        assert_eq!(char_width('\t'), None);
+        assert_eq!(char_width('\u{0301}'), Some(0)); // combining acute
+        assert_eq!(char_width('漢'), Some(2));
+        assert_eq!(char_width('🌀'), Some(2));
+        assert_eq!(char_width('█'), Some(1));
+        assert!(is_ambiguous('█') && is_ambiguous('·') && !is_ambiguous('@'));
+    }
+    #[test]
+    fn detects_cjk_locale() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |k: &str| {
+                vars.iter()
+                    .find(|(n, _)| *n == k)
+                    .map(|(_, v)| v.to_string())
+            }
+        };
+        assert!(cjk_locale(env(&[("LANG", "ja_JP.UTF-8")])));
+        assert!(!cjk_locale(env(&[
+            ("LC_ALL", "en_US.UTF-8"),
+            ("LANG", "zh_CN")
+        ])));
+        assert!(cjk_locale(env(&[("LC_ALL", ""), ("LANG", "ko_KR.UTF-8")])));
+        assert!(!cjk_locale(env(&[])));
+    }
+}